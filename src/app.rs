@@ -1,19 +1,37 @@
 use crate::{
-    command::CommandRunner,
-    evt, fs, languages,
+    command::{CommandResult, CommandRunner},
+    config::Field,
+    editor, evt, fs,
+    languages::{self, programming, spoken},
+    models::{
+        lesson,
+        tools::{self, Tool},
+    },
+    support,
     ui::tui::{
         self,
         screens::{self, Screen, Screens},
-        Evt,
+        Action, Evt, Keymap, Theme,
     },
-    Error, Status,
+    Config, Error, Status,
+};
+use crossterm::{
+    event::{self, DisableMouseCapture, EnableMouseCapture, EventStream},
+    execute,
 };
-use crossterm::event::{self, EventStream, KeyCode};
 use futures::{future::FutureExt, StreamExt};
 use futures_timer::Delay;
-use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::Widget,
+};
+use semver::Version;
 use std::{
     collections::HashMap,
+    path::{Path, PathBuf},
     sync::{
         atomic::{AtomicBool, AtomicU8, Ordering},
         Arc, Mutex,
@@ -23,12 +41,19 @@ use std::{
 use tokio::{
     select,
     sync::mpsc::{Receiver, Sender},
+    task::JoinHandle,
 };
 use tokio_util::sync::CancellationToken;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 const MAX_LOG_LINES: usize = 10000;
 
+/// how often to try to lock the project again while another instance holds the lock
+const LOCK_RETRY: Duration = Duration::from_secs(5);
+
+/// a file, line and column to open in the editor and the event to send when the editor exits
+type Edit = (PathBuf, Option<usize>, Option<usize>, Option<Evt>);
+
 /// Tui implementation of the UI
 pub struct App {
     /// The receiver from the logger
@@ -43,77 +68,187 @@ pub struct App {
     screen: AtomicU8,
     /// the cancelation token
     token: CancellationToken,
+    /// the cancelation token and the task of the running solution check
+    check: Option<(CancellationToken, JoinHandle<()>)>,
     /// the receiver for UI events
     receiver: Receiver<screens::Event>,
     /// the sender for UI events
     sender: Sender<screens::Event>,
     /// command runner for external processes
     command_runner: CommandRunner,
+    /// the key bindings
+    keymap: Keymap,
+    /// the colors to draw with
+    theme: Theme,
+    /// the file to open in the editor after the current event
+    edit: Option<Edit>,
+    /// the versions of the tools detected so far, `None` if a tool was not found
+    tools: HashMap<Tool, Option<Version>>,
+    /// the tools the current workshop needs that are missing, shown in a banner
+    read_only: Vec<String>,
+    /// the lock on the project's `.workshops` directory
+    lock: Option<fs::lock::Lock>,
+    /// the other instance that holds the lock, shown in a banner
+    owner: Option<String>,
 }
 
 impl Drop for App {
     fn drop(&mut self) {
         // cancel the token to stop the run loop
         self.token.cancel();
+        let _ = execute!(std::io::stdout(), DisableMouseCapture);
         ratatui::restore();
     }
 }
 
 impl App {
-    /// Create a new UI
-    pub fn new(from_logger: Receiver<String>) -> Result<Self, Error> {
+    /// Create a new UI with the loaded config
+    pub fn new(from_logger: Receiver<String>, config: Config) -> Result<Self, Error> {
         let (sender, receiver) = tokio::sync::mpsc::channel(1_000_000);
         let command_runner = CommandRunner::new(sender.clone());
+        let status = Status::load(config)?;
+        let keymap = status.keymap().clone();
+        let theme = Theme::from_env(status.theme());
+        for (key, actions) in keymap.conflicts() {
+            let actions: Vec<String> = actions.iter().map(|a| a.to_string()).collect();
+            warn!(
+                "The {} key is bound to {} in the keymap, it only does {}",
+                key,
+                actions.join(", "),
+                actions[0]
+            );
+        }
 
-        Ok(Self {
+        let mut app = Self {
             from_logger,
-            status: Arc::new(Mutex::new(Status::load()?)),
-            screens: Self::create_screens(),
+            status: Arc::new(Mutex::new(status)),
+            screens: Self::create_screens(&keymap),
             log: AtomicBool::new(false),
             screen: AtomicU8::new(Screens::Workshops as u8),
             token: CancellationToken::new(),
+            check: None,
+            edit: None,
+            tools: HashMap::new(),
+            read_only: Vec::new(),
+            lock: None,
+            owner: None,
             receiver,
             sender,
             command_runner,
-        })
+            keymap,
+            theme,
+        };
+        app.lock_project()?;
+        Ok(app)
+    }
+
+    /// Lock the project's `.workshops` directory, or stop saving progress if another instance
+    /// of the app holds the lock. While it does, this is called again every few seconds.
+    fn lock_project(&mut self) -> Result<(), Error> {
+        if self.lock.is_some() {
+            return Ok(());
+        }
+        let Some(dir) = fs::workshops::data_dir() else {
+            return Ok(());
+        };
+        match fs::lock::acquire(&dir)? {
+            fs::lock::Acquired::Locked(lock) => {
+                if let Some(owner) = self.owner.take() {
+                    info!("{} is done with this project, saving progress again", owner);
+                }
+                self.lock = Some(lock);
+                self.set_read_only(false)?;
+            }
+            fs::lock::Acquired::Busy(owner) => {
+                let owner = match owner {
+                    Some(owner) => format!("workshop ({owner})"),
+                    None => "another workshop".to_string(),
+                };
+                if self.owner.is_none() {
+                    warn!(
+                        "{} is using this project, progress will not be saved",
+                        owner
+                    );
+                }
+                self.owner = Some(owner);
+                self.set_read_only(true)?;
+            }
+        }
+        Ok(())
+    }
+
+    // tell the save paths whether another instance owns the project's state
+    fn set_read_only(&self, read_only: bool) -> Result<(), Error> {
+        self.status
+            .lock()
+            .map_err(|e| Error::StatusLock(e.to_string()))?
+            .set_read_only(read_only);
+        Ok(())
     }
 
     // create the screens
-    fn create_screens() -> HashMap<Screens, Box<dyn Screen>> {
-        let mut screens = HashMap::<Screens, Box<dyn Screen>>::with_capacity(8);
+    fn create_screens(keymap: &Keymap) -> HashMap<Screens, Box<dyn Screen>> {
+        let mut screens = HashMap::<Screens, Box<dyn Screen>>::with_capacity(10);
 
         // Welcome Screen
-        screens.insert(Screens::Welcome, Box::new(screens::Welcome::default()));
+        screens.insert(
+            Screens::Welcome,
+            Box::new(screens::Welcome::new(keymap.clone())),
+        );
 
         // Workshop Selection Screen
-        screens.insert(Screens::Workshops, Box::new(screens::Workshops::new()));
+        screens.insert(
+            Screens::Workshops,
+            Box::new(screens::Workshops::new(keymap.clone())),
+        );
 
         // Log Screen
-        screens.insert(Screens::Log, Box::new(screens::Log::new(MAX_LOG_LINES)));
+        screens.insert(
+            Screens::Log,
+            Box::new(screens::Log::new(MAX_LOG_LINES, keymap.clone())),
+        );
 
         // License Screen
-        screens.insert(Screens::License, Box::new(screens::License::default()));
+        screens.insert(
+            Screens::License,
+            Box::new(screens::License::new(keymap.clone())),
+        );
 
         // Spoken Language Selection Screen
-        screens.insert(Screens::Spoken, Box::new(screens::Spoken::default()));
+        screens.insert(
+            Screens::Spoken,
+            Box::new(screens::Spoken::new(keymap.clone())),
+        );
 
         // Programming Language Selection Screen
         screens.insert(
             Screens::Programming,
-            Box::new(screens::Programming::default()),
+            Box::new(screens::Programming::new(keymap.clone())),
         );
 
         // Set Default Confirmation Screen
         screens.insert(
             Screens::SetDefault,
-            Box::new(screens::SetDefault::default()),
+            Box::new(screens::SetDefault::new(keymap.clone())),
         );
 
         // Lessons Screen
-        screens.insert(Screens::Lessons, Box::new(screens::Lessons::new()));
+        screens.insert(
+            Screens::Lessons,
+            Box::new(screens::Lessons::new(keymap.clone())),
+        );
 
         // Lesson Screen
-        screens.insert(Screens::Lesson, Box::new(screens::Lesson::default()));
+        screens.insert(
+            Screens::Lesson,
+            Box::new(screens::Lesson::new(keymap.clone())),
+        );
+
+        // Settings Screen
+        screens.insert(
+            Screens::Settings,
+            Box::new(screens::Settings::new(keymap.clone())),
+        );
 
         screens
     }
@@ -123,100 +258,213 @@ impl App {
         &self.command_runner
     }
 
-    /// Setup python
-    async fn detect_python(&mut self) -> Result<(), Error> {
-        // try to get the python executable and minimum version from the status
-        let (py_exe, py_min_ver) = {
+    /// Find a tool and get its version. Tools are only detected the first time an action needs
+    /// them, so a missing tool does not stop the app from starting. A cached executable that no
+    /// longer runs or is older than the minimum version is replaced by detecting it again, and a
+    /// missing tool is looked for again the next time it is needed. The detected executable is
+    /// only kept for this project, the config is left for the learner to change.
+    async fn detect(&mut self, tool: Tool) -> Result<Option<Version>, Error> {
+        if let Some(version) = self.tools.get(&tool) {
+            return Ok(version.clone());
+        }
+
+        // try to get the executable and minimum version from the status
+        let (executable, min_version) = {
             let status = self
                 .status
                 .lock()
                 .map_err(|e| Error::StatusLock(e.to_string()))?;
             (
-                status.python_executable().map(String::from),
-                status.python_minimum_version().to_string(),
+                status.executable(tool).map(String::from),
+                status.minimum_version(tool).to_string(),
             )
         };
-
-        // if we don't have the path, try to find it
-        if py_exe.is_none() {
-            let python_executable = fs::application::find_python_executable(py_min_ver).await?;
-            debug!("Setting Python executable: {}", python_executable);
+        let minimum = Version::parse(&min_version).ok();
+
+        // check that the cached executable still works
+        let mut version = None;
+        if let Some(executable) = &executable {
+            match fs::application::probe_executable(tool, executable)
+                .await
+                .version
             {
+                Ok(found) if minimum.as_ref().is_none_or(|minimum| found >= *minimum) => {
+                    version = Some(found)
+                }
+                Ok(found) => warn!(
+                    "{} at {} is version {}, older than {}, detecting it again",
+                    tool, executable, found, min_version
+                ),
+                Err(reason) => warn!(
+                    "{} at {} does not work ({}), detecting it again",
+                    tool, executable, reason
+                ),
+            }
+        }
+
+        // otherwise try to find it, forgetting the stale path if nothing is found
+        if version.is_none() {
+            let found = fs::application::find_executable(tool, &min_version)
+                .await
+                .ok();
+            if let Some(found) = &found {
+                version = fs::application::probe_executable(tool, found)
+                    .await
+                    .version
+                    .ok();
+            }
+            if found != executable {
+                debug!("Setting {} executable: {:?}", tool, found);
                 let mut status = self
                     .status
                     .lock()
                     .map_err(|e| Error::StatusLock(e.to_string()))?;
-                status.set_python_executable(&python_executable, true);
+                status.set_executable(tool, found.as_deref(), false);
             }
         }
 
-        Ok(())
+        match &version {
+            Some(version) => {
+                self.tools.insert(tool, Some(version.clone()));
+            }
+            None => warn!(
+                "{} was not found, the actions that need it are disabled",
+                tool
+            ),
+        }
+        Ok(version)
+    }
+
+    /// Describe the requirements that the detected tools do not meet
+    async fn unmet(&mut self, requirements: &[tools::Requirement]) -> Result<Vec<String>, Error> {
+        let mut unmet = Vec::new();
+        for requirement in requirements {
+            let version = self.detect(requirement.tool).await?;
+            if !requirement.is_met(version.as_ref()) {
+                unmet.push(requirement.unmet(version.as_ref()));
+            }
+        }
+        Ok(unmet)
     }
 
-    // Setup docker compose
-    async fn detect_docker_compose(&mut self) -> Result<(), Error> {
-        // try to get the docker executable from the status
-        let (docker_compose_exe, docker_compose_min_ver) = {
+    /// Detect every tool for the settings
+    async fn detected(&mut self) -> Result<Vec<tools::Detected>, Error> {
+        let mut detected = Vec::new();
+        for tool in Tool::ALL {
+            let version = self.detect(tool).await?;
             let status = self
                 .status
                 .lock()
                 .map_err(|e| Error::StatusLock(e.to_string()))?;
-            (
-                status.docker_compose_executable().map(String::from),
-                status.docker_compose_minimum_version().to_string(),
-            )
-        };
-
-        // if we don't have the path, try to find it
-        if docker_compose_exe.is_none() {
-            let docker_compose_executable =
-                fs::application::find_docker_compose_executable(docker_compose_min_ver).await?;
-            debug!(
-                "Setting docker compose executable: {}",
-                docker_compose_executable
-            );
-            {
-                let mut status = self
-                    .status
-                    .lock()
-                    .map_err(|e| Error::StatusLock(e.to_string()))?;
-                status.set_docker_compose_executable(&docker_compose_executable, true);
-            }
+            detected.push(tools::Detected {
+                tool,
+                executable: status.executable(tool).map(String::from),
+                version,
+                minimum: status.minimum_version(tool).to_string(),
+            });
         }
+        Ok(detected)
+    }
 
-        Ok(())
+    /// Get the config fields and their values for the settings
+    fn settings(&self) -> Result<Vec<(Field, String)>, Error> {
+        let status = self
+            .status
+            .lock()
+            .map_err(|e| Error::StatusLock(e.to_string()))?;
+        Ok(Field::ALL
+            .into_iter()
+            .map(|field| (field, status.config().get(field)))
+            .collect())
     }
 
-    /// Setup git
-    async fn detect_git(&mut self) -> Result<(), Error> {
-        // try to get the git executable and minimum version from the status
-        let (git_exe, git_min_ver) = {
+    /// Use an executable for a tool if it works and is new enough, and describe the outcome
+    async fn set_executable(
+        &mut self,
+        field: Field,
+        tool: Tool,
+        executable: &str,
+    ) -> Result<String, Error> {
+        let (checked, minimum) = {
             let status = self
                 .status
                 .lock()
                 .map_err(|e| Error::StatusLock(e.to_string()))?;
+            let mut config = status.config().clone();
             (
-                status.git_executable().map(String::from),
-                status.git_minimum_version().to_string(),
+                config.set(field, executable),
+                status.minimum_version(tool).to_string(),
             )
         };
-
-        // if we don't have the path, try to find it
-        if git_exe.is_none() {
-            let git_executable = fs::application::find_git_executable(git_min_ver).await?;
-            debug!("Setting Git executable: {}", git_executable);
+        if let Err(e) = checked {
+            return Ok(e.to_string());
+        }
+        match fs::application::probe_executable(tool, executable)
+            .await
+            .version
+        {
+            Ok(version)
+                if Version::parse(&minimum)
+                    .ok()
+                    .is_none_or(|minimum| version >= minimum) =>
             {
-                let mut status = self
-                    .status
-                    .lock()
-                    .map_err(|e| Error::StatusLock(e.to_string()))?;
-                status.set_git_executable(&git_executable, true);
+                {
+                    let mut status = self
+                        .status
+                        .lock()
+                        .map_err(|e| Error::StatusLock(e.to_string()))?;
+                    status.set_config(field, Some(executable))?;
+                    if let Err(e) = status.config().save() {
+                        return Ok(format!("Cannot save the settings: {e}"));
+                    }
+                }
+                self.tools.insert(tool, Some(version.clone()));
+                Ok(format!("Using {executable} for {tool} {version}"))
             }
+            Ok(version) => Ok(format!(
+                "{executable} is {tool} {version}, older than {minimum}"
+            )),
+            Err(reason) => Ok(format!("{executable} does not work: {reason}")),
         }
+    }
 
+    /// Update the banner with the tools that the current workshop needs and are missing
+    async fn refresh_read_only(&mut self) -> Result<(), Error> {
+        let workshop = {
+            let status = self
+                .status
+                .lock()
+                .map_err(|e| Error::StatusLock(e.to_string()))?;
+            status.workshop().map(String::from)
+        };
+        self.read_only = match workshop {
+            Some(workshop) => {
+                let required = self.required_tools(&workshop).await?;
+                self.unmet(&required).await?
+            }
+            None => Vec::new(),
+        };
         Ok(())
     }
 
+    /// Get the tools a workshop needs from its metadata
+    async fn required_tools(&self, workshop: &str) -> Result<Vec<tools::Requirement>, Error> {
+        let spoken_language = {
+            let status = self
+                .status
+                .lock()
+                .map_err(|e| Error::StatusLock(e.to_string()))?;
+            status.spoken_language()
+        };
+        match fs::workshops::load(workshop) {
+            Some(workshop_data) => Ok(workshop_data
+                .get_metadata(spoken_language)
+                .await?
+                .required_tools()),
+            None => Ok(tools::defaults()),
+        }
+    }
+
     /// Queue up the initial events for the application
     async fn initial_events(&mut self, install: Option<String>) -> Result<(), Error> {
         // initialize the state
@@ -269,28 +517,31 @@ impl App {
         // initialize the terminal
         let mut terminal = ratatui::init();
 
+        // enable mouse capture for wheel scrolling and clicking
+        execute!(std::io::stdout(), EnableMouseCapture)?;
+
         // initialize the input event stream
         let mut reader = EventStream::new();
 
         // the timeout
         let mut timeout = Delay::new(Duration::from_secs(600));
 
-        // try to get the python executable and minimum version from the status
-        if self.detect_python().await.is_err() {
-            error!("Failed to detect Python executable or version");
-            return Err(fs::Error::NoPythonExecutable.into());
-        }
-
-        // try to get the docker compose executable and minimum version from the status
-        if self.detect_docker_compose().await.is_err() {
-            error!("Failed to detect Docker Compose executable or version");
-            return Err(fs::Error::NoDockerComposeExecutable.into());
-        }
+        // the timer for taking over the lock from another instance
+        let mut retry_lock = tokio::time::interval(LOCK_RETRY);
 
-        // try to get the git executable and minimum version from the status
-        if self.detect_git().await.is_err() {
-            error!("Failed to detect Git executable or version");
-            return Err(fs::Error::NoGitExecutable.into());
+        // check that the cached executables still work, the others are detected when needed
+        let cached: Vec<Tool> = {
+            let status = self
+                .status
+                .lock()
+                .map_err(|e| Error::StatusLock(e.to_string()))?;
+            Tool::ALL
+                .into_iter()
+                .filter(|tool| status.executable(*tool).is_some())
+                .collect()
+        };
+        for tool in cached {
+            self.detect(tool).await?;
         }
 
         // queue up the initial events
@@ -331,6 +582,9 @@ impl App {
                 // check the timeout
                 _ = &mut timeout => {}
 
+                // try to lock the project again
+                _ = retry_lock.tick(), if self.owner.is_some() => self.lock_project()?,
+
                 // check if we should quit
                 _ = self.token.cancelled() => {
                     debug!("cancelation token triggered, quitting...");
@@ -338,6 +592,20 @@ impl App {
                 }
             }
 
+            // run the editor in place of the terminal UI
+            if let Some((path, line, column, next)) = self.edit.take() {
+                // stop reading input so that every key press goes to the editor
+                drop(reader);
+                self.open_editor(&path, line, column).await?;
+                terminal = ratatui::init();
+                execute!(std::io::stdout(), EnableMouseCapture)?;
+                terminal.clear()?;
+                reader = EventStream::new();
+                if let Some(next) = next {
+                    self.sender.send(next.into()).await?;
+                }
+            }
+
             if self.log.load(Ordering::SeqCst) {
                 // if the log is visible, set a timer to redraw the UI @ 60 FPS
                 timeout = Delay::new(Duration::from_secs_f64(1.0 / 60.0));
@@ -355,17 +623,133 @@ impl App {
         // clean up the terminal
         info!("Quitting...");
         {
-            let status = self
+            let mut status = self
                 .status
                 .lock()
                 .map_err(|e| Error::StatusLock(e.to_string()))?;
             status.save()?;
         }
+        execute!(std::io::stdout(), DisableMouseCapture)?;
         ratatui::restore();
 
         Ok(())
     }
 
+    /// get the project folder that holds the `.workshops` directory
+    fn project_root() -> Result<PathBuf, Error> {
+        fs::workshops::data_dir()
+            .as_deref()
+            .and_then(Path::parent)
+            .map(Path::to_path_buf)
+            .ok_or_else(|| fs::Error::WorkshopDataDirNotFound.into())
+    }
+
+    /// explain why a workshop cannot be started yet, if the workshops it requires are not done. A
+    /// workshop whose requirements cannot be read is not locked.
+    async fn locked_by(
+        &self,
+        workshop: &str,
+        spoken_language: Option<spoken::Code>,
+        programming_language: Option<programming::Code>,
+    ) -> Option<String> {
+        let workshops = match fs::application::all_workshops() {
+            Ok(workshops) => workshops,
+            Err(e) => {
+                warn!("Failed to read the workshops to check {}: {}", workshop, e);
+                return None;
+            }
+        };
+        let workshop_data = workshops.get(workshop)?;
+        let metadata = match workshop_data.get_metadata(spoken_language).await {
+            Ok(metadata) => metadata,
+            Err(e) => {
+                warn!("Failed to read the requirements of {}: {}", workshop, e);
+                return None;
+            }
+        };
+        let unmet =
+            fs::workshops::unmet_requirements(&metadata, spoken_language, programming_language)
+                .await;
+        lesson::complete_first(&unmet)
+    }
+
+    /// tell the user that an action needs tools that are missing, then send the next event
+    async fn blocked(
+        to_ui: &Sender<screens::Event>,
+        action: &str,
+        unmet: &[String],
+        next: Option<Evt>,
+    ) -> Result<(), Error> {
+        info!("Cannot {} without {}", action, unmet.join(", "));
+        let missing = evt!(
+            Screens::Log,
+            tui::Event::Log(format!("x Cannot {action} without {}", unmet.join(", ")))
+        );
+        let completed = evt!(
+            Screens::Log,
+            tui::Event::CommandCompleted(
+                CommandResult {
+                    success: false,
+                    exit_code: 1,
+                    last_line: "Run `workshop doctor` to see how to install it".to_string(),
+                    cancelled: false,
+                },
+                None,
+                next,
+            )
+        );
+        to_ui
+            .send(evt!(None, tui::Event::ShowLog(None)).into())
+            .await?;
+        to_ui.send(missing.into()).await?;
+        to_ui.send(completed.into()).await?;
+        Ok(())
+    }
+
+    /// Report a solution check that could not start as a failed command, so that the screens
+    /// waiting for the check to complete stop waiting
+    async fn check_not_started(
+        to_ui: &Sender<screens::Event>,
+        reason: String,
+        failed: Option<Evt>,
+    ) -> Result<(), Error> {
+        error!("Cannot check solution: {}", reason);
+        let completed = evt!(
+            Screens::Log,
+            tui::Event::CommandCompleted(
+                CommandResult {
+                    success: false,
+                    exit_code: 1,
+                    last_line: reason,
+                    cancelled: false,
+                },
+                None,
+                failed,
+            )
+        );
+        to_ui.send(completed.into()).await?;
+        Ok(())
+    }
+
+    /// run the editor on the restored terminal and wait for it to exit
+    async fn open_editor(
+        &self,
+        path: &Path,
+        line: Option<usize>,
+        column: Option<usize>,
+    ) -> Result<(), Error> {
+        execute!(std::io::stdout(), DisableMouseCapture)?;
+        ratatui::restore();
+
+        info!("Opening editor: {}", path.display());
+        match editor::command(path, line, column).status().await {
+            Ok(status) if !status.success() => error!("Editor exited with: {status}"),
+            Ok(_) => {}
+            Err(e) => error!("Failed to run editor: {e}"),
+        }
+        Ok(())
+    }
+
     /// handle UI events
     pub async fn handle_ui_event(
         &mut self,
@@ -375,6 +759,19 @@ impl App {
         status: Arc<Mutex<Status>>,
     ) -> Result<(), Error> {
         if let Some(dest_screen) = screen.clone() {
+            // copy the log events to the lesson screen so it can show the output of a check
+            if dest_screen == Screens::Log {
+                if let Some(lesson) = self.screens.get_mut(&Screens::Lesson) {
+                    lesson
+                        .handle_event(
+                            (Some(Screens::Lesson), event.clone()).into(),
+                            to_ui.clone(),
+                            status.clone(),
+                        )
+                        .await?;
+                }
+            }
+
             // pass the event to the target screen
             if let Some(screen_state) = self.screens.get_mut(&dest_screen) {
                 return screen_state
@@ -408,6 +805,9 @@ impl App {
                         to_ui.send(next.into()).await?;
                     }
                 }
+                tui::Event::OpenEditor(path, line, column, next) => {
+                    self.edit = Some((path, line, column, next));
+                }
                 tui::Event::Show(screen) => {
                     debug!("Show screen: {}", screen);
                     self.screen.store(screen.clone() as u8, Ordering::SeqCst);
@@ -617,6 +1017,30 @@ impl App {
                             );
 
                             to_ui.send(change_programming_language.into()).await?;
+                        } else if let Some(cause) = self
+                            .locked_by(&workshop, spoken_language, programming_language)
+                            .await
+                        {
+                            // the workshops it requires in the series are not done yet
+                            info!("Workshop {} is locked: {}", workshop, cause);
+                            let load_workshops =
+                                evt!(Screens::Workshops, tui::Event::LoadWorkshops);
+                            let hide_log = evt!(None, tui::Event::HideLog(Some(load_workshops)));
+                            let locked = evt!(
+                                Screens::Log,
+                                tui::Event::CommandCompleted(
+                                    CommandResult {
+                                        success: false,
+                                        exit_code: 1,
+                                        last_line: cause,
+                                        cancelled: false,
+                                    },
+                                    None,
+                                    Some(hide_log),
+                                )
+                            );
+                            let show_log = evt!(None, tui::Event::ShowLog(Some(locked)));
+                            to_ui.send(show_log.into()).await?;
                         } else {
                             // we have both languages selected, so we can proceed with setting the
                             // workshop, initializing the local workshop data and loading the lessons
@@ -629,6 +1053,7 @@ impl App {
                                 status.set_workshop(Some(workshop.clone()));
                                 fs::workshops::init_data_dir(&workshop)?;
                             }
+                            self.lock_project()?;
                             let load_lessons = evt!(Screens::Lessons, tui::Event::LoadLessons);
                             let hide_log = evt!(None, tui::Event::HideLog(Some(load_lessons)));
                             let check_deps = evt!(
@@ -639,6 +1064,7 @@ impl App {
                         }
                     } else {
                         debug!("Clearing workshop");
+                        self.read_only.clear();
                         {
                             let mut status = self
                                 .status
@@ -678,6 +1104,31 @@ impl App {
                     }
                 }
                 tui::Event::CheckDeps(workshop, success, failed) => {
+                    // the lessons are read-only while the tools the workshop needs are missing
+                    let required = self.required_tools(&workshop).await?;
+                    self.read_only = self.unmet(&required).await?;
+
+                    // the dependency check is a python script, skip it if python is missing
+                    let python = required
+                        .into_iter()
+                        .find(|requirement| requirement.tool == Tool::Python)
+                        .unwrap_or_else(|| tools::Requirement::new(Tool::Python));
+                    let unmet = self.unmet(&[python]).await?;
+                    if !unmet.is_empty() {
+                        let skipped = evt!(
+                            Screens::Log,
+                            tui::Event::Log(format!(
+                                "^ Skipped the dependency check without {}",
+                                unmet.join(", ")
+                            ))
+                        );
+                        to_ui.send(skipped.into()).await?;
+                        if let Some(success) = success {
+                            to_ui.send(success.into()).await?;
+                        }
+                        return Ok(());
+                    }
+
                     // Run dependency check using workshop data (with fallback to defaults)
                     if let Some(workshop_data) = fs::workshops::load(&workshop) {
                         let (programming_language, spoken_language, python_executable) = {
@@ -772,8 +1223,37 @@ impl App {
                         }
                     }
                 }
-                tui::Event::CheckSolution(success, failed) => {
+                tui::Event::CheckSolution(show_log, success, failed) => {
                     debug!("Check solution");
+                    let workshop = {
+                        let status = status
+                            .lock()
+                            .map_err(|e| Error::StatusLock(e.to_string()))?;
+                        status.workshop().map(String::from)
+                    };
+
+                    // the check always runs python and docker compose
+                    let mut required = match &workshop {
+                        Some(workshop) => self.required_tools(workshop).await?,
+                        None => tools::defaults(),
+                    };
+                    for default in tools::defaults() {
+                        if !required.iter().any(|r| r.tool == default.tool) {
+                            required.push(default);
+                        }
+                    }
+                    let unmet = self.unmet(&required).await?;
+                    if !unmet.is_empty() {
+                        if show_log {
+                            Self::blocked(&to_ui, "check solutions", &unmet, failed).await?;
+                        } else {
+                            let reason =
+                                format!("Cannot check solutions without {}", unmet.join(", "));
+                            Self::check_not_started(&to_ui, reason, failed).await?;
+                        }
+                        return Ok(());
+                    }
+
                     // Get current status information
                     let (
                         spoken,
@@ -796,21 +1276,25 @@ impl App {
                         )
                     };
 
-                    let py_exe = python_executable.ok_or(fs::Error::NoPythonExecutable)?;
-                    let dc_exe =
-                        docker_compose_executable.ok_or(fs::Error::NoDockerComposeExecutable)?;
+                    let (py_exe, dc_exe) = match (python_executable, docker_compose_executable) {
+                        (Some(py_exe), Some(dc_exe)) => (py_exe, dc_exe),
+                        (None, _) => {
+                            let reason = fs::Error::NoPythonExecutable.to_string();
+                            return Self::check_not_started(&to_ui, reason, failed).await;
+                        }
+                        (_, None) => {
+                            let reason = fs::Error::NoDockerComposeExecutable.to_string();
+                            return Self::check_not_started(&to_ui, reason, failed).await;
+                        }
+                    };
 
                     // Check if we have required workshop and lesson
                     if let (Some(workshop), Some(lesson)) = (workshop, lesson) {
                         if let Some(workshop_data) = fs::workshops::load(&workshop) {
-                            let show_log = evt!(None, tui::Event::ShowLog(None));
-                            to_ui.send(show_log.into()).await?;
-
-                            let running = evt!(
-                                Screens::Log,
-                                tui::Event::Log(format!("r Running solution check: {lesson}"))
-                            );
-                            to_ui.send(running.into()).await?;
+                            if show_log {
+                                let show_log = evt!(None, tui::Event::ShowLog(None));
+                                to_ui.send(show_log.into()).await?;
+                            }
 
                             // Get lesson directory path using workshop model (handles defaults automatically)
                             match workshop_data.get_lesson_dir_path(&lesson, spoken, programming) {
@@ -820,66 +1304,225 @@ impl App {
                                         lesson_dir.display()
                                     );
 
+                                    // get the programming language and the optional host side
+                                    // pre-check for the lesson
+                                    let lessons =
+                                        workshop_data.get_lessons_data(spoken, programming).await;
+                                    let (code, pre_check) = match lessons
+                                        .as_ref()
+                                        .map(|l| l.get(&lesson))
+                                    {
+                                        Ok(Some(lesson_data)) => {
+                                            match lesson_data.get_metadata().await {
+                                                Ok(metadata) => (
+                                                    lesson_data.get_programming_language(),
+                                                    metadata.pre_check,
+                                                ),
+                                                Err(e) => {
+                                                    let reason = e.to_string();
+                                                    return Self::check_not_started(
+                                                        &to_ui, reason, failed,
+                                                    )
+                                                    .await;
+                                                }
+                                            }
+                                        }
+                                        Ok(None) => (programming.unwrap_or_default(), None),
+                                        Err(e) => {
+                                            let reason = e.to_string();
+                                            return Self::check_not_started(&to_ui, reason, failed)
+                                                .await;
+                                        }
+                                    };
+
+                                    // cancel the check that is already running, if any
+                                    let previous = self.check.take().map(|(token, task)| {
+                                        token.cancel();
+                                        task
+                                    });
+                                    let token = self.token.child_token();
+
                                     // Spawn async task to run solution check
                                     let command_runner = self.command_runner.clone();
-                                    let token = self.token.clone();
                                     let sender = to_ui.clone();
+                                    let check_token = token.clone();
 
-                                    tokio::spawn(async move {
-                                        match command_runner
-                                            .check_solution(&dc_exe, &py_exe, &lesson_dir, &token)
+                                    let task = tokio::spawn(async move {
+                                        // let the cancelled check take its containers down
+                                        // before this one starts
+                                        if let Some(previous) = previous {
+                                            let _ = previous.await;
+                                        }
+                                        let running = evt!(
+                                            Screens::Log,
+                                            tui::Event::Log(format!(
+                                                "r Running solution check: {lesson}"
+                                            ))
+                                        );
+                                        let _ = sender.send(running.into()).await;
+
+                                        let completed = match command_runner
+                                            .check_solution(
+                                                &dc_exe,
+                                                &py_exe,
+                                                &lesson_dir,
+                                                code,
+                                                pre_check.as_deref(),
+                                                &token,
+                                            )
                                             .await
                                         {
-                                            Ok(result) => {
-                                                let _ = sender
-                                                    .send(
-                                                        (
-                                                            Some(Screens::Log),
-                                                            tui::Event::CommandCompleted(
-                                                                result, success, failed,
-                                                            ),
-                                                        )
-                                                            .into(),
-                                                    )
-                                                    .await;
+                                            Ok(result) => tui::Event::CommandCompleted(
+                                                result, success, failed,
+                                            ),
+                                            Err(_) if token.is_cancelled() => {
+                                                // a newer check replaced this one
+                                                debug!("Solution check cancelled");
+                                                let result = CommandResult {
+                                                    success: false,
+                                                    exit_code: -1,
+                                                    last_line: "Check cancelled".to_string(),
+                                                    cancelled: true,
+                                                };
+                                                tui::Event::CommandCompleted(result, None, None)
                                             }
                                             Err(e) => {
-                                                let _ = sender
-                                                    .send(
-                                                        (
-                                                            Some(Screens::Log),
-                                                            tui::Event::Log(format!(
-                                                                "! check solution failed: {e}"
-                                                            )),
-                                                        )
-                                                            .into(),
-                                                    )
-                                                    .await;
+                                                let result = CommandResult {
+                                                    success: false,
+                                                    exit_code: -1,
+                                                    last_line: format!(
+                                                        "check solution failed: {e}"
+                                                    ),
+                                                    cancelled: false,
+                                                };
+                                                tui::Event::CommandCompleted(result, None, failed)
                                             }
-                                        }
+                                        };
+                                        let completed = evt!(Screens::Log, completed);
+                                        let _ = sender.send(completed.into()).await;
                                     });
+                                    self.check = Some((check_token, task));
                                 }
                                 Err(e) => {
-                                    error!("Failed to get lesson directory path: {}", e);
-                                    if let Some(failed) = failed {
-                                        let _ = to_ui.send(failed.into()).await;
-                                    }
+                                    let reason =
+                                        format!("Failed to get lesson directory path: {e}");
+                                    Self::check_not_started(&to_ui, reason, failed).await?;
                                 }
                             }
                         } else {
-                            error!("Failed to load workshop data for: {}", workshop);
-                            if let Some(failed) = failed {
-                                let _ = to_ui.send(failed.into()).await;
-                            }
+                            let reason = format!("Failed to load workshop data for: {workshop}");
+                            Self::check_not_started(&to_ui, reason, failed).await?;
                         }
                     } else {
-                        error!("Cannot check solution: missing workshop or lesson selection");
-                        if let Some(failed) = failed {
-                            let _ = to_ui.send(failed.into()).await;
+                        let reason = "missing workshop or lesson selection".to_string();
+                        Self::check_not_started(&to_ui, reason, failed).await?;
+                    }
+                }
+                tui::Event::LoadSettings => {
+                    let fields = self.settings()?;
+                    let detected = self.detected().await?;
+                    let show = evt!(
+                        Screens::Settings,
+                        tui::Event::ShowSettings(fields, detected, None)
+                    );
+                    to_ui.send(show.into()).await?;
+                }
+                tui::Event::SetConfig(field, value) => {
+                    let message = match (field.tool(), value) {
+                        (Some(tool), Some(executable)) if field.is_executable() => {
+                            self.set_executable(field, tool, &executable).await?
+                        }
+                        (Some(tool), None) if field.is_executable() => {
+                            // forget the executable and look for it again
+                            let saved = {
+                                let mut status = status
+                                    .lock()
+                                    .map_err(|e| Error::StatusLock(e.to_string()))?;
+                                status.set_config(field, None)?;
+                                status.config().save()
+                            };
+                            self.tools.remove(&tool);
+                            match (saved, self.detect(tool).await?) {
+                                (Err(e), _) => format!("Cannot save the settings: {e}"),
+                                (Ok(()), Some(version)) => format!("Detected {tool} {version}"),
+                                (Ok(()), None) => format!("{tool} was not found"),
+                            }
+                        }
+                        (_, value) => {
+                            let saved = {
+                                let mut status = status
+                                    .lock()
+                                    .map_err(|e| Error::StatusLock(e.to_string()))?;
+                                match status.set_config(field, value.as_deref()) {
+                                    Ok(()) => {
+                                        if field == Field::Theme {
+                                            self.theme = Theme::from_env(status.theme());
+                                        }
+                                        match status.config().save() {
+                                            Ok(()) => Ok(status.config().get(field)),
+                                            Err(e) => Err(format!("Cannot save the settings: {e}")),
+                                        }
+                                    }
+                                    Err(e) => Err(e.to_string()),
+                                }
+                            };
+                            match saved {
+                                Ok(saved) => {
+                                    // a new minimum version means checking the tool again
+                                    if let Some(tool) = field.tool() {
+                                        self.tools.remove(&tool);
+                                    }
+                                    format!("Saved {} as {saved}", field.label())
+                                }
+                                Err(e) => e,
+                            }
                         }
+                    };
+                    info!("{}", message);
+                    self.refresh_read_only().await?;
+                    let fields = self.settings()?;
+                    let detected = self.detected().await?;
+                    let show = evt!(
+                        Screens::Settings,
+                        tui::Event::ShowSettings(fields, detected, Some(message))
+                    );
+                    to_ui.send(show.into()).await?;
+                }
+                tui::Event::SupportBundle(log, failed) => {
+                    // the bundle is most needed when something is broken, so save it anyway
+                    let tools = self.detected().await.unwrap_or_else(|e| {
+                        error!("Failed to detect the tools for the support bundle: {}", e);
+                        Vec::new()
+                    });
+                    let (workshop, lesson) = {
+                        let status = status
+                            .lock()
+                            .map_err(|e| Error::StatusLock(e.to_string()))?;
+                        (
+                            status.workshop().map(String::from),
+                            status.lesson().map(String::from),
+                        )
+                    };
+                    let bundle = support::Bundle {
+                        workshop,
+                        lesson,
+                        failed,
+                        tools,
+                        status: fs::workshops::data_dir()
+                            .and_then(|dir| std::fs::read_to_string(dir.join("status.yaml")).ok()),
+                        log,
+                    };
+                    match support::save("support", &bundle.to_string()) {
+                        Ok(path) => info!("Saved a support bundle to {}", path.display()),
+                        Err(e) => error!("Failed to save a support bundle: {}", e),
                     }
                 }
                 tui::Event::InstallWorkshop(url, next) => {
+                    let unmet = self.unmet(&[tools::Requirement::new(Tool::Git)]).await?;
+                    if !unmet.is_empty() {
+                        return Self::blocked(&to_ui, "install workshops", &unmet, next).await;
+                    }
+
                     // Get current status information
                     let git_executable = {
                         let status = status
@@ -942,6 +1585,149 @@ impl App {
                         }
                     });
                 }
+                tui::Event::Checkpoint(workshop, lesson) => {
+                    let checkpoints = status
+                        .lock()
+                        .map_err(|e| Error::StatusLock(e.to_string()))?
+                        .checkpoints();
+                    if !checkpoints {
+                        return Ok(());
+                    }
+                    let unmet = self.unmet(&[tools::Requirement::new(Tool::Git)]).await?;
+                    if !unmet.is_empty() {
+                        let skipped = evt!(
+                            Screens::Log,
+                            tui::Event::Log(format!(
+                                "^ Skipped the checkpoint without {}",
+                                unmet.join(", ")
+                            ))
+                        );
+                        to_ui.send(skipped.into()).await?;
+                        return Ok(());
+                    }
+
+                    // a missing checkpoint is not worth stopping the app for
+                    let git_executable = status
+                        .lock()
+                        .map_err(|e| Error::StatusLock(e.to_string()))?
+                        .git_executable()
+                        .map(String::from);
+                    let (git_exe, project_root) = match (git_executable, Self::project_root()) {
+                        (Some(git_exe), Ok(project_root)) => (git_exe, project_root),
+                        (None, _) => {
+                            warn!("Skipped the checkpoint: {}", fs::Error::NoGitExecutable);
+                            return Ok(());
+                        }
+                        (_, Err(e)) => {
+                            warn!("Skipped the checkpoint: {e}");
+                            return Ok(());
+                        }
+                    };
+
+                    debug!("Saving checkpoint for {workshop}/{lesson}");
+
+                    // commit in the background, the output goes to the log
+                    let command_runner = self.command_runner.clone();
+                    let token = self.token.clone();
+                    let sender = to_ui.clone();
+
+                    tokio::spawn(async move {
+                        let msg = match command_runner
+                            .checkpoint(&git_exe, &project_root, &workshop, &lesson, &token)
+                            .await
+                        {
+                            Ok(result) if result.success => format!("y {}", result.last_line),
+                            Ok(result) => format!("! checkpoint failed: {}", result.last_line),
+                            Err(e) => format!("! checkpoint failed: {e}"),
+                        };
+                        let _ = sender
+                            .send((Some(Screens::Log), tui::Event::Log(msg)).into())
+                            .await;
+                    });
+                }
+                tui::Event::RestoreCheckpoint(workshop, lesson) => {
+                    let unmet = self.unmet(&[tools::Requirement::new(Tool::Git)]).await?;
+                    if !unmet.is_empty() {
+                        let load_lesson = evt!(Screens::Lesson, tui::Event::LoadLesson);
+                        let hide_log = evt!(None, tui::Event::HideLog(Some(load_lesson)));
+                        return Self::blocked(
+                            &to_ui,
+                            "restore checkpoints",
+                            &unmet,
+                            Some(hide_log),
+                        )
+                        .await;
+                    }
+
+                    let (git_executable, spoken, programming) = {
+                        let status = status
+                            .lock()
+                            .map_err(|e| Error::StatusLock(e.to_string()))?;
+                        (
+                            status.git_executable().map(String::from),
+                            status.spoken_language(),
+                            status.programming_language(),
+                        )
+                    };
+                    let git_exe = git_executable.ok_or(fs::Error::NoGitExecutable)?;
+                    let project_root = Self::project_root()?;
+
+                    // the checkpoint from before the lesson is the one of a lesson it requires
+                    let predecessors = match fs::workshops::load(&workshop) {
+                        Some(workshop_data) => workshop_data
+                            .get_predecessors(&lesson, spoken, programming)
+                            .await
+                            .unwrap_or_else(|e| {
+                                warn!("Cannot find the lessons before {lesson}: {e}");
+                                Vec::new()
+                            }),
+                        None => Vec::new(),
+                    };
+
+                    let show_log = evt!(None, tui::Event::ShowLog(None));
+                    to_ui.send(show_log.into()).await?;
+
+                    let running = evt!(
+                        Screens::Log,
+                        tui::Event::Log(format!("r Restoring the start of lesson: {lesson}"))
+                    );
+                    to_ui.send(running.into()).await?;
+
+                    // restore in the background, then reload the lesson from the restored files
+                    let command_runner = self.command_runner.clone();
+                    let token = self.token.clone();
+                    let sender = to_ui.clone();
+
+                    tokio::spawn(async move {
+                        let result = command_runner
+                            .restore_checkpoint(
+                                &git_exe,
+                                &project_root,
+                                &workshop,
+                                &lesson,
+                                &predecessors,
+                                &token,
+                            )
+                            .await
+                            .unwrap_or_else(|e| CommandResult {
+                                success: false,
+                                exit_code: -1,
+                                last_line: format!("restore failed: {e}"),
+                                cancelled: false,
+                            });
+                        let load_lesson = evt!(Screens::Lesson, tui::Event::LoadLesson);
+                        let hide_log = evt!(None, tui::Event::HideLog(Some(load_lesson)));
+                        let _ = sender
+                            .send(
+                                (
+                                    Some(Screens::Log),
+                                    tui::Event::CommandCompleted(result, Some(hide_log), None),
+                                )
+                                    .into(),
+                            )
+                            .await;
+                    });
+                }
 
                 _ => {
                     // pass the event to every screen
@@ -971,33 +1757,47 @@ impl App {
         to_ui: Sender<screens::Event>,
         status: Arc<Mutex<Status>>,
     ) -> Result<(), Error> {
-        if let event::Event::Key(key) = event {
-            match key.code {
+        // a screen taking text input gets every key
+        let typing = !self.log.load(Ordering::SeqCst)
+            && self
+                .screens
+                .get(&self.screen.load(Ordering::SeqCst).into())
+                .is_some_and(|screen| screen.is_typing());
+
+        match event {
+            event::Event::Key(_) if typing => {}
+            event::Event::Key(key) => match self.keymap.action(&key) {
                 // These key bindings work on every screen
-                KeyCode::Char('q') | KeyCode::Char('Q') => {
+                Some(Action::Quit) => {
                     self.token.cancel();
+                    return Ok(());
                 }
-                KeyCode::Char('`') => to_ui.send((None, tui::Event::ToggleLog).into()).await?,
-                _ => {
-                    if self.log.load(Ordering::SeqCst) {
-                        // send key events to the log window if it is showing
-                        if let Some(screen) = self.screens.get_mut(&Screens::Log) {
-                            return screen.handle_event(event.into(), to_ui, status).await;
-                        } else {
-                            error!("Log screen not found");
-                        }
-                    } else {
-                        // pass the key events to the current screen
-                        let current_screen = self.screen.load(Ordering::SeqCst).into();
-                        if let Some(screen) = self.screens.get_mut(&current_screen) {
-                            return screen.handle_event(event.into(), to_ui, status).await;
-                        } else {
-                            return Err(Error::Tui(format!(
-                                "Unknown screen type: {current_screen}",
-                            )));
-                        }
-                    }
+                Some(Action::ToggleLog) => {
+                    to_ui.send((None, tui::Event::ToggleLog).into()).await?;
+                    return Ok(());
                 }
+                _ => {}
+            },
+            event::Event::Mouse(_) => {}
+            _ => return Ok(()),
+        }
+
+        if self.log.load(Ordering::SeqCst) {
+            // send input events to the log window if it is showing
+            if let Some(screen) = self.screens.get_mut(&Screens::Log) {
+                return screen.handle_event(event.into(), to_ui, status).await;
+            } else {
+                error!("Log screen not found");
+            }
+        } else {
+            // pass the input events to the current screen
+            let current_screen = self.screen.load(Ordering::SeqCst).into();
+            if let Some(screen) = self.screens.get_mut(&current_screen) {
+                return screen.handle_event(event.into(), to_ui, status).await;
+            } else {
+                return Err(Error::Tui(
+                    format!("Unknown screen type: {current_screen}",),
+                ));
             }
         }
         Ok(())
@@ -1024,11 +1824,39 @@ impl Screen for App {
         }
     }
 
-    fn render_screen(&mut self, area: Rect, buf: &mut Buffer) -> Result<(), Error> {
+    fn render_screen(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) -> Result<(), Error> {
+        // show a banner above the screens while another instance owns the project or tools are
+        // missing
+        let banner = match &self.owner {
+            Some(owner) => Some(format!(
+                " Read-only: {owner} is using this project, so progress made here is not saved."
+            )),
+            None if !self.read_only.is_empty() => Some(format!(
+                " Read-only: {} not found. Run `workshop doctor` to see how to install it.",
+                self.read_only.join(", ")
+            )),
+            None => None,
+        };
+        let area = if let Some(text) = banner {
+            let [banner, area] =
+                Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(area);
+            Line::from(text)
+                .style(
+                    Style::default()
+                        .fg(theme.busy)
+                        .bg(theme.background)
+                        .add_modifier(Modifier::BOLD),
+                )
+                .render(banner, buf);
+            area
+        } else {
+            area
+        };
+
         // render the log if it is being show
         if self.log.load(Ordering::SeqCst) {
             if let Some(screen) = self.screens.get_mut(&Screens::Log) {
-                screen.render_screen(area, buf)?;
+                screen.render_screen(area, buf, theme)?;
             } else {
                 error!("Log screen not found");
             }
@@ -1036,7 +1864,7 @@ impl Screen for App {
             // render the current screen
             let current_screen = self.screen.load(Ordering::SeqCst).into();
             if let Some(screen) = self.screens.get_mut(&current_screen) {
-                screen.render_screen(area, buf)?;
+                screen.render_screen(area, buf, theme)?;
             } else {
                 error!("Unknown screen: {:?}", current_screen);
             }
@@ -1047,6 +1875,7 @@ impl Screen for App {
 
 impl Widget for &mut App {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let _ = self.render_screen(area, buf);
+        let theme = self.theme;
+        let _ = self.render_screen(area, buf, &theme);
     }
 }
@@ -1,6 +1,7 @@
 use anyhow::Result;
-use clap::Parser;
-use workshop::{App, Log};
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+use workshop::{config::Field, doctor, log, App, Config, Log};
 
 #[derive(Parser)]
 #[command(name = "workshop")]
@@ -13,6 +14,66 @@ struct Args {
 
     #[arg(long, help = "Show version information")]
     version: bool,
+
+    #[arg(
+        long = "set",
+        global = true,
+        value_name = "KEY=VALUE",
+        value_parser = parse_setting,
+        help = "Override a setting from the config files for this run"
+    )]
+    overrides: Vec<(Field, String)>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Write the log to this file instead of the one in the log directory"
+    )]
+    log_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "LEVEL",
+        help = "Log at this level, e.g. debug or workshop=trace, instead of from RUST_LOG"
+    )]
+    log_level: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        default_value = "text",
+        help = "Write the log file as text or json"
+    )]
+    log_format: log::Format,
+
+    #[command(subcommand)]
+    command: Option<Commands>,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Check the tools and the Docker setup that the workshops need
+    Doctor,
+    /// Inspect the settings
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Print the effective value of every setting and where it came from
+    Show,
+}
+
+// parse a `key=value` setting override
+fn parse_setting(s: &str) -> Result<(Field, String), String> {
+    let (key, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected KEY=VALUE, got '{s}'"))?;
+    let field = key.parse::<Field>().map_err(|e| e.to_string())?;
+    Ok((field, value.to_string()))
 }
 
 #[tokio::main(flavor = "multi_thread")]
@@ -26,11 +87,45 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
+    let config = Config::load(&args.overrides)?;
+    match args.command {
+        // Handle the doctor subcommand, exiting non-zero if anything needs fixing
+        Some(Commands::Doctor) => {
+            let report = doctor::run(&config).await;
+            print!("{report}");
+            std::process::exit(if report.is_healthy() { 0 } else { 1 });
+        }
+        Some(Commands::Config {
+            command: ConfigCommands::Show,
+        }) => {
+            println!("user config: {}", Config::user_path()?.display());
+            match Config::project_path() {
+                Some(path) => println!("project config: {}", path.display()),
+                None => println!("project config: none"),
+            }
+            println!();
+            for field in Field::ALL {
+                let value = config.get(field);
+                let value = if value.is_empty() { "none" } else { &value };
+                println!("{:<32}{value:<28}{}", field.key(), config.source(field));
+            }
+            return Ok(());
+        }
+        None => {}
+    }
+
     // initialize the logger
-    let from_logger = Log::init(Some("log.txt"))?;
+    let from_logger = Log::init(&log::Options {
+        file: Some(match args.log_file {
+            Some(path) => path,
+            None => Log::default_file()?,
+        }),
+        level: args.log_level,
+        format: args.log_format,
+    })?;
 
     // Initialize the app
-    let mut app = App::new(from_logger)?;
+    let mut app = App::new(from_logger, config)?;
 
     // run the app
     let app_handle = tokio::spawn(async move { app.run(args.install).await });
@@ -0,0 +1,244 @@
+use crate::fs::watch::{ALWAYS_IGNORED, CHECK_OUTPUT};
+use std::path::Path;
+use tokio::process::Command;
+
+/// The namespace of the tags that mark completed lessons
+const TAG_PREFIX: &str = "workshop";
+
+/// The pathspec of the files in a checkpoint: the project without the workshop data and the
+/// folders and caches that are not watched. Git leaves out the untracked files matched by the
+/// project's `.gitignore` itself.
+pub fn pathspec() -> Vec<String> {
+    let dirs = ALWAYS_IGNORED.iter().map(|dir| format!("{dir}/"));
+    let check_output = CHECK_OUTPUT.lines().map(String::from);
+    let excluded = dirs
+        .chain(check_output)
+        .map(|pattern| match pattern.strip_suffix('/') {
+            Some(dir) => format!(":(exclude,glob)**/{dir}/**"),
+            None => format!(":(exclude,glob)**/{pattern}"),
+        });
+    std::iter::once(".".to_string()).chain(excluded).collect()
+}
+
+/// The options that give git an identity to commit with when the user has not configured one
+const IDENTITY: [&str; 4] = [
+    "-c",
+    "user.name=workshop",
+    "-c",
+    "user.email=workshop@localhost",
+];
+
+// replace the characters that are not allowed in a git ref name component
+fn ref_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    name.trim_matches('.').replace("..", "-")
+}
+
+/// The tag that marks the end of a lesson, e.g. `workshop/intro/01-hello`
+pub fn tag(workshop: &str, lesson: &str) -> String {
+    format!("{TAG_PREFIX}/{}/{}", ref_name(workshop), ref_name(lesson))
+}
+
+/// The branch to restore the checkpoint from before a lesson into
+pub fn branch(workshop: &str, lesson: &str, stamp: u64) -> String {
+    format!(
+        "restore/{}/{}-{stamp}",
+        ref_name(workshop),
+        ref_name(lesson)
+    )
+}
+
+// run git in the project root and get its output if it succeeds
+async fn query(git: &str, root: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new(git)
+        .args(args)
+        .current_dir(root)
+        .output()
+        .await
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Check if the project root is in a git repository
+pub async fn is_repository(git: &str, root: &Path) -> bool {
+    query(git, root, &["rev-parse", "--git-dir"])
+        .await
+        .is_some()
+}
+
+/// Get the options to put before a git command that records the user's identity
+pub async fn identity(git: &str, root: &Path) -> Vec<&'static str> {
+    if query(git, root, &["config", "user.email"]).await.is_some() {
+        Vec::new()
+    } else {
+        IDENTITY.to_vec()
+    }
+}
+
+/// Find the checkpoint from before a lesson: the tag of the first of the lessons it requires, in
+/// the same workshop, that has one
+pub async fn previous(
+    git: &str,
+    root: &Path,
+    workshop: &str,
+    predecessors: &[String],
+) -> Option<String> {
+    for predecessor in predecessors {
+        let tag = tag(workshop, predecessor);
+        let reference = format!("refs/tags/{tag}");
+        if query(git, root, &["rev-parse", "--verify", "--quiet", &reference])
+            .await
+            .is_some()
+        {
+            return Some(tag);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ref_names() {
+        assert_eq!(tag("intro", "01-hello"), "workshop/intro/01-hello");
+        assert_eq!(
+            tag("intro to p2p", ".hidden..lesson~1"),
+            "workshop/intro-to-p2p/hidden-lesson-1"
+        );
+        assert_eq!(branch("intro", "02-world", 42), "restore/intro/02-world-42");
+    }
+
+    // commit the project and tag it as the checkpoint of a lesson, like a completed lesson does
+    fn complete(root: &Path, workshop: &str, lesson: &str) {
+        std::fs::write(root.join("progress.txt"), format!("{workshop} {lesson}")).unwrap();
+        let message = format!("Completed {lesson} of {workshop}");
+        let pathspec = pathspec();
+        let pathspec: Vec<&str> = pathspec.iter().map(String::as_str).collect();
+        let steps = [
+            [&["add", "--all", "--"], &pathspec[..]].concat(),
+            [
+                &IDENTITY[..],
+                &["commit", "--quiet", "-m", &message, "--"],
+                &pathspec[..],
+            ]
+            .concat(),
+            [&IDENTITY[..], &["tag", "--force", "-a", "-m", &message]].concat(),
+        ];
+        for (i, args) in steps.iter().enumerate() {
+            let mut command = std::process::Command::new("git");
+            command.args(args).current_dir(root);
+            if i == 2 {
+                command.arg(tag(workshop, lesson));
+            }
+            assert!(command.output().unwrap().status.success());
+        }
+    }
+
+    // create an empty git repository
+    fn init(root: &Path) {
+        let init = std::process::Command::new("git")
+            .args(["init", "--quiet"])
+            .current_dir(root)
+            .output()
+            .unwrap();
+        assert!(init.status.success());
+    }
+
+    #[tokio::test]
+    async fn test_pathspec() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        let project = root.join("project");
+        init(root);
+        for file in [
+            "src/main.py",
+            "src/__pycache__/main.cpython-312.pyc",
+            "lib/target/debug/lib",
+            "target/debug/app",
+            "web/node_modules/left-pad/index.js",
+            ".workshops/intro/status.yaml",
+        ] {
+            let path = project.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, file).unwrap();
+        }
+        // a change staged outside the project is not part of its checkpoint
+        std::fs::write(root.join("notes.txt"), "notes").unwrap();
+        let staged = std::process::Command::new("git")
+            .args(["add", "notes.txt"])
+            .current_dir(root)
+            .output()
+            .unwrap();
+        assert!(staged.status.success());
+
+        complete(&project, "intro", "01-hello");
+
+        let files = query("git", root, &["ls-tree", "-r", "--name-only", "HEAD"]).await;
+        assert_eq!(
+            files.as_deref(),
+            Some("project/progress.txt\nproject/src/main.py")
+        );
+        let status = query("git", root, &["status", "--porcelain", "notes.txt"]).await;
+        assert_eq!(status.as_deref(), Some("A  notes.txt"));
+    }
+
+    #[tokio::test]
+    async fn test_previous() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        init(root);
+
+        complete(root, "intro", "01-hello");
+        complete(root, "intro", "02-loops");
+        complete(root, "other", "01-start");
+        complete(root, "intro", "03-end");
+        // redo the first lesson, moving its tag
+        complete(root, "intro", "01-hello");
+
+        let previous = |workshop: &'static str, predecessors: &[&str]| {
+            let predecessors: Vec<String> = predecessors.iter().map(|p| p.to_string()).collect();
+            async move { super::previous("git", root, workshop, &predecessors).await }
+        };
+
+        // the checkpoint is the lesson's predecessor, not the newest tag
+        assert_eq!(
+            previous("intro", &["02-loops"]).await.as_deref(),
+            Some("workshop/intro/02-loops")
+        );
+        assert_eq!(
+            previous("intro", &["01-hello"]).await.as_deref(),
+            Some("workshop/intro/01-hello")
+        );
+        let redo = query(
+            "git",
+            root,
+            &["rev-parse", "workshop/intro/01-hello^{commit}"],
+        )
+        .await;
+        assert_eq!(redo, query("git", root, &["rev-parse", "HEAD"]).await);
+        // predecessors without a checkpoint are skipped
+        assert_eq!(
+            previous("intro", &["04-missing", "02-loops"])
+                .await
+                .as_deref(),
+            Some("workshop/intro/02-loops")
+        );
+        // other workshops do not count
+        assert_eq!(previous("other", &["02-loops"]).await, None);
+        assert_eq!(previous("intro", &[]).await, None);
+    }
+}
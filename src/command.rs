@@ -1,15 +1,22 @@
 use crate::{
+    checkpoint,
+    diagnostics::Scanner,
+    fs,
+    languages::programming,
     ui::tui::{self, screens, widgets::StatusMode},
     Error,
 };
-use std::path::Path;
+use std::{
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
 use tokio::{
     io::{AsyncBufReadExt, BufReader},
     process::Command,
     sync::mpsc::Sender,
 };
 use tokio_util::sync::CancellationToken;
-use tracing::{debug, error};
+use tracing::{debug, error, warn};
 
 /// Result of command execution
 #[derive(Debug, Clone)]
@@ -17,6 +24,18 @@ pub struct CommandResult {
     pub success: bool,
     pub exit_code: i32,
     pub last_line: String,
+    /// the command was cancelled before it finished
+    pub cancelled: bool,
+}
+
+/// Where the output of a command goes
+struct Output<'a> {
+    /// stream each line to the Log screen with this prefix. Output from the check scripts starts
+    /// with the log message type, other output is prefixed with spaces so that the Log screen
+    /// does not mistake its first characters for a message type.
+    prefix: Option<&'a str>,
+    /// scan each line for compiler diagnostics
+    diagnostics: Option<&'a mut Scanner>,
 }
 
 /// Generic command runner that sends output to the Log screen
@@ -65,6 +84,24 @@ impl CommandRunner {
         env_vars: &[(&str, &str)],
         token: &CancellationToken,
         trace: bool,
+    ) -> Result<CommandResult, Error> {
+        let output = Output {
+            prefix: trace.then_some(""),
+            diagnostics: None,
+        };
+        self.run_with_output(cmd, args, working_dir, env_vars, token, output)
+            .await
+    }
+
+    /// Run a command, streaming and scanning its output as described by `output`
+    async fn run_with_output(
+        &self,
+        cmd: &str,
+        args: &[&str],
+        working_dir: Option<&std::path::Path>,
+        env_vars: &[(&str, &str)],
+        token: &CancellationToken,
+        mut output: Output<'_>,
     ) -> Result<CommandResult, Error> {
         // Build command
         let mut command = Command::new(cmd);
@@ -106,6 +143,10 @@ impl CommandRunner {
             .spawn()
         {
             Ok(child) => child,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                error!("Failed to spawn command '{cmd}': {e}");
+                return Err(Error::CommandNotFound(cmd.to_string()));
+            }
             Err(e) => {
                 error!("Failed to spawn command '{cmd}': {e}");
                 return Err(Error::Command(format!(
@@ -142,12 +183,13 @@ impl CommandRunner {
                 line = stdout_lines.next_line(), if !stdout_finished => {
                     match line {
                         Ok(Some(line)) => {
+                            self.scan(&mut output, &line).await?;
                             if let Some(prev_line) = stdout_line.take() {
-                                if trace {
+                                if let Some(prefix) = output.prefix {
                                     self.event_sender
                                         .send((
                                             Some(screens::Screens::Log),
-                                            tui::Event::CommandOutput(prev_line, None)
+                                            tui::Event::CommandOutput(format!("{prefix}{prev_line}"), None)
                                         ).into())
                                         .await?;
                                 }
@@ -169,12 +211,13 @@ impl CommandRunner {
                 line = stderr_lines.next_line(), if !stderr_finished => {
                     match line {
                         Ok(Some(line)) => {
+                            self.scan(&mut output, &line).await?;
                             if let Some(prev_line) = stderr_line.take() {
-                                if trace {
+                                if let Some(prefix) = output.prefix {
                                     self.event_sender
                                         .send((
                                             Some(screens::Screens::Log),
-                                            tui::Event::CommandOutput(prev_line, None)
+                                            tui::Event::CommandOutput(format!("{prefix}{prev_line}"), None)
                                         ).into())
                                         .await?;
                                 }
@@ -207,6 +250,7 @@ impl CommandRunner {
             success,
             exit_code,
             last_line: last_line.clone(),
+            cancelled: false,
         };
 
         Ok(result)
@@ -219,17 +263,146 @@ impl CommandRunner {
         docker_compose_executable: &str,
         python_executable: &str,
         lesson_dir: &Path,
+        programming: programming::Code,
+        pre_check: Option<&str>,
+        token: &CancellationToken,
+    ) -> Result<CommandResult, Error> {
+        let result = self
+            .run_check(
+                docker_compose_executable,
+                python_executable,
+                lesson_dir,
+                programming,
+                pre_check,
+                token,
+            )
+            .await;
+        if !token.is_cancelled() {
+            return result;
+        }
+
+        // killing the compose command leaves its containers running, so take them down before
+        // the next check starts
+        let (project_root, lesson_path) = self.calculate_docker_env_paths(lesson_dir)?;
+        let env_vars = [
+            ("PROJECT_ROOT", project_root.as_str()),
+            ("LESSON_PATH", lesson_path.as_str()),
+        ];
+        self.run_command_with_env(
+            docker_compose_executable,
+            &[
+                "compose",
+                "--project-name",
+                "workshop",
+                "down",
+                "--remove-orphans",
+            ],
+            Some(lesson_dir),
+            &env_vars,
+            &CancellationToken::new(),
+            false,
+        )
+        .await?;
+        Err(Error::Command("Check cancelled".to_string()))
+    }
+
+    /// Run the quick pre-check of a lesson on the host. Returns None when there is nothing to run
+    /// or the pre-check command is not installed.
+    async fn run_pre_check(
+        &self,
+        pre_check: &str,
+        project_root: &Path,
+        env_vars: &[(&str, &str)],
+        token: &CancellationToken,
+        scanner: &mut Scanner,
+    ) -> Result<Option<CommandResult>, Error> {
+        let mut parts = pre_check.split_whitespace();
+        let Some(cmd) = parts.next() else {
+            return Ok(None);
+        };
+        let args: Vec<&str> = parts.collect();
+        self.event_sender
+            .send(
+                (
+                    Some(screens::Screens::Log),
+                    tui::Event::Log(format!("r Running pre-check: {pre_check}")),
+                )
+                    .into(),
+            )
+            .await?;
+        let result = self
+            .run_with_output(
+                cmd,
+                &args,
+                Some(project_root),
+                env_vars,
+                token,
+                Output {
+                    prefix: Some("  "),
+                    diagnostics: Some(scanner),
+                },
+            )
+            .await;
+        match result {
+            // the pre-check only saves time, the compose check still runs without it
+            Err(Error::CommandNotFound(cmd)) => {
+                warn!("Skipping pre-check, {cmd} was not found");
+                self.event_sender
+                    .send(
+                        (
+                            Some(screens::Screens::Log),
+                            tui::Event::Log(format!("! Skipping pre-check, {cmd} was not found")),
+                        )
+                            .into(),
+                    )
+                    .await?;
+                Ok(None)
+            }
+            result => result.map(Some),
+        }
+    }
+
+    /// Run the steps of a solution check
+    async fn run_check(
+        &self,
+        docker_compose_executable: &str,
+        python_executable: &str,
+        lesson_dir: &Path,
+        programming: programming::Code,
+        pre_check: Option<&str>,
         token: &CancellationToken,
     ) -> Result<CommandResult, Error> {
         // Calculate PROJECT_ROOT and LESSON_PATH for docker-compose environment
         let (project_root, lesson_path) = self.calculate_docker_env_paths(lesson_dir)?;
 
+        // scan the build output for compiler diagnostics in the project
+        let mut scanner = Scanner::new(programming, Path::new(&project_root));
+        self.event_sender
+            .send((Some(screens::Screens::Log), tui::Event::ClearDiagnostics).into())
+            .await?;
+
         // Set up environment variables for docker-compose
         let env_vars = [
             ("PROJECT_ROOT", project_root.as_str()),
             ("LESSON_PATH", lesson_path.as_str()),
         ];
 
+        // Run the quick pre-check on the host and stop early if it fails
+        if let Some(pre_check) = pre_check {
+            let result = self
+                .run_pre_check(
+                    pre_check,
+                    Path::new(&project_root),
+                    &env_vars,
+                    token,
+                    &mut scanner,
+                )
+                .await?;
+            if let Some(result) = result.filter(|result| !result.success) {
+                return Ok(result);
+            }
+        }
+
         // Clean up any previous containers
         self.run_command_with_env(
             docker_compose_executable,
@@ -317,7 +490,7 @@ impl CommandRunner {
 
         // Run docker compose up --build
         let docker_result = self
-            .run_command_with_env(
+            .run_with_output(
                 docker_compose_executable.as_ref(),
                 &[
                     "compose",
@@ -331,7 +504,10 @@ impl CommandRunner {
                 Some(lesson_dir),
                 &env_vars,
                 token,
-                false,
+                Output {
+                    prefix: None,
+                    diagnostics: Some(&mut scanner),
+                },
             )
             .await?;
 
@@ -373,12 +549,16 @@ impl CommandRunner {
         .await?;
 
         // Run python check.py
-        self.run_command(
+        self.run_with_output(
             python_executable.as_ref(),
             &["check.py"],
             Some(lesson_dir),
+            &[],
             token,
-            true,
+            Output {
+                prefix: Some(""),
+                diagnostics: Some(&mut scanner),
+            },
         )
         .await
     }
@@ -429,31 +609,135 @@ impl CommandRunner {
         .await
     }
 
-    /// Calculate PROJECT_ROOT and LESSON_PATH environment variables for docker-compose
-    fn calculate_docker_env_paths(&self, lesson_dir: &Path) -> Result<(String, String), Error> {
-        // Find the .workshops directory by going up from lesson_dir
-        let mut current = lesson_dir;
-        let workshops_dir = loop {
-            if current
-                .file_name()
-                .map(|n| n == ".workshops")
-                .unwrap_or(false)
-            {
-                break current;
+    /// Commit the project and tag the commit as the checkpoint for a completed lesson
+    pub async fn checkpoint(
+        &self,
+        git_executable: &str,
+        project_root: &Path,
+        workshop: &str,
+        lesson: &str,
+        token: &CancellationToken,
+    ) -> Result<CommandResult, Error> {
+        if !checkpoint::is_repository(git_executable, project_root).await {
+            let result = self
+                .run_command(git_executable, &["init"], Some(project_root), token, true)
+                .await?;
+            if !result.success {
+                return Ok(result);
             }
-            if let Some(parent) = current.parent() {
-                current = parent;
-            } else {
-                return Err(Error::Command(
-                    "Could not find .workshops directory".to_string(),
-                ));
+        }
+
+        let identity = checkpoint::identity(git_executable, project_root).await;
+        let tag = checkpoint::tag(workshop, lesson);
+        let message = format!("Completed {lesson} of {workshop}");
+        // only commit the project, leaving anything else the user has staged alone
+        let pathspec = checkpoint::pathspec();
+        let pathspec: Vec<&str> = pathspec.iter().map(String::as_str).collect();
+        let steps = [
+            [&["add", "--all", "--"], &pathspec[..]].concat(),
+            [
+                &identity[..],
+                &["commit", "--allow-empty", "--quiet", "-m", &message, "--"],
+                &pathspec[..],
+            ]
+            .concat(),
+            [
+                &identity[..],
+                &["tag", "--force", "-a", "-m", &message, &tag],
+            ]
+            .concat(),
+        ];
+        for args in steps.iter() {
+            let result = self
+                .run_command(git_executable, args, Some(project_root), token, true)
+                .await?;
+            if !result.success {
+                return Ok(result);
             }
+        }
+
+        Ok(CommandResult {
+            success: true,
+            exit_code: 0,
+            last_line: format!("Saved checkpoint {tag}"),
+            cancelled: false,
+        })
+    }
+
+    /// Stash the user's changes and check out the checkpoint from before a lesson into a new
+    /// branch
+    pub async fn restore_checkpoint(
+        &self,
+        git_executable: &str,
+        project_root: &Path,
+        workshop: &str,
+        lesson: &str,
+        predecessors: &[String],
+        token: &CancellationToken,
+    ) -> Result<CommandResult, Error> {
+        let Some(tag) =
+            checkpoint::previous(git_executable, project_root, workshop, predecessors).await
+        else {
+            return Ok(CommandResult {
+                success: false,
+                exit_code: 1,
+                last_line: "There is no checkpoint from before this lesson".to_string(),
+                cancelled: false,
+            });
         };
 
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let branch = checkpoint::branch(workshop, lesson, stamp);
+        let identity = checkpoint::identity(git_executable, project_root).await;
+        let message = format!("Before restoring {lesson} of {workshop}");
+        let pathspec = checkpoint::pathspec();
+        let pathspec: Vec<&str> = pathspec.iter().map(String::as_str).collect();
+        let stash = ["stash", "push", "--include-untracked", "-m", &message, "--"];
+        let steps = [
+            [&identity[..], &stash, &pathspec[..]].concat(),
+            vec!["checkout", "-b", &branch, &tag],
+        ];
+        for args in steps.iter() {
+            let result = self
+                .run_command(git_executable, args, Some(project_root), token, true)
+                .await?;
+            if !result.success {
+                return Ok(result);
+            }
+        }
+
+        Ok(CommandResult {
+            success: true,
+            exit_code: 0,
+            last_line: format!("Restored {tag} into branch {branch}"),
+            cancelled: false,
+        })
+    }
+
+    /// Send the diagnostic completed by a line of output to the Log screen
+    async fn scan(&self, output: &mut Output<'_>, line: &str) -> Result<(), Error> {
+        if let Some(diagnostic) = output.diagnostics.as_mut().and_then(|d| d.scan(line)) {
+            self.event_sender
+                .send(
+                    (
+                        Some(screens::Screens::Log),
+                        tui::Event::Diagnostic(diagnostic),
+                    )
+                        .into(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Calculate PROJECT_ROOT and LESSON_PATH environment variables for docker-compose
+    fn calculate_docker_env_paths(&self, lesson_dir: &Path) -> Result<(String, String), Error> {
         // PROJECT_ROOT is the parent of .workshops directory
-        let project_root = workshops_dir
-            .parent()
-            .ok_or_else(|| Error::Command("Could not find PROJECT_ROOT directory".to_string()))?;
+        let project_root = fs::watch::project_root(lesson_dir)
+            .ok_or_else(|| Error::Command("Could not find .workshops directory".to_string()))?;
 
         // LESSON_PATH is the relative path from PROJECT_ROOT to lesson_dir
         let lesson_path = lesson_dir
@@ -466,3 +750,37 @@ impl CommandRunner {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_missing_pre_check_is_skipped() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(16);
+        let runner = CommandRunner::new(sender);
+        let root = tempfile::tempdir().unwrap();
+        let mut scanner = Scanner::new(programming::Code::py, root.path());
+        let result = runner
+            .run_pre_check(
+                "workshop-missing-pre-check --quick",
+                root.path(),
+                &[],
+                &CancellationToken::new(),
+                &mut scanner,
+            )
+            .await
+            .unwrap();
+        assert!(result.is_none());
+
+        // the Log screen is told why the pre-check did not run
+        drop(runner);
+        let mut skipped = false;
+        while let Some(event) = receiver.recv().await {
+            if let screens::Event::Ui(_, tui::Event::Log(message)) = event {
+                skipped |= message.starts_with("! Skipping pre-check");
+            }
+        }
+        assert!(skipped);
+    }
+}
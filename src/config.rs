@@ -1,15 +1,227 @@
 use crate::{
-    fs,
+    fs::{
+        self,
+        schema::{self, Migration, Versioned},
+    },
+    keymap::Keymap,
     languages::{programming, spoken},
+    models::tools::Tool,
+    ui::tui::{theme::Palette, ThemeConfig},
     Error,
 };
+use semver::Version;
 use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fmt, path::PathBuf, str::FromStr};
 use tracing::info;
 
+/// The value of a language setting that accepts every language
+const ANY: &str = "any";
+
+/// The settings in the config, in the order they are shown
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Field {
+    SpokenLanguage,
+    ProgrammingLanguage,
+    PythonExecutable,
+    PythonMinimumVersion,
+    DockerComposeExecutable,
+    DockerComposeMinimumVersion,
+    GitExecutable,
+    GitMinimumVersion,
+    CheckAfterEdit,
+    Checkpoints,
+    Theme,
+    Keymap,
+}
+
+impl Field {
+    /// Every field, in the order they are shown
+    pub const ALL: [Field; 12] = [
+        Field::SpokenLanguage,
+        Field::ProgrammingLanguage,
+        Field::PythonExecutable,
+        Field::PythonMinimumVersion,
+        Field::DockerComposeExecutable,
+        Field::DockerComposeMinimumVersion,
+        Field::GitExecutable,
+        Field::GitMinimumVersion,
+        Field::CheckAfterEdit,
+        Field::Checkpoints,
+        Field::Theme,
+        Field::Keymap,
+    ];
+
+    /// The key of the field in `config.yaml`
+    pub fn key(&self) -> &'static str {
+        match self {
+            Field::SpokenLanguage => "spoken_language",
+            Field::ProgrammingLanguage => "programming_language",
+            Field::PythonExecutable => "python_executable",
+            Field::PythonMinimumVersion => "python_minimum_version",
+            Field::DockerComposeExecutable => "docker_compose_executable",
+            Field::DockerComposeMinimumVersion => "docker_compose_minimum_version",
+            Field::GitExecutable => "git_executable",
+            Field::GitMinimumVersion => "git_minimum_version",
+            Field::CheckAfterEdit => "check_after_edit",
+            Field::Checkpoints => "checkpoints",
+            Field::Theme => "theme",
+            Field::Keymap => "keymap",
+        }
+    }
+
+    /// The environment variable that overrides the field, e.g. `WORKSHOP_CHECKPOINTS`
+    pub fn env(&self) -> String {
+        format!("WORKSHOP_{}", self.key().to_uppercase())
+    }
+
+    /// The name to show the user
+    pub fn label(&self) -> &'static str {
+        match self {
+            Field::SpokenLanguage => "Spoken language",
+            Field::ProgrammingLanguage => "Programming language",
+            Field::PythonExecutable => "Python executable",
+            Field::PythonMinimumVersion => "Python minimum version",
+            Field::DockerComposeExecutable => "Docker Compose executable",
+            Field::DockerComposeMinimumVersion => "Docker Compose minimum version",
+            Field::GitExecutable => "Git executable",
+            Field::GitMinimumVersion => "Git minimum version",
+            Field::CheckAfterEdit => "Check after edit",
+            Field::Checkpoints => "Checkpoints",
+            Field::Theme => "Theme",
+            Field::Keymap => "Keymap",
+        }
+    }
+
+    /// The tool the field configures, if any
+    pub fn tool(&self) -> Option<Tool> {
+        match self {
+            Field::PythonExecutable | Field::PythonMinimumVersion => Some(Tool::Python),
+            Field::DockerComposeExecutable | Field::DockerComposeMinimumVersion => {
+                Some(Tool::Docker)
+            }
+            Field::GitExecutable | Field::GitMinimumVersion => Some(Tool::Git),
+            _ => None,
+        }
+    }
+
+    /// Check if the field is the executable for a tool
+    pub fn is_executable(&self) -> bool {
+        matches!(
+            self,
+            Field::PythonExecutable | Field::DockerComposeExecutable | Field::GitExecutable
+        )
+    }
+
+    /// Check if the field can be changed in the settings. The keymap is only edited in the file.
+    pub fn is_editable(&self) -> bool {
+        *self != Field::Keymap
+    }
+
+    /// The values to pick from and their names, for the fields that are not typed in
+    pub fn choices(&self) -> Option<Vec<(String, String)>> {
+        let any = (ANY.to_string(), "Any".to_string());
+        match self {
+            Field::SpokenLanguage => {
+                let mut codes: Vec<spoken::Code> = spoken::Code::default().into_iter().collect();
+                codes.sort();
+                Some(
+                    std::iter::once(any)
+                        .chain(
+                            codes.into_iter().map(|code| {
+                                (code.to_string(), code.get_name_in_english().to_string())
+                            }),
+                        )
+                        .collect(),
+                )
+            }
+            Field::ProgrammingLanguage => {
+                let mut codes: Vec<programming::Code> =
+                    programming::Code::default().into_iter().collect();
+                codes.sort();
+                Some(
+                    std::iter::once(any)
+                        .chain(
+                            codes
+                                .into_iter()
+                                .map(|code| (code.to_string(), code.get_name().to_string())),
+                        )
+                        .collect(),
+                )
+            }
+            Field::CheckAfterEdit | Field::Checkpoints => Some(vec![
+                ("true".to_string(), "On".to_string()),
+                ("false".to_string(), "Off".to_string()),
+            ]),
+            Field::Theme => Some(
+                Palette::ALL
+                    .iter()
+                    .map(|palette| (palette.name().to_string(), palette.name().to_string()))
+                    .collect(),
+            ),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Field {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let key = s.trim().replace('-', "_").to_lowercase();
+        Field::ALL
+            .into_iter()
+            .find(|field| field.key() == key)
+            .ok_or_else(|| Error::InvalidSetting(format!("unknown setting '{s}'")))
+    }
+}
+
+/// Where the value of a field came from, from the lowest precedence to the highest
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Source {
+    /// the built-in default
+    Default,
+    /// the `config.yaml` in the user's config directory
+    User,
+    /// the `config.yaml` in the project's `.workshops` directory
+    Project,
+    /// a `WORKSHOP_*` environment variable
+    Env(String),
+    /// a `--set` flag on the command line
+    Cli,
+}
+
+impl Source {
+    /// Check if the value is only for this run and stays out of the user's `config.yaml`
+    pub fn is_override(&self) -> bool {
+        matches!(self, Source::Project | Source::Env(_) | Source::Cli)
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Default => write!(f, "default"),
+            Source::User => write!(f, "user config"),
+            Source::Project => write!(f, "project config"),
+            Source::Env(name) => write!(f, "environment variable {name}"),
+            Source::Cli => write!(f, "--set flag"),
+        }
+    }
+}
+
+// version 1 fixed the spelling of the Python minimum version key
+fn rename_python_minimum_version(mapping: &mut serde_yaml::Mapping) {
+    if let Some(version) = mapping.remove("python_minumum_version") {
+        mapping
+            .entry("python_minimum_version".into())
+            .or_insert(version);
+    }
+}
+
 /// Represents the application configuration
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Config {
-    python_minumum_version: String,
+    python_minimum_version: String,
     python_executable: Option<String>,
     docker_compose_minimum_version: String,
     docker_compose_executable: Option<String>,
@@ -17,12 +229,26 @@ pub struct Config {
     git_minimum_version: String,
     spoken_language: Option<spoken::Code>,
     programming_language: Option<programming::Code>,
+    #[serde(default)]
+    keymap: Keymap,
+    #[serde(default)]
+    theme: ThemeConfig,
+    #[serde(default)]
+    check_after_edit: bool,
+    #[serde(default)]
+    checkpoints: bool,
+    /// where each field that is not the default came from
+    #[serde(skip)]
+    sources: HashMap<Field, Source>,
+    /// the config as the user's file has it, to save in place of the overridden fields
+    #[serde(skip)]
+    user: Option<Box<Config>>,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
-            python_minumum_version: "3.10.0".to_string(),
+            python_minimum_version: "3.10.0".to_string(),
             python_executable: None,
             docker_compose_minimum_version: "2.0.0".to_string(),
             docker_compose_executable: None,
@@ -30,37 +256,134 @@ impl Default for Config {
             git_minimum_version: "2.39.0".to_string(),
             spoken_language: None,
             programming_language: None,
+            keymap: Keymap::default(),
+            theme: ThemeConfig::default(),
+            check_after_edit: false,
+            checkpoints: false,
+            sources: HashMap::new(),
+            user: None,
         }
     }
 }
 
+impl Versioned for Config {
+    const FILE: &'static str = "config.yaml";
+    const MIGRATIONS: &'static [Migration] = &[rename_python_minimum_version];
+}
+
 impl Config {
-    /// Load the Config from a file, createing it if necessary
-    pub fn load() -> Result<Self, Error> {
-        // Load the config from a file or create a new one
-        let config_path = fs::application::config_dir()?.join("config.yaml");
-        if config_path.exists() {
-            info!("Loading config from: {}", config_path.display());
-            Ok(serde_yaml::from_reader(std::fs::File::open(&config_path)?)?)
-        } else {
-            info!("Creating config at: {}", config_path.display());
-            let config = Config::default();
-            config.save()?;
-            Ok(config)
+    /// Get the path to the user's config file
+    pub fn user_path() -> Result<PathBuf, Error> {
+        Ok(fs::application::config_dir()?.join("config.yaml"))
+    }
+
+    /// Get the path to the project's config file, if the project has one
+    pub fn project_path() -> Option<PathBuf> {
+        fs::workshops::data_dir()
+            .map(|dir| dir.join("config.yaml"))
+            .filter(|path| path.is_file())
+    }
+
+    /// Load the Config, creating the user's file if necessary. The defaults are overridden by the
+    /// user's file, then the project's file, then the `WORKSHOP_*` environment variables and
+    /// finally the given command line overrides.
+    pub fn load(overrides: &[(Field, String)]) -> Result<Self, Error> {
+        let user_path = Self::user_path()?;
+        if !user_path.exists() {
+            info!("Creating config at: {}", user_path.display());
+            Config::default().save()?;
+        }
+
+        let files = std::iter::once((Source::User, user_path)).chain(
+            Self::project_path()
+                .into_iter()
+                .map(|path| (Source::Project, path)),
+        );
+        let mut config = Self::from_files(files)?;
+
+        for field in Field::ALL.into_iter().filter(Field::is_editable) {
+            if let Ok(value) = std::env::var(field.env()) {
+                config.assign(field, &value, Source::Env(field.env()))?;
+            }
+        }
+        for (field, value) in overrides {
+            config.assign(*field, value, Source::Cli)?;
+        }
+        Ok(config)
+    }
+
+    // load the defaults overridden by each file in turn, where each file replaces the top level
+    // entries it has
+    fn from_files(files: impl IntoIterator<Item = (Source, PathBuf)>) -> Result<Self, Error> {
+        let mut merged = serde_yaml::to_value(Config::default())?;
+        let mut sources = HashMap::new();
+        let mut user = None;
+        for (source, path) in files {
+            info!("Loading {} from: {}", source, path.display());
+            let layer = schema::migrate::<Config>(fs::atomic::read_yaml(&path)?)?;
+            if let Some(values) = merged.as_mapping_mut() {
+                for (key, value) in layer {
+                    if let Some(field) = key.as_str().and_then(|key| key.parse::<Field>().ok()) {
+                        sources.insert(field, source.clone());
+                    }
+                    values.insert(key, value);
+                }
+            }
+            if source == Source::User {
+                user = Some(Box::new(serde_yaml::from_value(merged.clone())?));
+            }
         }
+        let mut config: Config = serde_yaml::from_value(merged)?;
+        config.sources = sources;
+        config.user = user;
+        Ok(config)
+    }
+
+    // set a field from a source that overrides the user's file
+    fn assign(&mut self, field: Field, value: &str, source: Source) -> Result<(), Error> {
+        self.set(field, value).map_err(|e| match e {
+            Error::InvalidSetting(reason) => Error::InvalidSetting(format!("{reason} ({source})")),
+            e => e,
+        })?;
+        self.sources.insert(field, source);
+        Ok(())
     }
 
-    /// Save the config to a file
+    /// Save the config to the user's file. Fields that the project, the environment or the
+    /// command line override are saved with the value the user's file had.
     pub fn save(&self) -> Result<(), Error> {
-        let config_path = fs::application::config_dir()?.join("config.yaml");
-        serde_yaml::to_writer(std::fs::File::create(&config_path).unwrap(), &self)?;
+        let config_path = Self::user_path()?;
+        schema::write(&config_path, &self.saved())?;
         info!("Config saved to: {}", config_path.display());
         Ok(())
     }
 
+    // the config as it is saved to the user's file
+    fn saved(&self) -> Config {
+        let mut saved = self.clone();
+        if let Some(user) = &self.user {
+            for field in Field::ALL {
+                if self.source(field).is_override() {
+                    saved.copy(field, user);
+                }
+            }
+        }
+        saved
+    }
+
+    /// Get where the value of a field came from
+    pub fn source(&self, field: Field) -> Source {
+        self.sources.get(&field).cloned().unwrap_or(Source::Default)
+    }
+
+    // the user changed a field, so it is saved to their file
+    fn changed(&mut self, field: Field) {
+        self.sources.insert(field, Source::User);
+    }
+
     /// Get the minimum required Python version
     pub fn python_minimum_version(&self) -> &str {
-        &self.python_minumum_version
+        &self.python_minimum_version
     }
 
     /// Get the preferred Python executable
@@ -98,28 +421,296 @@ impl Config {
         self.programming_language
     }
 
+    /// Get the key bindings
+    pub fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    /// Get the color theme
+    pub fn theme(&self) -> &ThemeConfig {
+        &self.theme
+    }
+
+    /// Check if the solution should be checked when the editor exits
+    pub fn check_after_edit(&self) -> bool {
+        self.check_after_edit
+    }
+
+    /// Check if the project should be committed and tagged when a lesson is completed
+    pub fn checkpoints(&self) -> bool {
+        self.checkpoints
+    }
+
+    /// Get the value of a field as text
+    pub fn get(&self, field: Field) -> String {
+        fn language<T: ToString>(code: Option<T>) -> String {
+            code.map(|code| code.to_string())
+                .unwrap_or_else(|| ANY.to_string())
+        }
+
+        match field {
+            Field::SpokenLanguage => language(self.spoken_language),
+            Field::ProgrammingLanguage => language(self.programming_language),
+            Field::PythonExecutable => self.python_executable.clone().unwrap_or_default(),
+            Field::PythonMinimumVersion => self.python_minimum_version.clone(),
+            Field::DockerComposeExecutable => {
+                self.docker_compose_executable.clone().unwrap_or_default()
+            }
+            Field::DockerComposeMinimumVersion => self.docker_compose_minimum_version.clone(),
+            Field::GitExecutable => self.git_executable.clone().unwrap_or_default(),
+            Field::GitMinimumVersion => self.git_minimum_version.clone(),
+            Field::CheckAfterEdit => self.check_after_edit.to_string(),
+            Field::Checkpoints => self.checkpoints.to_string(),
+            Field::Theme if self.theme.has_custom_colors() => {
+                format!("{} with custom colors", self.theme.palette().name())
+            }
+            Field::Theme => self.theme.palette().name().to_string(),
+            Field::Keymap if self.keymap == Keymap::default() => "default".to_string(),
+            Field::Keymap => "custom".to_string(),
+        }
+    }
+
+    /// Set a field from text, checking that the value is valid
+    pub fn set(&mut self, field: Field, value: &str) -> Result<(), Error> {
+        let invalid = |reason: String| Error::InvalidSetting(format!("{}: {reason}", field.key()));
+        let value = value.trim();
+        match field {
+            Field::SpokenLanguage => {
+                self.spoken_language = match value {
+                    ANY => None,
+                    code => Some(
+                        spoken::Code::try_from(code)
+                            .map_err(|_| invalid(format!("unknown spoken language '{code}'")))?,
+                    ),
+                }
+            }
+            Field::ProgrammingLanguage => {
+                self.programming_language =
+                    match value {
+                        ANY => None,
+                        code => Some(programming::Code::try_from(code).map_err(|_| {
+                            invalid(format!("unknown programming language '{code}'"))
+                        })?),
+                    }
+            }
+            Field::PythonExecutable | Field::DockerComposeExecutable | Field::GitExecutable => {
+                if !value.is_empty() && fs::application::resolve(value).is_none() {
+                    return Err(invalid(format!("{value} was not found")));
+                }
+                if let Some(tool) = field.tool() {
+                    self.set_executable(tool, Some(value).filter(|value| !value.is_empty()));
+                }
+            }
+            Field::PythonMinimumVersion
+            | Field::DockerComposeMinimumVersion
+            | Field::GitMinimumVersion => {
+                Version::parse(value)
+                    .map_err(|_| invalid(format!("'{value}' is not a version like 3.10.0")))?;
+                let minimum = match field {
+                    Field::PythonMinimumVersion => &mut self.python_minimum_version,
+                    Field::DockerComposeMinimumVersion => &mut self.docker_compose_minimum_version,
+                    _ => &mut self.git_minimum_version,
+                };
+                *minimum = value.to_string();
+            }
+            Field::CheckAfterEdit | Field::Checkpoints => {
+                let on = match value.to_lowercase().as_str() {
+                    "true" | "yes" | "on" => true,
+                    "false" | "no" | "off" => false,
+                    _ => return Err(invalid(format!("'{value}' is not true or false"))),
+                };
+                if field == Field::CheckAfterEdit {
+                    self.check_after_edit = on;
+                } else {
+                    self.checkpoints = on;
+                }
+            }
+            Field::Theme => {
+                let palette = Palette::ALL
+                    .into_iter()
+                    .find(|palette| palette.name() == value)
+                    .ok_or_else(|| invalid(format!("unknown palette '{value}'")))?;
+                self.theme.set_palette(palette);
+            }
+            Field::Keymap => {
+                return Err(invalid(
+                    "the keymap is changed in the config.yaml file".to_string(),
+                ))
+            }
+        }
+        self.changed(field);
+        Ok(())
+    }
+
+    /// Reset a field to its default value
+    pub fn reset(&mut self, field: Field) {
+        self.copy(field, &Config::default());
+        self.changed(field);
+    }
+
+    // copy the value of a field from another config
+    fn copy(&mut self, field: Field, from: &Config) {
+        match field {
+            Field::SpokenLanguage => self.spoken_language = from.spoken_language,
+            Field::ProgrammingLanguage => self.programming_language = from.programming_language,
+            Field::PythonExecutable => self.python_executable = from.python_executable.clone(),
+            Field::PythonMinimumVersion => {
+                self.python_minimum_version = from.python_minimum_version.clone()
+            }
+            Field::DockerComposeExecutable => {
+                self.docker_compose_executable = from.docker_compose_executable.clone()
+            }
+            Field::DockerComposeMinimumVersion => {
+                self.docker_compose_minimum_version = from.docker_compose_minimum_version.clone()
+            }
+            Field::GitExecutable => self.git_executable = from.git_executable.clone(),
+            Field::GitMinimumVersion => self.git_minimum_version = from.git_minimum_version.clone(),
+            Field::CheckAfterEdit => self.check_after_edit = from.check_after_edit,
+            Field::Checkpoints => self.checkpoints = from.checkpoints,
+            Field::Theme => self.theme = from.theme.clone(),
+            Field::Keymap => self.keymap = from.keymap.clone(),
+        }
+    }
+
     /// Set the preferred Python executable
     pub fn set_python_executable(&mut self, python_executable: &str) {
         self.python_executable = Some(python_executable.to_string());
+        self.changed(Field::PythonExecutable);
     }
 
     /// Set the preferred Docker Compose executable
     pub fn set_docker_compose_executable(&mut self, docker_compose_executable: &str) {
         self.docker_compose_executable = Some(docker_compose_executable.to_string());
+        self.changed(Field::DockerComposeExecutable);
     }
 
     /// Set the preferred Git executable
     pub fn set_git_executable(&mut self, git_executable: &str) {
         self.git_executable = Some(git_executable.to_string());
+        self.changed(Field::GitExecutable);
+    }
+
+    /// Set or clear the preferred executable for a tool
+    pub fn set_executable(&mut self, tool: Tool, executable: Option<&str>) {
+        let (field, value) = match tool {
+            Tool::Python => (Field::PythonExecutable, &mut self.python_executable),
+            Tool::Docker => (
+                Field::DockerComposeExecutable,
+                &mut self.docker_compose_executable,
+            ),
+            Tool::Git => (Field::GitExecutable, &mut self.git_executable),
+        };
+        *value = executable.map(String::from);
+        self.changed(field);
     }
 
     /// Set the spoken language
     pub fn set_spoken_language(&mut self, spoken_language: Option<spoken::Code>) {
         self.spoken_language = spoken_language;
+        self.changed(Field::SpokenLanguage);
     }
 
     /// Set the programming language
     pub fn set_programming_language(&mut self, programming_language: Option<programming::Code>) {
         self.programming_language = programming_language;
+        self.changed(Field::ProgrammingLanguage);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_and_reset() {
+        let mut config = Config::default();
+
+        config.set(Field::PythonMinimumVersion, "3.12.1").unwrap();
+        assert_eq!(config.python_minimum_version(), "3.12.1");
+        assert!(config.set(Field::PythonMinimumVersion, "3.12").is_err());
+        assert_eq!(config.get(Field::PythonMinimumVersion), "3.12.1");
+
+        config.set(Field::SpokenLanguage, "de").unwrap();
+        assert_eq!(config.spoken_language(), Some(spoken::Code::de));
+        config.set(Field::SpokenLanguage, "any").unwrap();
+        assert_eq!(config.spoken_language(), None);
+        assert!(config.set(Field::ProgrammingLanguage, "cobol").is_err());
+
+        assert!(config
+            .set(Field::GitExecutable, "/no/such/dir/git")
+            .is_err());
+        config.set(Field::GitExecutable, "").unwrap();
+        assert_eq!(config.git_executable(), None);
+
+        config.set(Field::Checkpoints, "on").unwrap();
+        assert!(config.checkpoints());
+        config.set(Field::Theme, "light").unwrap();
+        assert_eq!(config.get(Field::Theme), "light");
+        assert!(config.set(Field::Keymap, "default").is_err());
+
+        config.reset(Field::PythonMinimumVersion);
+        config.reset(Field::Checkpoints);
+        config.reset(Field::Theme);
+        assert_eq!(config.python_minimum_version(), "3.10.0");
+        assert!(!config.checkpoints());
+        assert_eq!(config.get(Field::Theme), "dark");
+    }
+
+    #[test]
+    fn test_layers() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        let user = dir.join("user.yaml");
+        let project = dir.join("project.yaml");
+        // the user's file is from before the key was spelled correctly
+        std::fs::write(&user, "python_minumum_version: 3.11.0\ncheckpoints: true\n").unwrap();
+        std::fs::write(&project, "checkpoints: false\ntheme: light\n").unwrap();
+
+        let mut config =
+            Config::from_files([(Source::User, user), (Source::Project, project)]).unwrap();
+        assert_eq!(config.python_minimum_version(), "3.11.0");
+        assert_eq!(config.source(Field::PythonMinimumVersion), Source::User);
+        assert!(!config.checkpoints());
+        assert_eq!(config.source(Field::Checkpoints), Source::Project);
+        assert_eq!(config.get(Field::Theme), "light");
+        assert_eq!(config.source(Field::GitMinimumVersion), Source::Default);
+
+        config
+            .assign(
+                Field::GitMinimumVersion,
+                "2.30.0",
+                Source::Env(Field::GitMinimumVersion.env()),
+            )
+            .unwrap();
+        assert_eq!(config.git_minimum_version(), "2.30.0");
+        let err = config
+            .assign(Field::CheckAfterEdit, "maybe", Source::Cli)
+            .unwrap_err();
+        assert!(err.to_string().ends_with("(--set flag)"));
+
+        // the overrides are not saved, but a change the user makes is
+        config.set(Field::Theme, "high-contrast").unwrap();
+        let saved = config.saved();
+        assert!(saved.checkpoints());
+        assert_eq!(saved.git_minimum_version(), "2.39.0");
+        assert_eq!(saved.get(Field::Theme), "high-contrast");
+
+        assert_eq!(
+            "python-minimum-version".parse::<Field>().unwrap(),
+            Field::PythonMinimumVersion
+        );
+
+        // a project file from a newer version is rejected
+        let project = dir.join("newer.yaml");
+        std::fs::write(&project, "schema_version: 99\ntheme: light\n").unwrap();
+        assert!(matches!(
+            Config::from_files([(Source::Project, project)]),
+            Err(Error::SchemaTooNew { found: 99, .. })
+        ));
+        assert_eq!(
+            Field::PythonMinimumVersion.env(),
+            "WORKSHOP_PYTHON_MINIMUM_VERSION"
+        );
+        assert!("colour".parse::<Field>().is_err());
     }
 }
@@ -0,0 +1,384 @@
+use crate::languages::programming;
+use std::{
+    fmt,
+    path::{Component, Path, PathBuf},
+};
+
+/// How serious a diagnostic is
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+impl Severity {
+    // parse the severity word printed by a compiler
+    fn parse(word: &str) -> Option<Self> {
+        match word.trim().to_lowercase().as_str() {
+            "error" | "fatal error" => Some(Severity::Error),
+            "warning" => Some(Severity::Warning),
+            "note" | "help" | "info" => Some(Severity::Note),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Note => write!(f, "note"),
+        }
+    }
+}
+
+/// A compiler diagnostic that points at a file in the project
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Diagnostic {
+    /// the path to the file in the project
+    pub path: PathBuf,
+    /// the path relative to the project root, for display
+    pub file: String,
+    /// the line number, starting at 1
+    pub line: usize,
+    /// the column number, starting at 1
+    pub column: Option<usize>,
+    /// how serious the diagnostic is
+    pub severity: Severity,
+    /// the diagnostic message
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)?;
+        if let Some(column) = self.column {
+            write!(f, ":{column}")?;
+        }
+        write!(f, " {}: {}", self.severity, self.message)
+    }
+}
+
+/// The diagnostic formats printed by the tools for a programming language
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Format {
+    /// rustc prints the message and then the location on a `-->` line
+    Rust,
+    /// python tracebacks print the location and then the exception
+    Python,
+    /// tsc prints `file(line,col): error TS1234: message`
+    TypeScript,
+    /// `file:line:col: severity: message` as printed by go, gcc, clang, javac and others
+    Generic,
+}
+
+impl From<programming::Code> for Format {
+    fn from(code: programming::Code) -> Self {
+        match code {
+            programming::Code::rs => Format::Rust,
+            programming::Code::py => Format::Python,
+            programming::Code::ts | programming::Code::js => Format::TypeScript,
+            _ => Format::Generic,
+        }
+    }
+}
+
+/// Scans command output line by line for compiler diagnostics. Locations are mapped back to
+/// files in the project root, even when they are paths inside of a docker container.
+#[derive(Clone, Debug)]
+pub struct Scanner {
+    format: Format,
+    root: PathBuf,
+    /// a rust message waiting for its location
+    message: Option<(Severity, String)>,
+    /// a python location waiting for its exception
+    location: Option<(String, usize)>,
+}
+
+impl Scanner {
+    /// Create a scanner for the programming language of a lesson
+    pub fn new(code: programming::Code, root: &Path) -> Self {
+        Self {
+            format: code.into(),
+            root: root.to_path_buf(),
+            message: None,
+            location: None,
+        }
+    }
+
+    /// Scan a line of output and return the diagnostic it completes, if any
+    pub fn scan(&mut self, line: &str) -> Option<Diagnostic> {
+        let line = clean(line);
+        let line = line.as_str();
+        let found = match self.format {
+            Format::Rust => self.scan_rust(line),
+            Format::Python => self.scan_python(line),
+            Format::TypeScript => scan_typescript(line),
+            Format::Generic => None,
+        };
+        let (file, line, column, severity, message) = found.or_else(|| scan_generic(line))?;
+        let (path, file) = self.resolve(&file)?;
+        Some(Diagnostic {
+            path,
+            file,
+            line,
+            column,
+            severity,
+            message,
+        })
+    }
+
+    fn scan_rust(&mut self, line: &str) -> Option<Found> {
+        let trimmed = line.trim_start();
+        if let Some(location) = trimmed.strip_prefix("--> ") {
+            let (severity, message) = self.message.take()?;
+            let (file, line, column, _) = split_location(location)?;
+            return Some((file.to_string(), line, column, severity, message));
+        }
+
+        // remember the message until its location is printed
+        let (head, message) = line.split_once(": ")?;
+        let word = head.split('[').next().unwrap_or(head);
+        if let Some(severity) = Severity::parse(word) {
+            self.message = Some((severity, message.trim().to_string()));
+        }
+        None
+    }
+
+    fn scan_python(&mut self, line: &str) -> Option<Found> {
+        let trimmed = line.trim();
+        if let Some(rest) = trimmed.strip_prefix("File \"") {
+            let (file, rest) = rest.split_once('"')?;
+            let number = rest.trim_start_matches(',').trim().strip_prefix("line ")?;
+            let number = number.split(',').next()?.trim().parse().ok()?;
+            self.location = Some((file.to_string(), number));
+            return None;
+        }
+
+        // the exception after the traceback, e.g. `NameError: name 'x' is not defined`
+        let (kind, message) = trimmed.split_once(':')?;
+        let severity = if kind.ends_with("Error") || kind.ends_with("Exception") {
+            Severity::Error
+        } else if kind.ends_with("Warning") {
+            Severity::Warning
+        } else {
+            return None;
+        };
+        if kind.contains(char::is_whitespace) {
+            return None;
+        }
+        let (file, line) = self.location.take()?;
+        let message = format!("{kind}:{message}");
+        Some((file, line, None, severity, message))
+    }
+
+    // map a path printed by a tool to a file in the project root, trying ever shorter suffixes
+    // of the path so that paths inside of a container are found too
+    fn resolve(&self, file: &str) -> Option<(PathBuf, String)> {
+        let parts: Vec<Component> = Path::new(file)
+            .components()
+            .filter(|c| matches!(c, Component::Normal(_)))
+            .collect();
+        (0..parts.len()).find_map(|i| {
+            let relative: PathBuf = parts[i..].iter().collect();
+            let path = self.root.join(&relative);
+            path.is_file()
+                .then(|| (path, relative.to_string_lossy().replace('\\', "/")))
+        })
+    }
+}
+
+/// a diagnostic before its path is resolved: file, line, column, severity and message
+type Found = (String, usize, Option<usize>, Severity, String);
+
+fn scan_typescript(line: &str) -> Option<Found> {
+    let trimmed = line.trim();
+    let (file, rest) = trimmed.split_once('(')?;
+    let (position, rest) = rest.split_once("): ")?;
+    let (line, column) = position.split_once(',')?;
+    let (word, message) = rest.split_once(' ')?;
+    if file.contains(char::is_whitespace) {
+        return None;
+    }
+    Some((
+        file.to_string(),
+        line.parse().ok()?,
+        column.parse().ok(),
+        Severity::parse(word)?,
+        message.trim().to_string(),
+    ))
+}
+
+fn scan_generic(line: &str) -> Option<Found> {
+    let (file, line, column, rest) = split_location(line.trim())?;
+
+    // the severity is optional, go only prints the message
+    let rest = rest.trim();
+    let (severity, message) = match rest.split_once(':') {
+        Some((word, message)) => match Severity::parse(word) {
+            Some(severity) => (severity, message.trim()),
+            None => (Severity::Error, rest),
+        },
+        None => match rest.split_once(' ') {
+            Some((word, message)) if Severity::parse(word).is_some() => {
+                (Severity::parse(word)?, message.trim())
+            }
+            _ => (Severity::Error, rest),
+        },
+    };
+    if message.is_empty() {
+        return None;
+    }
+    Some((
+        file.to_string(),
+        line,
+        column,
+        severity,
+        message.to_string(),
+    ))
+}
+
+// split `file:line:col: rest` or `file:line - rest` into its parts
+fn split_location(text: &str) -> Option<(&str, usize, Option<usize>, &str)> {
+    fn number(text: &str) -> Option<(usize, &str)> {
+        let end = text
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(text.len());
+        Some((text[..end].parse().ok()?, &text[end..]))
+    }
+
+    text.match_indices(':').find_map(|(i, _)| {
+        let file = &text[..i];
+        // a file has an extension and no spaces
+        if file.is_empty() || file.contains(char::is_whitespace) || !file.contains('.') {
+            return None;
+        }
+        let (line, rest) = number(&text[i + 1..])?;
+        let (column, rest) = match rest.strip_prefix(':').and_then(number) {
+            Some((column, rest)) => (Some(column), rest),
+            None => (None, rest),
+        };
+        let rest = if rest.is_empty() {
+            rest
+        } else {
+            rest.strip_prefix(':')
+                .or_else(|| rest.strip_prefix(" - "))?
+        };
+        Some((file, line, column, rest))
+    })
+}
+
+// remove the colors and the prefixes that docker adds to each line of output
+fn clean(line: &str) -> String {
+    // strip the ANSI escape sequences
+    let mut text = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            if chars.next() == Some('[') {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            continue;
+        }
+        text.push(c);
+    }
+
+    // docker compose prefixes the service name, e.g. `lesson-1  | `
+    let mut line = text.as_str();
+    if let Some((name, rest)) = line.split_once(" | ") {
+        let name = name.trim();
+        if !name.is_empty() && !name.contains(char::is_whitespace) {
+            line = rest;
+        }
+    }
+
+    // buildkit prefixes the step and the time, e.g. `#12 3.456 `
+    if let Some(rest) = line.strip_prefix('#') {
+        let mut parts = rest.splitn(3, ' ');
+        if let (Some(step), Some(time), Some(rest)) = (parts.next(), parts.next(), parts.next()) {
+            if step.parse::<usize>().is_ok() && time.parse::<f64>().is_ok() {
+                line = rest;
+            }
+        }
+    }
+
+    line.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project() -> tempfile::TempDir {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(root.path().join("src")).unwrap();
+        for file in ["src/main.rs", "main.go", "src/index.ts", "main.py"] {
+            std::fs::write(root.path().join(file), "").unwrap();
+        }
+        root
+    }
+
+    #[test]
+    fn test_scan_formats() {
+        let root = project();
+        let root = root.path();
+
+        // rustc inside of a container, with a buildkit prefix
+        let mut rust = Scanner::new(programming::Code::rs, root);
+        assert_eq!(
+            rust.scan("#12 1.234 error[E0425]: cannot find value `x` in this scope"),
+            None
+        );
+        let d = rust.scan("#12 1.234   --> /app/src/main.rs:3:5").unwrap();
+        assert_eq!(d.path, root.join("src/main.rs"));
+        assert_eq!(d.file, "src/main.rs");
+        assert_eq!((d.line, d.column), (3, Some(5)));
+        assert_eq!(d.severity, Severity::Error);
+        assert_eq!(d.message, "cannot find value `x` in this scope");
+        assert_eq!(
+            d.to_string(),
+            "src/main.rs:3:5 error: cannot find value `x` in this scope"
+        );
+
+        // go without a severity
+        let mut go = Scanner::new(programming::Code::go, root);
+        let d = go
+            .scan("lesson-1  | ./main.go:7:2: undefined: fmt.Printn")
+            .unwrap();
+        assert_eq!((d.file.as_str(), d.line), ("main.go", 7));
+        assert_eq!(d.message, "undefined: fmt.Printn");
+
+        // tsc in both formats
+        let mut ts = Scanner::new(programming::Code::ts, root);
+        let d = ts
+            .scan("src/index.ts(4,10): error TS2304: Cannot find name 'foo'.")
+            .unwrap();
+        assert_eq!((d.line, d.column), (4, Some(10)));
+        assert_eq!(d.message, "TS2304: Cannot find name 'foo'.");
+        let d = ts
+            .scan("\x1b[96msrc/index.ts\x1b[0m:4:10 - error TS2304: Cannot find name 'foo'.")
+            .unwrap();
+        assert_eq!((d.severity, d.column), (Severity::Error, Some(10)));
+
+        // a python traceback
+        let mut py = Scanner::new(programming::Code::py, root);
+        assert_eq!(py.scan("Traceback (most recent call last):"), None);
+        assert_eq!(
+            py.scan("  File \"/usr/src/app/main.py\", line 2, in <module>"),
+            None
+        );
+        assert_eq!(py.scan("    print(x)"), None);
+        let d = py.scan("NameError: name 'x' is not defined").unwrap();
+        assert_eq!((d.file.as_str(), d.line), ("main.py", 2));
+
+        // files outside of the project and plain text are not diagnostics
+        assert_eq!(go.scan("/usr/lib/go/src/fmt/print.go:12:1: oops"), None);
+        assert_eq!(go.scan("Step 3/5 : RUN go build 12:30:45"), None);
+    }
+}
@@ -0,0 +1,419 @@
+use crate::{
+    fs::application::{self, Probe},
+    models::tools,
+    Config,
+};
+use semver::Version;
+use std::fmt;
+use tokio::process::Command;
+
+/// The result of checking one thing the workshops need
+#[derive(Clone, Debug)]
+pub struct Check {
+    /// what was checked, e.g. `Python >= 3.10.0`
+    pub name: String,
+    /// one line per thing that was tried and whether it worked
+    pub results: Vec<(bool, String)>,
+    /// how to fix the problem, if the check failed
+    pub remediation: Option<String>,
+}
+
+impl Check {
+    /// Check if nothing needs fixing
+    pub fn passed(&self) -> bool {
+        self.remediation.is_none()
+    }
+}
+
+/// Everything `workshop doctor` found
+#[derive(Clone, Debug, Default)]
+pub struct Report {
+    /// the checks in the order they ran
+    pub checks: Vec<Check>,
+}
+
+impl Report {
+    /// Check if every check passed
+    pub fn is_healthy(&self) -> bool {
+        self.checks.iter().all(Check::passed)
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for check in &self.checks {
+            writeln!(f, "{}", check.name)?;
+            for (ok, result) in &check.results {
+                writeln!(f, "  {} {result}", if *ok { "✅" } else { "❌" })?;
+            }
+            if let Some(remediation) = &check.remediation {
+                writeln!(f, "  fix: {remediation}")?;
+            }
+            writeln!(f)?;
+        }
+        match self.checks.iter().filter(|check| !check.passed()).count() {
+            0 => writeln!(f, "All checks passed"),
+            1 => writeln!(f, "1 check failed"),
+            failed => writeln!(f, "{failed} checks failed"),
+        }
+    }
+}
+
+// a tool the workshops run and how it is configured
+struct Tool<'a> {
+    name: &'a str,
+    minimum: &'a str,
+    // the config.yaml key that caches the executable
+    key: &'a str,
+    configured: Option<String>,
+    install: String,
+}
+
+// decide whether a tool is usable from its probes. if an executable is configured it is the first
+// probe and it is the one the app will use, so it alone decides the outcome.
+fn evaluate(tool: &Tool, probes: &[Probe]) -> Check {
+    let name = format!("{} >= {}", tool.name, tool.minimum);
+    let Ok(minimum) = Version::parse(tool.minimum) else {
+        return Check {
+            name,
+            results: vec![(false, format!("'{}' is not a version", tool.minimum))],
+            remediation: Some(format!(
+                "Set the minimum {} version in config.yaml to a version like 1.2.3",
+                tool.name
+            )),
+        };
+    };
+
+    let results = probes
+        .iter()
+        .enumerate()
+        .map(|(i, probe)| {
+            let configured = if i == 0 && tool.configured.is_some() {
+                " (configured)"
+            } else {
+                ""
+            };
+            let outcome = match &probe.version {
+                Ok(version) if *version >= minimum => version.to_string(),
+                Ok(version) => format!("{version} is older than {minimum}"),
+                Err(reason) => reason.clone(),
+            };
+            (
+                probe.meets(&minimum),
+                format!("{}{configured}: {outcome}", probe.command),
+            )
+        })
+        .collect();
+
+    let remediation = match &tool.configured {
+        Some(configured) if !probes.first().is_some_and(|probe| probe.meets(&minimum)) => {
+            Some(format!(
+                "Remove `{}: {configured}` from config.yaml so {} is detected again, or set it \
+                 to {} {minimum} or newer",
+                tool.key, tool.name, tool.name
+            ))
+        }
+        Some(_) => None,
+        None if probes.iter().any(|probe| probe.meets(&minimum)) => None,
+        None => Some(tool.install.clone()),
+    };
+
+    Check {
+        name,
+        results,
+        remediation,
+    }
+}
+
+// probe the configured executable, then every candidate that is not the configured one
+async fn probe_all(configured: Option<&str>, candidates: Vec<String>, args: &[&str]) -> Vec<Probe> {
+    let mut probes = Vec::new();
+    if let Some(configured) = configured {
+        probes.push(application::probe(configured, args).await);
+    }
+    for candidate in candidates {
+        if Some(candidate.as_str()) != configured {
+            probes.push(application::probe(&candidate, args).await);
+        }
+    }
+    probes
+}
+
+// run a command and get its output, or the reason it failed
+async fn output(executable: &str, args: &[&str]) -> Result<String, String> {
+    match Command::new(executable).args(args).output().await {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            Err(format!("{executable} not found"))
+        }
+        Err(e) => Err(e.to_string()),
+        Ok(output) if output.status.success() => {
+            Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        }
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            Err(stderr
+                .lines()
+                .map(str::trim)
+                .find(|line| !line.is_empty())
+                .map(String::from)
+                .unwrap_or_else(|| format!("exited with {}", output.status)))
+        }
+    }
+}
+
+// how to get the Docker daemon running
+fn start_docker(reason: &str) -> String {
+    if reason.ends_with("not found") {
+        "Install Docker from https://docs.docker.com/get-docker/".to_string()
+    } else if reason.to_lowercase().contains("permission denied") {
+        "Add your user to the docker group with `sudo usermod -aG docker $USER`, then log out and \
+         back in"
+            .to_string()
+    } else if cfg!(target_os = "linux") {
+        "Start the Docker daemon with `sudo systemctl start docker`".to_string()
+    } else {
+        "Start Docker Desktop and wait until it reports that Docker is running".to_string()
+    }
+}
+
+/// Check the tools and the Docker daemon that the workshops need
+pub async fn run(config: &Config) -> Report {
+    let mut report = Report::default();
+
+    // python
+    let tool = Tool {
+        name: "Python",
+        minimum: config.python_minimum_version(),
+        key: "python_executable",
+        configured: config.python_executable(),
+        install: format!(
+            "Install Python {} or newer from https://www.python.org/downloads/ and make sure it \
+             is on your PATH",
+            config.python_minimum_version()
+        ),
+    };
+    let probes = probe_all(
+        tool.configured.as_deref(),
+        application::python_candidates(),
+        &["--version"],
+    )
+    .await;
+    report.checks.push(evaluate(&tool, &probes));
+
+    // docker compose, either the docker plugin or the standalone executable
+    let tool = Tool {
+        name: "Docker Compose",
+        minimum: config.docker_compose_minimum_version(),
+        key: "docker_compose_executable",
+        configured: config.docker_compose_executable(),
+        install: format!(
+            "Install Docker Compose {} or newer, it comes with Docker Desktop or as the compose \
+             plugin: https://docs.docker.com/compose/install/",
+            config.docker_compose_minimum_version()
+        ),
+    };
+    // the configured executable is probed the way the app runs it, as the docker plugin or as
+    // the standalone docker-compose
+    let configured = tool.configured.as_deref();
+    let mut probes = Vec::new();
+    if let Some(configured) = configured {
+        probes.push(application::probe_executable(tools::Tool::Docker, configured).await);
+    }
+    let others = |candidates: Vec<String>| -> Vec<String> {
+        candidates
+            .into_iter()
+            .filter(|candidate| Some(candidate.as_str()) != configured)
+            .collect()
+    };
+    probes.extend(
+        probe_all(
+            None,
+            others(application::docker_candidates()),
+            &["compose", "version"],
+        )
+        .await,
+    );
+    probes.extend(
+        probe_all(
+            None,
+            others(application::docker_compose_candidates()),
+            &["--version"],
+        )
+        .await,
+    );
+    report.checks.push(evaluate(&tool, &probes));
+
+    // the docker executable that talks to the daemon is the first one with a compose plugin
+    let docker = probes
+        .iter()
+        .find(|probe| probe.version.is_ok() && probe.command.ends_with("compose version"))
+        .map(|probe| probe.executable.clone())
+        .unwrap_or_else(|| "docker".to_string());
+
+    // git
+    let tool = Tool {
+        name: "Git",
+        minimum: config.git_minimum_version(),
+        key: "git_executable",
+        configured: config.git_executable(),
+        install: format!(
+            "Install git {} or newer from https://git-scm.com/downloads",
+            config.git_minimum_version()
+        ),
+    };
+    let probes = probe_all(
+        tool.configured.as_deref(),
+        application::git_candidates(),
+        &["--version"],
+    )
+    .await;
+    report.checks.push(evaluate(&tool, &probes));
+
+    // the docker daemon
+    let info = output(&docker, &["info", "--format", "{{.ServerVersion}}"]).await;
+    let reachable = info.is_ok();
+    report.checks.push(match info {
+        Ok(version) => Check {
+            name: "Docker daemon".to_string(),
+            results: vec![(true, format!("{docker} info: server version {version}"))],
+            remediation: None,
+        },
+        Err(reason) => Check {
+            name: "Docker daemon".to_string(),
+            remediation: Some(start_docker(&reason)),
+            results: vec![(false, format!("{docker} info: {reason}"))],
+        },
+    });
+
+    // a network like the ones the workshops' compose files create, only if the daemon answers
+    if reachable {
+        let network = format!("workshop-doctor-{}", std::process::id());
+        let mut check = Check {
+            name: "Docker network".to_string(),
+            results: Vec::new(),
+            remediation: None,
+        };
+        match output(&docker, &["network", "create", &network]).await {
+            Ok(_) => {
+                check
+                    .results
+                    .push((true, format!("{docker} network create {network}")));
+                match output(&docker, &["network", "rm", &network]).await {
+                    Ok(_) => check
+                        .results
+                        .push((true, format!("{docker} network rm {network}"))),
+                    Err(reason) => {
+                        check
+                            .results
+                            .push((false, format!("{docker} network rm {network}: {reason}")));
+                        check.remediation = Some(format!(
+                            "Remove the test network with `{docker} network rm {network}`"
+                        ));
+                    }
+                }
+            }
+            Err(reason) => {
+                check.results.push((
+                    false,
+                    format!("{docker} network create {network}: {reason}"),
+                ));
+                check.remediation = Some(format!(
+                    "Remove unused networks with `{docker} network prune`, or add address pools \
+                     to `default-address-pools` in the Docker daemon settings"
+                ));
+            }
+        }
+        report.checks.push(check);
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn probe(executable: &str, version: Result<&str, &str>) -> Probe {
+        Probe {
+            executable: executable.to_string(),
+            command: format!("{executable} --version"),
+            version: version
+                .map(|v| Version::parse(v).unwrap())
+                .map_err(String::from),
+        }
+    }
+
+    fn python(configured: Option<&str>) -> Tool<'static> {
+        Tool {
+            name: "Python",
+            minimum: "3.10.0",
+            key: "python_executable",
+            configured: configured.map(String::from),
+            install: "Install Python".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_evaluate() {
+        // any candidate that is new enough will do
+        let probes = [
+            probe("python3", Ok("3.9.6")),
+            probe("python", Err("not found")),
+            probe("/usr/bin/python3", Ok("3.12.3")),
+        ];
+        let check = evaluate(&python(None), &probes);
+        assert!(check.passed());
+        assert_eq!(
+            check.results,
+            vec![
+                (
+                    false,
+                    "python3 --version: 3.9.6 is older than 3.10.0".to_string()
+                ),
+                (false, "python --version: not found".to_string()),
+                (true, "/usr/bin/python3 --version: 3.12.3".to_string()),
+            ]
+        );
+
+        // nothing new enough means installing python
+        let check = evaluate(&python(None), &probes[..2]);
+        assert_eq!(check.remediation.as_deref(), Some("Install Python"));
+
+        // a configured executable that is too old fails even if another candidate would work
+        let check = evaluate(&python(Some("python3")), &probes);
+        assert!(check
+            .remediation
+            .unwrap()
+            .contains("python_executable: python3"));
+        assert!(check.results[0]
+            .1
+            .starts_with("python3 --version (configured)"));
+
+        let mut tool = python(None);
+        tool.minimum = "three";
+        assert!(!evaluate(&tool, &probes).passed());
+    }
+
+    #[test]
+    fn test_report() {
+        let mut report = Report {
+            checks: vec![Check {
+                name: "Docker daemon".to_string(),
+                results: vec![(true, "docker info: server version 27.1.1".to_string())],
+                remediation: None,
+            }],
+        };
+        assert!(report.is_healthy());
+        assert!(report.to_string().ends_with("All checks passed\n"));
+
+        report.checks.push(Check {
+            name: "Docker network".to_string(),
+            results: vec![(false, "docker network create x: pool exhausted".to_string())],
+            remediation: Some("Prune".to_string()),
+        });
+        assert!(!report.is_healthy());
+        let text = report.to_string();
+        assert!(text.contains("  ❌ docker network create x: pool exhausted\n  fix: Prune\n"));
+        assert!(text.ends_with("1 check failed\n"));
+    }
+}
@@ -0,0 +1,79 @@
+use std::path::Path;
+use tokio::process::Command;
+
+/// Build the command that opens a file in the user's editor at the given line and column. The
+/// editor comes from `$VISUAL` or `$EDITOR` and may include arguments, e.g. `code --wait`.
+pub fn command(path: &Path, line: Option<usize>, column: Option<usize>) -> Command {
+    let editor = std::env::var("VISUAL")
+        .ok()
+        .filter(|e| !e.trim().is_empty())
+        .or_else(|| std::env::var("EDITOR").ok())
+        .filter(|e| !e.trim().is_empty())
+        .unwrap_or_else(|| default_editor().to_string());
+
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or(default_editor());
+    let mut command = Command::new(program);
+    command.args(parts);
+    command.args(arguments(program, path, line, column));
+    command
+}
+
+fn default_editor() -> &'static str {
+    if cfg!(windows) {
+        "notepad"
+    } else {
+        "vi"
+    }
+}
+
+// the arguments that open the path at the position, which differ between editors
+fn arguments(
+    program: &str,
+    path: &Path,
+    line: Option<usize>,
+    column: Option<usize>,
+) -> Vec<String> {
+    let path = path.display().to_string();
+    let Some(line) = line else {
+        return vec![path];
+    };
+    let column = column.unwrap_or(1);
+    let name = Path::new(program)
+        .file_stem()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match name.as_str() {
+        "code" | "code-insiders" | "codium" => {
+            vec!["--goto".to_string(), format!("{path}:{line}:{column}")]
+        }
+        "subl" | "zed" | "hx" | "helix" => vec![format!("{path}:{line}:{column}")],
+        "nano" | "micro" => vec![format!("+{line},{column}"), path],
+        "notepad" => vec![path],
+        // vi, vim, nvim, emacs, kak and most others take +line
+        _ => vec![format!("+{line}"), path],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arguments() {
+        let path = Path::new("/p/src/main.rs");
+        assert_eq!(
+            arguments("vim", path, Some(3), Some(5)),
+            ["+3", "/p/src/main.rs"]
+        );
+        assert_eq!(
+            arguments("/usr/bin/code", path, Some(3), Some(5)),
+            ["--goto", "/p/src/main.rs:3:5"]
+        );
+        assert_eq!(
+            arguments("nano", path, Some(3), None),
+            ["+3,1", "/p/src/main.rs"]
+        );
+        assert_eq!(arguments("vim", path, None, None), ["/p/src/main.rs"]);
+    }
+}
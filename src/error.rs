@@ -38,6 +38,10 @@ pub enum Error {
     #[error("TUI error: {0}")]
     Tui(String),
 
+    /// Invalid key binding
+    #[error("Invalid key binding: {0}")]
+    InvalidKey(String),
+
     /// Project directories error
     #[error("Project directories error: {0}")]
     ProjectDirs(String),
@@ -46,6 +50,33 @@ pub enum Error {
     #[error("Command error: {0}")]
     Command(String),
 
+    /// Command not found
+    #[error("Command not found: {0}")]
+    CommandNotFound(String),
+
+    /// Invalid setting
+    #[error("Invalid setting: {0}")]
+    InvalidSetting(String),
+
+    /// Invalid log option
+    #[error("Invalid log option: {0}")]
+    InvalidLogOption(String),
+
+    /// A persisted file has a schema version that cannot be read
+    #[error("Invalid schema: {0}")]
+    InvalidSchema(String),
+
+    /// A persisted file was written by a newer version of the app
+    #[error(
+        "{file} was written by a newer version of workshop (schema version {found}, this version \
+         reads up to {supported}), upgrade workshop to use it"
+    )]
+    SchemaTooNew {
+        file: String,
+        found: u64,
+        supported: u64,
+    },
+
     /// Initial events failed
     #[error("Initial events failed")]
     InitialEvents,
@@ -1,3 +1,5 @@
+pub mod atomic;
+
 pub mod error;
 pub use error::Error;
 
@@ -6,3 +8,12 @@ pub use lazy_loader::{LazyLoader, TryLoad};
 
 pub mod utils;
 pub use utils::{application, workshops};
+
+pub mod watch;
+pub use watch::Watcher;
+
+pub mod lock;
+
+pub mod schema;
+
+pub mod starter;
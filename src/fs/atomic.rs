@@ -0,0 +1,135 @@
+use crate::Error;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    ffi::OsString,
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+};
+use tracing::warn;
+
+/// Get the path of the backup that holds the previous version of a file, e.g. `status.yaml.bak`
+pub fn backup_path(path: &Path) -> PathBuf {
+    with_suffix(path, ".bak")
+}
+
+// the path with a suffix added to the file name
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().map(OsString::from).unwrap_or_default();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+// write the contents to a temporary file next to the path, flush it to disk and move it into place
+fn replace(path: &Path, contents: &[u8]) -> Result<(), Error> {
+    let temp = with_suffix(path, &format!(".tmp-{}", std::process::id()));
+    let written = File::create(&temp).and_then(|mut file| {
+        file.write_all(contents)?;
+        file.sync_all()
+    });
+    if let Err(e) = written.and_then(|_| std::fs::rename(&temp, path)) {
+        let _ = std::fs::remove_file(&temp);
+        return Err(e.into());
+    }
+
+    // make the rename itself durable
+    #[cfg(unix)]
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        File::open(parent)?.sync_all()?;
+    }
+    Ok(())
+}
+
+/// Write a file so that a crash leaves either the old or the new contents, never a partial file.
+/// The previous contents are kept in the backup file.
+pub fn write(path: &Path, contents: &[u8]) -> Result<(), Error> {
+    if path.is_file() {
+        replace(&backup_path(path), &std::fs::read(path)?)?;
+    }
+    replace(path, contents)
+}
+
+/// Serialize a value to a YAML file with [`write`]
+pub fn write_yaml<T: Serialize>(path: &Path, value: &T) -> Result<(), Error> {
+    write(path, serde_yaml::to_string(value)?.as_bytes())
+}
+
+/// Read a YAML file. If the file is empty or does not parse, the backup is used instead and the
+/// file is restored from it.
+pub fn read_yaml<T: DeserializeOwned>(path: &Path) -> Result<T, Error> {
+    let contents = std::fs::read_to_string(path)?;
+    let parsed = serde_yaml::from_str::<T>(&contents);
+    if parsed.is_ok() && !contents.trim().is_empty() {
+        return Ok(parsed?);
+    }
+
+    let backup = backup_path(path);
+    let restored = std::fs::read_to_string(&backup)
+        .ok()
+        .filter(|contents| !contents.trim().is_empty())
+        .and_then(|contents| {
+            let value = serde_yaml::from_str::<T>(&contents).ok()?;
+            Some((contents, value))
+        });
+    if let Some((backup_contents, value)) = restored {
+        warn!(
+            "{} is damaged, restoring it from {}",
+            path.display(),
+            backup.display()
+        );
+        replace(path, backup_contents.as_bytes())?;
+        return Ok(value);
+    }
+    Ok(parsed?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_write_and_recover() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        let path = dir.join("status.yaml");
+
+        let first = BTreeMap::from([("lesson".to_string(), "01".to_string())]);
+        let second = BTreeMap::from([("lesson".to_string(), "02".to_string())]);
+        write_yaml(&path, &first).unwrap();
+        assert!(!backup_path(&path).exists());
+        write_yaml(&path, &second).unwrap();
+        assert_eq!(
+            read_yaml::<BTreeMap<String, String>>(&path).unwrap(),
+            second
+        );
+        assert_eq!(
+            read_yaml::<BTreeMap<String, String>>(&backup_path(&path)).unwrap(),
+            first
+        );
+
+        // a file truncated by a crash is restored from the backup
+        std::fs::write(&path, "").unwrap();
+        assert_eq!(read_yaml::<BTreeMap<String, String>>(&path).unwrap(), first);
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            std::fs::read_to_string(backup_path(&path)).unwrap()
+        );
+
+        // without a usable backup the error is reported
+        std::fs::write(&path, "lesson: [").unwrap();
+        std::fs::remove_file(backup_path(&path)).unwrap();
+        assert!(read_yaml::<BTreeMap<String, String>>(&path).is_err());
+
+        // no temporary files are left behind
+        let mut names: Vec<_> = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec![OsString::from("status.yaml")]);
+    }
+}
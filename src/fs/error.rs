@@ -21,4 +21,8 @@ pub enum Error {
     /// No Git executable found
     #[error("No Git executable found")]
     NoGitExecutable,
+
+    /// Watching the project files failed
+    #[error("File watching error: {0}")]
+    Watch(#[from] notify::Error),
 }
@@ -0,0 +1,134 @@
+use crate::Error;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    fs::{File, OpenOptions, TryLockError},
+    io::{Read, Seek, Write},
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tracing::{info, warn};
+
+/// The name of the lock file in the `.workshops` directory
+pub const LOCK_FILE: &str = "workshop.lock";
+
+/// The instance of the app that holds the lock, as written in the lock file
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Owner {
+    /// the process id
+    pub pid: u32,
+    /// when it took the lock, in seconds since the Unix epoch
+    pub since: u64,
+}
+
+impl Owner {
+    // this process
+    fn current() -> Self {
+        Self {
+            pid: std::process::id(),
+            since: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl fmt::Display for Owner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let minutes = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|now| now.as_secs().saturating_sub(self.since) / 60)
+            .unwrap_or_default();
+        match minutes {
+            0 => write!(f, "process {}, started just now", self.pid),
+            1 => write!(f, "process {}, started a minute ago", self.pid),
+            minutes => write!(f, "process {}, started {minutes} minutes ago", self.pid),
+        }
+    }
+}
+
+/// An advisory lock on a `.workshops` directory. The operating system releases it if the app
+/// crashes, so a lock file that is left behind is stale and is taken over.
+#[derive(Debug)]
+pub struct Lock {
+    file: File,
+}
+
+impl Drop for Lock {
+    fn drop(&mut self) {
+        // the lock file is emptied instead of deleted, because another instance may have it open
+        // and be about to lock it. An empty lock file was released cleanly.
+        let _ = self.file.set_len(0);
+        let _ = self.file.unlock();
+    }
+}
+
+/// The outcome of trying to lock a `.workshops` directory
+#[derive(Debug)]
+pub enum Acquired {
+    /// this instance owns the state until the lock is dropped
+    Locked(Lock),
+    /// another instance owns the state, if the lock file says which one
+    Busy(Option<Owner>),
+}
+
+/// Try to lock a `.workshops` directory. If another instance holds the lock, this instance is
+/// read-only until it gets the lock.
+pub fn acquire(dir: &Path) -> Result<Acquired, Error> {
+    let path = dir.join(LOCK_FILE);
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(&path)?;
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)?;
+    let previous = serde_yaml::from_str::<Owner>(&contents).ok();
+
+    match file.try_lock() {
+        Ok(()) => {}
+        Err(TryLockError::WouldBlock) => return Ok(Acquired::Busy(previous)),
+        // file systems without locks cannot protect the state, so carry on without it
+        Err(TryLockError::Error(e)) => warn!("Cannot lock {}: {}", path.display(), e),
+    }
+    if let Some(previous) = previous {
+        warn!("Taking over the stale lock left by workshop {}", previous);
+    }
+
+    let owner = Owner::current();
+    file.set_len(0)?;
+    file.rewind()?;
+    file.write_all(serde_yaml::to_string(&owner)?.as_bytes())?;
+    file.sync_all()?;
+    info!("Locked {}", dir.display());
+    Ok(Acquired::Locked(Lock { file }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_acquire() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+
+        // a lock file without a lock is stale
+        std::fs::write(dir.join(LOCK_FILE), "pid: 1\nsince: 0\n").unwrap();
+        let Acquired::Locked(lock) = acquire(dir).unwrap() else {
+            panic!("the stale lock was not taken over");
+        };
+
+        let Acquired::Busy(Some(owner)) = acquire(dir).unwrap() else {
+            panic!("the lock was taken twice");
+        };
+        assert_eq!(owner.pid, std::process::id());
+
+        // the lock file is kept but emptied, so the next lock is not mistaken for a stale one
+        drop(lock);
+        assert_eq!(std::fs::read_to_string(dir.join(LOCK_FILE)).unwrap(), "");
+        assert!(matches!(acquire(dir).unwrap(), Acquired::Locked(_)));
+    }
+}
@@ -0,0 +1,156 @@
+use crate::{fs::atomic, Error};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_yaml::{Mapping, Value};
+use std::path::Path;
+
+/// The key that holds the schema version in every persisted file
+pub const KEY: &str = "schema_version";
+
+/// Upgrades the contents of a file from one schema version to the next
+pub type Migration = fn(&mut Mapping);
+
+/// A type that is persisted to a YAML file with a schema version
+pub trait Versioned: Serialize + DeserializeOwned {
+    /// the name of the file, for messages
+    const FILE: &'static str;
+
+    /// the migrations in order. Files without a schema version are version 0, and the migration
+    /// at index `n` upgrades version `n` to `n + 1`, so the current version is the number of
+    /// migrations.
+    const MIGRATIONS: &'static [Migration];
+
+    /// Get the current schema version
+    fn version() -> u64 {
+        Self::MIGRATIONS.len() as u64
+    }
+}
+
+/// The first migration for files that did not change when versioning was added
+pub fn unversioned(_: &mut Mapping) {}
+
+/// Upgrade the contents of a file to the current schema version, without the version key.
+/// Files written by a newer version of the app are rejected.
+pub fn migrate<T: Versioned>(value: Value) -> Result<Mapping, Error> {
+    let mut mapping = match value {
+        Value::Mapping(mapping) => mapping,
+        Value::Null => Mapping::new(),
+        _ => {
+            return Err(Error::InvalidSchema(format!(
+                "{} does not hold a mapping",
+                T::FILE
+            )))
+        }
+    };
+    let found = match mapping.remove(KEY) {
+        None => 0,
+        Some(version) => version.as_u64().ok_or_else(|| {
+            Error::InvalidSchema(format!(
+                "{} has {KEY} {version:?}, which is not a number",
+                T::FILE
+            ))
+        })?,
+    };
+    if found > T::version() {
+        return Err(Error::SchemaTooNew {
+            file: T::FILE.to_string(),
+            found,
+            supported: T::version(),
+        });
+    }
+    for migration in &T::MIGRATIONS[found as usize..] {
+        migration(&mut mapping);
+    }
+    Ok(mapping)
+}
+
+/// Read a file, upgrading it to the current schema version
+pub fn read<T: Versioned>(path: &Path) -> Result<T, Error> {
+    let mapping = migrate::<T>(atomic::read_yaml(path)?)?;
+    Ok(serde_yaml::from_value(Value::Mapping(mapping))?)
+}
+
+/// Write a file with the current schema version as its first key
+pub fn write<T: Versioned>(path: &Path, value: &T) -> Result<(), Error> {
+    let mut mapping = Mapping::new();
+    mapping.insert(KEY.into(), T::version().into());
+    if let Value::Mapping(values) = serde_yaml::to_value(value)? {
+        mapping.extend(values);
+    }
+    atomic::write_yaml(path, &mapping)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct Lesson {
+        title: String,
+        #[serde(default)]
+        minutes: u32,
+    }
+
+    // version 2 renamed `name` to `title`
+    fn rename_name(mapping: &mut Mapping) {
+        if let Some(name) = mapping.remove("name") {
+            mapping.insert("title".into(), name);
+        }
+    }
+
+    impl Versioned for Lesson {
+        const FILE: &'static str = "lesson.yaml";
+        const MIGRATIONS: &'static [Migration] = &[unversioned, rename_name];
+    }
+
+    #[test]
+    fn test_migrate() {
+        // a file from before versioning goes through every migration
+        let value = serde_yaml::from_str("name: Intro\nminutes: 5").unwrap();
+        let mapping = migrate::<Lesson>(value).unwrap();
+        assert_eq!(
+            serde_yaml::from_value::<Lesson>(Value::Mapping(mapping)).unwrap(),
+            Lesson {
+                title: "Intro".to_string(),
+                minutes: 5
+            }
+        );
+
+        // a current file is left alone
+        let value = serde_yaml::from_str("schema_version: 2\ntitle: Intro\nname: x").unwrap();
+        let mapping = migrate::<Lesson>(value).unwrap();
+        assert_eq!(mapping.get("name"), Some(&Value::from("x")));
+        assert!(!mapping.contains_key(KEY));
+
+        let value = serde_yaml::from_str("schema_version: 3\ntitle: Intro").unwrap();
+        let err = migrate::<Lesson>(value).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::SchemaTooNew {
+                found: 3,
+                supported: 2,
+                ..
+            }
+        ));
+        assert!(err.to_string().contains("newer version"));
+
+        let value = serde_yaml::from_str("schema_version: two").unwrap();
+        assert!(migrate::<Lesson>(value).is_err());
+    }
+
+    #[test]
+    fn test_write_and_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        let path = dir.join("lesson.yaml");
+        let lesson = Lesson {
+            title: "Intro".to_string(),
+            minutes: 5,
+        };
+        write(&path, &lesson).unwrap();
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .starts_with("schema_version: 2\n"));
+        assert_eq!(read::<Lesson>(&path).unwrap(), lesson);
+    }
+}
@@ -0,0 +1,146 @@
+use crate::Error;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// The name of the directory in a lesson that holds its starter code
+pub const STARTER_DIR: &str = "starter";
+
+/// The result of copying a starter tree into the project
+#[derive(Clone, Debug, Default)]
+pub struct Overlay {
+    /// the files that were copied, relative to the project root
+    pub copied: Vec<PathBuf>,
+    /// the files the project already has with different contents, relative to the project root
+    pub conflicts: Vec<PathBuf>,
+}
+
+/// List the files in a starter tree, relative to its root and in sorted order. Symlinked
+/// directories are skipped so that a link to a parent directory cannot loop.
+pub fn files(starter: &Path) -> Result<Vec<PathBuf>, Error> {
+    let mut files = Vec::new();
+    let mut dirs = vec![starter.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            if entry.file_type()?.is_dir() {
+                dirs.push(path);
+            } else if path.is_dir() {
+                debug!("Skipping symlinked starter directory: {}", path.display());
+            } else if let Ok(relative) = path.strip_prefix(starter) {
+                files.push(relative.to_path_buf());
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Copy the starter files into the project. Files that the project already has with different
+/// contents are overwritten if `overwrite` is set, otherwise they are kept and reported as
+/// conflicts.
+pub fn copy(starter: &Path, project_root: &Path, overwrite: bool) -> Result<Overlay, Error> {
+    let mut overlay = Overlay::default();
+    for file in files(starter)? {
+        let source = starter.join(&file);
+        let target = project_root.join(&file);
+        if target.exists() {
+            if std::fs::read(&source)? == std::fs::read(&target)? {
+                continue;
+            }
+            if !overwrite {
+                overlay.conflicts.push(file);
+                continue;
+            }
+        }
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::copy(&source, &target)?;
+        overlay.copied.push(file);
+    }
+    Ok(overlay)
+}
+
+/// Copy the project's current versions of the starter files into the backup directory and return
+/// the files that were backed up
+pub fn backup(
+    starter: &Path,
+    project_root: &Path,
+    backup_dir: &Path,
+) -> Result<Vec<PathBuf>, Error> {
+    let mut backed_up = Vec::new();
+    for file in files(starter)? {
+        let source = project_root.join(&file);
+        if !source.is_file() {
+            continue;
+        }
+        let target = backup_dir.join(&file);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::copy(&source, &target)?;
+        backed_up.push(file);
+    }
+    Ok(backed_up)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_copy_and_backup() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        let starter = root.join("lesson").join(STARTER_DIR);
+        let project = root.join("project");
+        std::fs::create_dir_all(starter.join("src")).unwrap();
+        std::fs::create_dir_all(&project).unwrap();
+        std::fs::write(starter.join("Cargo.toml"), "[package]").unwrap();
+        std::fs::write(starter.join("src/main.rs"), "fn main() {}").unwrap();
+        std::fs::write(project.join("Cargo.toml"), "[package]").unwrap();
+
+        // identical files are neither copied nor conflicts
+        let overlay = copy(&starter, &project, false).unwrap();
+        assert_eq!(overlay.copied, vec![PathBuf::from("src/main.rs")]);
+        assert!(overlay.conflicts.is_empty());
+
+        // the user's changes are kept unless overwriting
+        std::fs::write(project.join("src/main.rs"), "fn main() { todo!() }").unwrap();
+        let overlay = copy(&starter, &project, false).unwrap();
+        assert!(overlay.copied.is_empty());
+        assert_eq!(overlay.conflicts, vec![PathBuf::from("src/main.rs")]);
+
+        let backups = root.join("backups");
+        let backed_up = backup(&starter, &project, &backups).unwrap();
+        assert_eq!(backed_up.len(), 2);
+        assert_eq!(
+            std::fs::read_to_string(backups.join("src/main.rs")).unwrap(),
+            "fn main() { todo!() }"
+        );
+
+        let overlay = copy(&starter, &project, true).unwrap();
+        assert_eq!(overlay.copied, vec![PathBuf::from("src/main.rs")]);
+        assert_eq!(
+            std::fs::read_to_string(project.join("src/main.rs")).unwrap(),
+            "fn main() {}"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_files_skips_symlinked_dirs() {
+        let root = tempfile::tempdir().unwrap();
+        let starter = root.path().join(STARTER_DIR);
+        std::fs::create_dir_all(starter.join("src")).unwrap();
+        std::fs::write(starter.join("src/main.rs"), "fn main() {}").unwrap();
+        std::os::unix::fs::symlink(&starter, starter.join("src/loop")).unwrap();
+        std::os::unix::fs::symlink(starter.join("src/main.rs"), starter.join("main.rs")).unwrap();
+
+        assert_eq!(
+            files(&starter).unwrap(),
+            vec![PathBuf::from("main.rs"), PathBuf::from("src/main.rs")]
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{
     fs,
     languages::{programming, spoken},
-    models::workshop,
+    models::{tools::Tool, workshop},
     Error,
 };
 use semver::Version;
@@ -17,18 +17,103 @@ const APPLICATION_PARTS: [&str; 3] = ["io", "libp2p", "workshop"];
 pub mod application {
     use super::*;
 
-    /// Try to get the path to the python executable
-    pub async fn find_python_executable<S: AsRef<str>>(min_version: S) -> Result<String, Error> {
-        // parse the python version from the --version output
-        fn parse_version(output: &str) -> Option<Version> {
-            let version_str = output.rsplit_once(' ')?.1.trim();
-            Version::parse(version_str).ok()
+    /// The outcome of running a candidate executable to get its version
+    #[derive(Clone, Debug)]
+    pub struct Probe {
+        /// the executable that was run
+        pub executable: String,
+        /// the full command that was run, e.g. `docker compose version`
+        pub command: String,
+        /// the version it reported, or why there is none
+        pub version: Result<Version, String>,
+    }
+
+    impl Probe {
+        /// Check if the candidate ran and reported at least the minimum version
+        pub fn meets(&self, min_version: &Version) -> bool {
+            matches!(&self.version, Ok(version) if version >= min_version)
         }
+    }
 
-        let min_version =
-            Version::parse(min_version.as_ref()).map_err(|_| fs::Error::NoPythonExecutable)?;
+    /// Parse the first version number in the output of a `--version` command. This accepts the
+    /// `v` prefix and the suffixes that tools add, e.g. `v2.36.2`, `2.39.3 (Apple Git-146)`,
+    /// `2.41.0.windows.1` and `3.13.0rc1`.
+    pub fn parse_version(output: &str) -> Option<Version> {
+        output.split_whitespace().find_map(|word| {
+            let word = word.trim_start_matches('v');
+            let numeric: String = word
+                .chars()
+                .take_while(|c| c.is_ascii_digit() || *c == '.')
+                .collect();
+            let mut parts = numeric
+                .split('.')
+                .take(3)
+                .map(|part| part.parse::<u64>().ok());
+            let major = parts.next()??;
+            let minor = parts.next()??;
+            let patch = parts.next().flatten().unwrap_or(0);
+            Some(Version::new(major, minor, patch))
+        })
+    }
 
+    /// Run a candidate executable with the arguments that print its version
+    pub async fn probe(executable: &str, args: &[&str]) -> Probe {
+        let command = std::iter::once(executable)
+            .chain(args.iter().copied())
+            .collect::<Vec<_>>()
+            .join(" ");
+        debug!("Probing: {}", command);
+        let version = match Command::new(executable).args(args).output().await {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Err("not found".to_string()),
+            Err(e) => Err(e.to_string()),
+            Ok(output) if !output.status.success() => {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                Err(stderr
+                    .lines()
+                    .map(str::trim)
+                    .find(|line| !line.is_empty())
+                    .map(String::from)
+                    .unwrap_or_else(|| format!("exited with {}", output.status)))
+            }
+            Ok(output) => {
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                parse_version(&stdout)
+                    .ok_or_else(|| format!("could not parse a version from '{}'", stdout.trim()))
+            }
+        };
+        Probe {
+            executable: executable.to_string(),
+            command,
+            version,
+        }
+    }
+
+    // expand the home directory and user name in the platform-specific candidates
+    fn expand(candidates: Vec<&str>) -> Vec<String> {
+        candidates
+            .into_iter()
+            .map(|candidate| {
+                // On Windows, replace %USERNAME% with actual username
+                #[cfg(target_os = "windows")]
+                let candidate =
+                    candidate.replace("%USERNAME%", &std::env::var("USERNAME").unwrap_or_default());
+
+                // Expand tilde (~) for home directory on Unix-like systems
+                #[cfg(any(target_os = "macos", target_os = "linux"))]
+                let candidate = shellexpand::tilde(candidate).to_string();
+
+                #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+                let candidate = candidate.to_string();
+
+                candidate
+            })
+            .collect()
+    }
+
+    /// The Python executables to try, in order
+    pub fn python_candidates() -> Vec<String> {
         // Common Python executable names
+        #[allow(unused_mut)]
         let mut candidates = vec!["python3", "python", "py"];
 
         // Platform-specific candidates
@@ -69,101 +154,13 @@ pub mod application {
             ]);
         }
 
-        // Try each candidate
-        for candidate in candidates.iter() {
-            // On Windows, replace %USERNAME% with actual username
-            #[cfg(target_os = "windows")]
-            let candidate =
-                candidate.replace("%USERNAME%", &std::env::var("USERNAME").unwrap_or_default());
-
-            // Expand tilde (~) for home directory on Unix-like systems
-            #[cfg(any(target_os = "macos", target_os = "linux"))]
-            let candidate = shellexpand::tilde(candidate).to_string();
-
-            // Check if the executable exists and is runnable
-            debug!("Checking Python candidate: {}", candidate);
-            let output = Command::new(&candidate).arg("--version").output().await;
-
-            if let Ok(output) = output {
-                if output.status.success() {
-                    // Verify it's a Python executable by checking version output
-                    let version_output = String::from_utf8_lossy(&output.stdout);
-                    if let Some(version) = parse_version(&version_output) {
-                        if version >= min_version {
-                            info!(
-                                "v Found Python executable: {} (version: {})",
-                                candidate, version
-                            );
-                            return Ok(candidate.to_string());
-                        }
-                    } else {
-                        debug!(
-                            "Candidate '{}' did not return a valid Python version",
-                            candidate
-                        );
-                    }
-                }
-            } else {
-                debug!(
-                    "Failed to execute candidate '{}': {}",
-                    candidate,
-                    output.unwrap_err()
-                );
-            }
-        }
-
-        // Try querying the system for Python (Windows-specific: py launcher)
-        #[cfg(target_os = "windows")]
-        {
-            let output = Command::new("py").arg("-0").output().await;
-            if let Ok(output) = output {
-                if output.status.success() {
-                    let py_output = String::from_utf8_lossy(&output.stdout);
-                    // Parse the output of `py -0` to find the highest Python version
-                    if let Some(line) = py_output.lines().find(|line| line.contains("-3")) {
-                        if let Some(version) = line.split_whitespace().next() {
-                            return Ok(format!("py -{}", version.trim_start_matches('-')));
-                        }
-                    }
-                }
-            }
-        }
-
-        Err(fs::Error::NoPythonExecutable.into())
-    }
-
-    /// Try to get the path to the docker compose executable
-    pub async fn find_docker_compose_executable<S: AsRef<str>>(
-        min_version: S,
-    ) -> Result<String, Error> {
-        let min_version = Version::parse(min_version.as_ref())
-            .map_err(|_| fs::Error::NoDockerComposeExecutable)?;
-
-        // First, try to find docker executable and test if it has compose subcommand
-        if let Ok(docker_compose_cmd) = try_docker_compose_plugin(&min_version).await {
-            return Ok(docker_compose_cmd);
-        }
-
-        // If docker compose plugin doesn't work, try standalone docker-compose
-        if let Ok(docker_compose_cmd) = try_docker_compose_standalone(&min_version).await {
-            return Ok(docker_compose_cmd);
-        }
-
-        Err(fs::Error::NoDockerComposeExecutable.into())
+        expand(candidates)
     }
 
-    /// Try to find git executable and test if it has the required version
-    pub async fn find_git_executable<S: AsRef<str>>(min_version: S) -> Result<String, Error> {
-        // parse the git version from the --version output
-        fn parse_version(output: &str) -> Option<Version> {
-            let version_str = output.rsplit_once(' ')?.1.trim();
-            Version::parse(version_str).ok()
-        }
-
-        let min_version =
-            Version::parse(min_version.as_ref()).map_err(|_| fs::Error::NoGitExecutable)?;
-
+    /// The git executables to try, in order
+    pub fn git_candidates() -> Vec<String> {
         // Common git executable names
+        #[allow(unused_mut)]
         let mut candidates = vec!["git"];
 
         // Platform-specific candidates
@@ -194,56 +191,19 @@ pub mod application {
             ]);
         }
 
-        for candidate in candidates.iter() {
-            debug!("Checking git executable: {}", candidate);
-
-            // Test if git --version works
-            let output = Command::new(candidate).arg("--version").output().await;
-
-            if let Ok(output) = output {
-                if output.status.success() {
-                    let version_output = String::from_utf8_lossy(&output.stdout);
-                    debug!("Git version output: {}", version_output);
-
-                    // Parse version from "git version 2.34.1"
-                    if let Some(version) = parse_version(&version_output) {
-                        if version >= min_version {
-                            info!("Found git executable: {} (version: {})", candidate, version);
-                            return Ok(candidate.to_string());
-                        } else {
-                            debug!("Git version {} is below minimum {}", version, min_version);
-                        }
-                    } else {
-                        debug!("Could not parse git version from output");
-                    }
-                }
-            } else {
-                debug!(
-                    "Failed to execute git command '{}': {}",
-                    candidate,
-                    output.unwrap_err()
-                );
-            }
-        }
-
-        Err(fs::Error::NoGitExecutable.into())
+        expand(candidates)
     }
 
-    /// Try to find docker executable and test if it has compose subcommand
-    async fn try_docker_compose_plugin(min_version: &Version) -> Result<String, Error> {
-        // parse the python version from the --version output
-        fn parse_version(output: &str) -> Option<Version> {
-            let version_str = output.rsplit_once('v')?.1.trim();
-            Version::parse(version_str).ok()
-        }
-
+    /// The docker executables to try for the compose plugin, in order
+    pub fn docker_candidates() -> Vec<String> {
         // Common docker executable names
-        let mut docker_candidates = vec!["docker"];
+        #[allow(unused_mut)]
+        let mut candidates = vec!["docker"];
 
         // Platform-specific docker candidates
         #[cfg(target_os = "windows")]
         {
-            docker_candidates.extend(vec![
+            candidates.extend(vec![
                 "docker.exe",
                 "C:\\Program Files\\Docker\\Docker\\resources\\bin\\docker.exe",
             ]);
@@ -251,7 +211,7 @@ pub mod application {
 
         #[cfg(target_os = "macos")]
         {
-            docker_candidates.extend(vec![
+            candidates.extend(vec![
                 "/usr/local/bin/docker",
                 "/opt/homebrew/bin/docker",
                 "/Applications/Docker.app/Contents/Resources/bin/docker",
@@ -260,72 +220,26 @@ pub mod application {
 
         #[cfg(target_os = "linux")]
         {
-            docker_candidates.extend(vec![
+            candidates.extend(vec![
                 "/usr/bin/docker",
                 "/usr/local/bin/docker",
                 "/snap/bin/docker",
             ]);
         }
 
-        for docker_cmd in docker_candidates.iter() {
-            debug!("Checking docker executable: {}", docker_cmd);
-
-            // Test if docker compose version works
-            let output = Command::new(docker_cmd)
-                .args(["compose", "version"])
-                .output()
-                .await;
-
-            if let Ok(output) = output {
-                if output.status.success() {
-                    let version_output = String::from_utf8_lossy(&output.stdout);
-                    debug!("Docker compose version output: {}", version_output);
-
-                    // Parse version from "Docker Compose version v2.36.2"
-                    if let Some(version) = parse_version(&version_output) {
-                        if version >= *min_version {
-                            info!(
-                                "v Found Docker with compose plugin: {} (version: {})",
-                                docker_cmd, version
-                            );
-                            return Ok(docker_cmd.to_string());
-                        } else {
-                            debug!(
-                                "Docker compose version {} is below minimum {}",
-                                version, min_version
-                            );
-                        }
-                    } else {
-                        debug!("Could not parse Docker Compose version from output");
-                    }
-                }
-            } else {
-                debug!(
-                    "Failed to execute docker command '{}': {}",
-                    docker_cmd,
-                    output.unwrap_err()
-                );
-            }
-        }
-
-        Err(fs::Error::NoDockerComposeExecutable.into())
+        expand(candidates)
     }
 
-    /// Try to find standalone docker-compose executable
-    async fn try_docker_compose_standalone(min_version: &Version) -> Result<String, Error> {
-        // parse the python version from the --version output
-        fn parse_version(output: &str) -> Option<Version> {
-            let version_str = output.rsplit_once(' ')?.1.trim();
-            Version::parse(version_str).ok()
-        }
-
+    /// The standalone docker-compose executables to try, in order
+    pub fn docker_compose_candidates() -> Vec<String> {
         // Common docker-compose executable names
-        let mut docker_compose_candidates = vec!["docker-compose"];
+        #[allow(unused_mut)]
+        let mut candidates = vec!["docker-compose"];
 
         // Platform-specific docker-compose candidates
         #[cfg(target_os = "windows")]
         {
-            docker_compose_candidates.extend(vec![
+            candidates.extend(vec![
                 "docker-compose.exe",
                 "C:\\Program Files\\Docker\\Docker\\resources\\bin\\docker-compose.exe",
                 "C:\\ProgramData\\DockerDesktop\\version-bin\\docker-compose.exe",
@@ -334,7 +248,7 @@ pub mod application {
 
         #[cfg(target_os = "macos")]
         {
-            docker_compose_candidates.extend(vec![
+            candidates.extend(vec![
                 "/usr/local/bin/docker-compose",
                 "/opt/homebrew/bin/docker-compose",
                 "/Applications/Docker.app/Contents/Resources/bin/docker-compose",
@@ -343,51 +257,148 @@ pub mod application {
 
         #[cfg(target_os = "linux")]
         {
-            docker_compose_candidates.extend(vec![
+            candidates.extend(vec![
                 "/usr/bin/docker-compose",
                 "/usr/local/bin/docker-compose",
                 "/snap/bin/docker-compose",
             ]);
         }
 
-        for docker_compose_cmd in docker_compose_candidates.iter() {
-            debug!("Checking docker-compose executable: {}", docker_compose_cmd);
+        expand(candidates)
+    }
+
+    // probe the candidates in order and return the first that meets the minimum version
+    async fn first_meeting(
+        candidates: Vec<String>,
+        args: &[&str],
+        min_version: &Version,
+    ) -> Option<String> {
+        for candidate in candidates.iter() {
+            let probe = probe(candidate, args).await;
+            match &probe.version {
+                Ok(version) if version >= min_version => {
+                    info!("v Found {} (version: {})", probe.command, version);
+                    return Some(probe.executable);
+                }
+                Ok(version) => debug!(
+                    "'{}' version {} is below minimum {}",
+                    probe.command, version, min_version
+                ),
+                Err(reason) => debug!("'{}' failed: {}", probe.command, reason),
+            }
+        }
+        None
+    }
+
+    /// Ask an executable for the version of a tool. This keeps the arguments of launchers like
+    /// `py -3` and knows that the docker compose plugin is run through `docker`.
+    pub async fn probe_executable(tool: Tool, executable: &str) -> Probe {
+        let mut parts = executable.split_whitespace();
+        let program = parts.next().unwrap_or_default();
+        let mut args: Vec<&str> = parts.collect();
+        let standalone = Path::new(program)
+            .file_stem()
+            .is_some_and(|stem| stem == "docker-compose");
+        match tool {
+            Tool::Docker if !standalone => args.extend(["compose", "version"]),
+            _ => args.push("--version"),
+        }
+        probe(program, &args).await
+    }
+
+    /// Find the file an executable runs, looking through `PATH` for bare names. Launchers like
+    /// `py -3` are found by their first word.
+    pub fn resolve(executable: &str) -> Option<PathBuf> {
+        let program = shellexpand::tilde(executable.split_whitespace().next()?).to_string();
+        let path = PathBuf::from(&program);
+        if path.components().count() > 1 {
+            return path.is_file().then_some(path);
+        }
+        let extensions: &[&str] = if cfg!(windows) { &["", ".exe"] } else { &[""] };
+        let paths = std::env::var_os("PATH")?;
+        std::env::split_paths(&paths).find_map(|dir| {
+            extensions
+                .iter()
+                .map(|extension| dir.join(format!("{program}{extension}")))
+                .find(|candidate| candidate.is_file())
+        })
+    }
+
+    /// Try to find an executable for a tool that is at least the minimum version
+    pub async fn find_executable(tool: Tool, min_version: &str) -> Result<String, Error> {
+        match tool {
+            Tool::Python => find_python_executable(min_version).await,
+            Tool::Docker => find_docker_compose_executable(min_version).await,
+            Tool::Git => find_git_executable(min_version).await,
+        }
+    }
+
+    /// Try to get the path to the python executable
+    pub async fn find_python_executable<S: AsRef<str>>(min_version: S) -> Result<String, Error> {
+        let min_version =
+            Version::parse(min_version.as_ref()).map_err(|_| fs::Error::NoPythonExecutable)?;
 
-            // Test if docker-compose --version works
-            let output = Command::new(docker_compose_cmd)
-                .arg("--version")
-                .output()
-                .await;
+        if let Some(python) = first_meeting(python_candidates(), &["--version"], &min_version).await
+        {
+            return Ok(python);
+        }
 
+        // Try querying the system for Python (Windows-specific: py launcher)
+        #[cfg(target_os = "windows")]
+        {
+            let output = Command::new("py").arg("-0").output().await;
             if let Ok(output) = output {
                 if output.status.success() {
-                    let version_output = String::from_utf8_lossy(&output.stdout);
-                    debug!("Docker-compose version output: {}", version_output);
-
-                    // Parse version from "docker-compose version 1.29.2"
-                    if let Some(version) = parse_version(&version_output) {
-                        if version >= *min_version {
-                            info!(
-                                "Found docker-compose standalone: {} (version: {})",
-                                docker_compose_cmd, version
-                            );
-                            return Ok(docker_compose_cmd.to_string());
-                        } else {
-                            debug!(
-                                "Docker-compose version {} is below minimum {}",
-                                version, min_version
-                            );
+                    let py_output = String::from_utf8_lossy(&output.stdout);
+                    // Parse the output of `py -0` to find the highest Python version
+                    if let Some(line) = py_output.lines().find(|line| line.contains("-3")) {
+                        if let Some(version) = line.split_whitespace().next() {
+                            return Ok(format!("py -{}", version.trim_start_matches('-')));
                         }
-                    } else {
-                        debug!("Could not parse docker-compose version from output");
                     }
                 }
             }
         }
 
+        Err(fs::Error::NoPythonExecutable.into())
+    }
+
+    /// Try to get the path to the docker compose executable
+    pub async fn find_docker_compose_executable<S: AsRef<str>>(
+        min_version: S,
+    ) -> Result<String, Error> {
+        let min_version = Version::parse(min_version.as_ref())
+            .map_err(|_| fs::Error::NoDockerComposeExecutable)?;
+
+        // First, try to find docker executable and test if it has compose subcommand
+        if let Some(docker) =
+            first_meeting(docker_candidates(), &["compose", "version"], &min_version).await
+        {
+            return Ok(docker);
+        }
+
+        // If docker compose plugin doesn't work, try standalone docker-compose
+        if let Some(docker_compose) =
+            first_meeting(docker_compose_candidates(), &["--version"], &min_version).await
+        {
+            return Ok(docker_compose);
+        }
+
         Err(fs::Error::NoDockerComposeExecutable.into())
     }
 
+    /// Try to find git executable and test if it has the required version
+    pub async fn find_git_executable<S: AsRef<str>>(min_version: S) -> Result<String, Error> {
+        let min_version =
+            Version::parse(min_version.as_ref()).map_err(|_| fs::Error::NoGitExecutable)?;
+
+        if let Some(git) = first_meeting(git_candidates(), &["--version"], &min_version).await {
+            return Ok(git);
+        }
+
+        Err(fs::Error::NoGitExecutable.into())
+    }
+
     /// Get the application data directory. This works on Windows, macOS, and Linux.
     pub fn data_dir() -> Result<PathBuf, Error> {
         let data_dir = if let Ok(workshops_dir) = std::env::var("WORKSHOPS_DIR") {
@@ -424,6 +435,26 @@ pub mod application {
         Ok(config_dir)
     }
 
+    /// Get the directory for the log files. This is the state directory on Linux and a `logs`
+    /// directory in the local data directory elsewhere.
+    pub fn log_dir() -> Result<PathBuf, Error> {
+        let log_dir = directories::ProjectDirs::from(
+            APPLICATION_PARTS[0],
+            APPLICATION_PARTS[1],
+            APPLICATION_PARTS[2],
+        )
+        .map(|dirs| match dirs.state_dir() {
+            Some(state_dir) => state_dir.to_path_buf(),
+            None => dirs.data_local_dir().join("logs"),
+        })
+        .ok_or(fs::Error::ApplicationDirsNotFound)?;
+
+        // create the log directory if it doesn't exist
+        std::fs::create_dir_all(&log_dir)?;
+
+        Ok(log_dir)
+    }
+
     /// Get all of the workshops data objects for all workshops in the application data directory
     pub fn all_workshops() -> Result<HashMap<String, workshop::WorkshopData>, Error> {
         let mut workshops_data = workshops::load_workshop_data(data_dir()?)?;
@@ -585,6 +616,50 @@ pub mod workshops {
         None
     }
 
+    /// Check the workshops that a workshop requires against the progress in the `.workshops`
+    /// directory and describe each requirement that is not met
+    pub async fn unmet_requirements(
+        workshop: &workshop::Workshop,
+        spoken_language: Option<spoken::Code>,
+        programming_language: Option<programming::Code>,
+    ) -> Vec<String> {
+        let mut unmet = Vec::new();
+        for requirement in workshop.requires_workshops.iter() {
+            // a required workshop that is not in the project has not been started
+            let progress = match load(&requirement.name) {
+                Some(data) => data
+                    .get_progress(spoken_language, programming_language)
+                    .await
+                    .unwrap_or_default(),
+                None => workshop::Progress::default(),
+            };
+            if requirement.is_met(&progress) {
+                continue;
+            }
+            let title = title(&requirement.name, spoken_language).await;
+            unmet.push(match requirement.min_lessons {
+                Some(min_lessons) => format!("{min_lessons} lessons of \"{title}\""),
+                None => format!("\"{title}\""),
+            });
+        }
+        unmet
+    }
+
+    // get the title of an installed workshop, falling back to its name
+    async fn title(name: &str, spoken_language: Option<spoken::Code>) -> String {
+        let Ok(data_dir) = application::data_dir() else {
+            return name.to_string();
+        };
+        match workshop::Loader::new(name).path(&data_dir).try_load() {
+            Ok(data) => data
+                .get_metadata(spoken_language)
+                .await
+                .map(|w| w.title)
+                .unwrap_or_else(|_| name.to_string()),
+            Err(_) => name.to_string(),
+        }
+    }
+
     /// Get all workshop data objects for workshops in the given folder
     pub fn load_workshop_data<T: AsRef<Path>>(
         data_dir: T,
@@ -609,3 +684,32 @@ pub mod workshops {
         Ok(workshops)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        let parse = application::parse_version;
+        assert_eq!(parse("Python 3.12.3\n"), Some(Version::new(3, 12, 3)));
+        assert_eq!(parse("Python 3.13.0rc1"), Some(Version::new(3, 13, 0)));
+        assert_eq!(
+            parse("Docker Compose version v2.36.2"),
+            Some(Version::new(2, 36, 2))
+        );
+        assert_eq!(
+            parse("docker-compose version 1.29.2, build 5becea4c"),
+            Some(Version::new(1, 29, 2))
+        );
+        assert_eq!(
+            parse("git version 2.39.3 (Apple Git-146)"),
+            Some(Version::new(2, 39, 3))
+        );
+        assert_eq!(
+            parse("git version 2.41.0.windows.1"),
+            Some(Version::new(2, 41, 0))
+        );
+        assert_eq!(parse("command not found"), None);
+    }
+}
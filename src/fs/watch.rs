@@ -0,0 +1,325 @@
+use crate::fs::Error;
+use notify::{
+    event::ModifyKind, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher as _,
+};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+use tracing::warn;
+
+/// Directories that are never watched
+pub const ALWAYS_IGNORED: [&str; 3] = [".workshops", ".git", "target"];
+
+/// The caches and build output that the solution checks write into the project, in `.gitignore`
+/// syntax. They come before the project's own `.gitignore` rules, which can re-include them.
+pub const CHECK_OUTPUT: &str = "__pycache__/\n*.pyc\n.pytest_cache/\n.mypy_cache/\nnode_modules/\n";
+
+/// Find the project root for a lesson directory. The project root is the parent of the
+/// `.workshops` directory that the lesson lives in.
+pub fn project_root(lesson_dir: &Path) -> Option<&Path> {
+    lesson_dir
+        .ancestors()
+        .find(|dir| dir.file_name().map(|n| n == ".workshops").unwrap_or(false))
+        .and_then(Path::parent)
+}
+
+/// A single pattern from a `.gitignore` file
+#[derive(Clone, Debug)]
+struct Pattern {
+    /// the glob, without any leading or trailing slash
+    glob: String,
+    /// the pattern contains a slash so it matches the path relative to the root
+    anchored: bool,
+    /// the pattern ends with a slash so it only matches directories
+    dir_only: bool,
+    /// the pattern starts with `!` so it re-includes what an earlier pattern ignored
+    negated: bool,
+}
+
+impl Pattern {
+    fn parse(line: &str) -> Option<Self> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let negated = line.starts_with('!');
+        let line = line.strip_prefix('!').unwrap_or(line);
+        // a leading backslash escapes a literal `#` or `!`
+        let line = line.strip_prefix('\\').unwrap_or(line);
+        let dir_only = line.ends_with('/');
+        let line = line.trim_end_matches('/');
+        let anchored = line.contains('/');
+        let glob = line.trim_start_matches('/').to_string();
+        if glob.is_empty() {
+            return None;
+        }
+        Some(Self {
+            glob,
+            anchored,
+            dir_only,
+            negated,
+        })
+    }
+
+    fn matches(&self, relative: &str, name: &str, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        if self.anchored {
+            glob(self.glob.as_bytes(), relative.as_bytes())
+        } else {
+            glob(self.glob.as_bytes(), name.as_bytes())
+        }
+    }
+}
+
+/// Match a gitignore style glob where `*` and `?` do not match `/` and `**` matches anything
+fn glob(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        [b'*', b'*', rest @ ..] => {
+            let rest = rest.strip_prefix(b"/").unwrap_or(rest);
+            (0..=text.len()).any(|i| glob(rest, &text[i..]))
+        }
+        [b'*', rest @ ..] => {
+            let end = text.iter().position(|c| *c == b'/').unwrap_or(text.len());
+            (0..=end).any(|i| glob(rest, &text[i..]))
+        }
+        [b'?', rest @ ..] => matches!(text, [c, tail @ ..] if *c != b'/' && glob(rest, tail)),
+        [p, rest @ ..] => matches!(text, [c, tail @ ..] if c == p && glob(rest, tail)),
+    }
+}
+
+/// The paths to skip while watching a project
+#[derive(Clone, Debug, Default)]
+pub struct Ignore {
+    patterns: Vec<Pattern>,
+}
+
+impl Ignore {
+    /// Load the patterns from the `.gitignore` file in the root, if there is one
+    pub fn load(root: &Path) -> Self {
+        let text = std::fs::read_to_string(root.join(".gitignore")).unwrap_or_default();
+        Self::parse(&text)
+    }
+
+    /// Parse the patterns from the text of a `.gitignore` file, after the check output patterns
+    pub fn parse(text: &str) -> Self {
+        Self {
+            patterns: CHECK_OUTPUT
+                .lines()
+                .chain(text.lines())
+                .filter_map(Pattern::parse)
+                .collect(),
+        }
+    }
+
+    /// Check if a path relative to the root is ignored. The last pattern that matches wins, so a
+    /// negated pattern re-includes a path that an earlier pattern ignored.
+    pub fn is_ignored(&self, relative: &Path, is_dir: bool) -> bool {
+        let name = relative
+            .file_name()
+            .map(|n| n.to_string_lossy())
+            .unwrap_or_default();
+        if is_dir && ALWAYS_IGNORED.contains(&name.as_ref()) {
+            return true;
+        }
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        self.patterns
+            .iter()
+            .rev()
+            .find(|p| p.matches(&relative, &name, is_dir))
+            .is_some_and(|p| !p.negated)
+    }
+
+    /// Check if a path under the root is ignored, either itself or because one of the
+    /// directories it is in is ignored. Like git, a negated pattern cannot re-include a path in an
+    /// ignored directory. Paths outside of the root are always ignored.
+    pub fn is_ignored_path(&self, root: &Path, path: &Path) -> bool {
+        let Ok(relative) = path.strip_prefix(root) else {
+            return true;
+        };
+        let mut dir = PathBuf::new();
+        let mut components = relative.components().peekable();
+        while let Some(component) = components.next() {
+            dir.push(component);
+            let is_dir = components.peek().is_some() || path.is_dir();
+            if self.is_ignored(&dir, is_dir) {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+/// Watches a project directory for changed files with the file events from the operating system.
+/// Only the directories that are not ignored are watched, so large build directories cost
+/// nothing.
+pub struct Watcher {
+    root: PathBuf,
+    ignore: Arc<Ignore>,
+    watcher: RecommendedWatcher,
+    changes: UnboundedReceiver<PathBuf>,
+}
+
+impl Watcher {
+    /// Start watching the root directory
+    pub fn new(root: &Path) -> Result<Self, Error> {
+        let ignore = Arc::new(Ignore::load(root));
+        let (sender, changes) = mpsc::unbounded_channel();
+
+        // filter the events on the watcher's thread, as soon as they happen
+        let filter = (root.to_path_buf(), ignore.clone());
+        let watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+            let Ok(event) = event else {
+                return;
+            };
+            if !is_change(&event.kind) {
+                return;
+            }
+            for path in event.paths {
+                if !filter.1.is_ignored_path(&filter.0, &path) {
+                    let _ = sender.send(path);
+                }
+            }
+        })?;
+
+        let mut watcher = Self {
+            root: root.to_path_buf(),
+            ignore,
+            watcher,
+            changes,
+        };
+        watcher.watch_tree(root)?;
+        Ok(watcher)
+    }
+
+    /// Wait for the next changed path. New directories are watched as they appear.
+    pub async fn changed(&mut self) -> Option<PathBuf> {
+        let path = self.changes.recv().await?;
+        if path.is_dir() {
+            if let Err(e) = self.watch_tree(&path) {
+                warn!("Cannot watch {}: {}", path.display(), e);
+            }
+        }
+        Some(path)
+    }
+
+    // watch a directory and every directory below it that is not ignored
+    fn watch_tree(&mut self, dir: &Path) -> Result<(), Error> {
+        let mut dirs = vec![dir.to_path_buf()];
+        while let Some(dir) = dirs.pop() {
+            self.watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+                if is_dir && !self.ignore.is_ignored_path(&self.root, &path) {
+                    dirs.push(path);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// only files being added, removed, written or renamed are changes, not reads or metadata
+fn is_change(kind: &EventKind) -> bool {
+    match kind {
+        EventKind::Create(_) | EventKind::Remove(_) => true,
+        EventKind::Modify(kind) => !matches!(kind, ModifyKind::Metadata(_)),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_root() {
+        let lesson = Path::new("/home/me/project/.workshops/intro/en/rust/01-hello");
+        assert_eq!(project_root(lesson), Some(Path::new("/home/me/project")));
+        assert_eq!(project_root(Path::new("/home/me/project")), None);
+    }
+
+    #[test]
+    fn test_ignore_patterns() {
+        let ignore = Ignore::parse("# build output\n*.log\n/dist\nbuild/\ndocs/**/*.tmp\n");
+        assert!(ignore.is_ignored(Path::new("target"), true));
+        assert!(ignore.is_ignored(Path::new(".workshops"), true));
+        assert!(ignore.is_ignored(Path::new("src/debug.log"), false));
+        assert!(ignore.is_ignored(Path::new("dist"), false));
+        assert!(!ignore.is_ignored(Path::new("src/dist"), false));
+        assert!(ignore.is_ignored(Path::new("src/build"), true));
+        assert!(!ignore.is_ignored(Path::new("src/build"), false));
+        assert!(ignore.is_ignored(Path::new("docs/a/b/notes.tmp"), false));
+        assert!(!ignore.is_ignored(Path::new("src/main.rs"), false));
+    }
+
+    #[test]
+    fn test_ignore_negation() {
+        let ignore = Ignore::parse("*.log\n!keep.log\nbuild/\n!build/out\n\\!bang\n");
+        assert!(ignore.is_ignored(Path::new("debug.log"), false));
+        assert!(!ignore.is_ignored(Path::new("src/keep.log"), false));
+        assert!(ignore.is_ignored(Path::new("!bang"), false));
+
+        // a file in an ignored directory cannot be re-included
+        let root = Path::new("/project");
+        assert!(ignore.is_ignored_path(root, Path::new("/project/build/out")));
+        assert!(!ignore.is_ignored_path(root, Path::new("/project/src/keep.log")));
+        assert!(ignore.is_ignored_path(root, Path::new("/project/target/debug/app")));
+        assert!(ignore.is_ignored_path(root, Path::new("/elsewhere/main.rs")));
+
+        // the output of the checks is ignored unless the project re-includes it
+        assert!(ignore.is_ignored_path(root, Path::new("/project/app/__pycache__/m.pyc")));
+        assert!(ignore.is_ignored(Path::new("node_modules"), true));
+        let ignore = Ignore::parse("!node_modules/\n");
+        assert!(!ignore.is_ignored(Path::new("node_modules"), true));
+    }
+
+    // wait for the next change the watcher reports
+    async fn next(watcher: &mut Watcher) -> PathBuf {
+        let changed = tokio::time::timeout(std::time::Duration::from_secs(5), watcher.changed());
+        changed.await.unwrap().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_watcher_sees_changes() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        std::fs::create_dir_all(root.join("target")).unwrap();
+        std::fs::write(root.join(".gitignore"), "*.log\n").unwrap();
+        std::fs::write(root.join("main.rs"), "fn main() {}").unwrap();
+        let root = root.canonicalize().unwrap();
+
+        let mut watcher = Watcher::new(&root).unwrap();
+
+        // ignored paths and the output of the checks do not count as changes, so the first
+        // change reported is the last file written
+        std::fs::write(root.join("target/out"), "x").unwrap();
+        std::fs::write(root.join("run.log"), "x").unwrap();
+        std::fs::create_dir(root.join("__pycache__")).unwrap();
+        std::fs::write(root.join("__pycache__/check.pyc"), "x").unwrap();
+        std::fs::write(root.join("main.rs"), "fn main() { println!() }").unwrap();
+        assert_eq!(next(&mut watcher).await, root.join("main.rs"));
+
+        // new directories are watched
+        std::fs::create_dir(root.join("src")).unwrap();
+        loop {
+            if next(&mut watcher).await == root.join("src") {
+                break;
+            }
+        }
+        std::fs::write(root.join("src/lib.rs"), "").unwrap();
+        loop {
+            if next(&mut watcher).await == root.join("src/lib.rs") {
+                break;
+            }
+        }
+    }
+}
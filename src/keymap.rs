@@ -0,0 +1,420 @@
+use crate::Error;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{collections::BTreeMap, fmt};
+
+/// The named actions that keys can be bound to
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    /// Quit the application
+    Quit,
+    /// Show or hide the log
+    ToggleLog,
+    /// Move up or scroll up
+    Up,
+    /// Move down or scroll down
+    Down,
+    /// Jump to the top
+    Top,
+    /// Jump to the bottom
+    Bottom,
+    /// Select, expand or continue
+    Select,
+    /// Go back to the previous screen
+    Back,
+    /// Move focus to the next view
+    NextFocus,
+    /// Move focus to the previous view
+    PreviousFocus,
+    /// Check the lesson solution
+    Check,
+    /// Show the workshop license
+    License,
+    /// Filter the workshops by language, or the log to the last command's output
+    Filter,
+    /// Open the workshop homepage
+    Homepage,
+    /// Show the last check output beside the lesson
+    Split,
+    /// Check the solution whenever the project files change
+    Watch,
+    /// Open the lesson's file in the editor
+    Edit,
+    /// Restore the project to the checkpoint from before the lesson
+    Restore,
+    /// Reset the lesson's starter files in the project, or a setting to its default
+    Reset,
+    /// Show the settings
+    Settings,
+    /// Save the log to a file
+    Export,
+    /// Copy the log to the clipboard
+    Copy,
+    /// Save a support bundle with the log, the status and the tool versions
+    Bundle,
+}
+
+impl fmt::Display for Action {
+    /// The config file name of the action, e.g. `toggle_log`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.serialize(f)
+    }
+}
+
+/// A key with its modifiers, written as e.g. `q`, `enter`, `shift+tab` or `ctrl+n`
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Key {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl Key {
+    const fn new(code: KeyCode) -> Self {
+        Self {
+            code,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    const fn char(c: char) -> Self {
+        Self::new(KeyCode::Char(c))
+    }
+
+    /// Check if a key event is this key. Shift is part of the character for character keys and
+    /// shift+tab may be reported as either `BackTab` or `Tab` with shift.
+    fn matches(&self, event: &KeyEvent) -> bool {
+        let code = match event.code {
+            KeyCode::Tab if event.modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+            code => code,
+        };
+        let mask = KeyModifiers::CONTROL | KeyModifiers::ALT;
+        self.code == code && (self.modifiers & mask) == (event.modifiers & mask)
+    }
+
+    /// The config file name of the key, without modifiers
+    fn name(&self) -> String {
+        match self.code {
+            KeyCode::Char(' ') => "space".to_string(),
+            KeyCode::Char(c) => c.to_string(),
+            KeyCode::F(n) => format!("f{n}"),
+            KeyCode::Enter => "enter".to_string(),
+            KeyCode::Tab => "tab".to_string(),
+            KeyCode::BackTab => "shift+tab".to_string(),
+            KeyCode::Esc => "esc".to_string(),
+            KeyCode::PageUp => "pageup".to_string(),
+            KeyCode::PageDown => "pagedown".to_string(),
+            KeyCode::Up => "up".to_string(),
+            KeyCode::Down => "down".to_string(),
+            KeyCode::Left => "left".to_string(),
+            KeyCode::Right => "right".to_string(),
+            KeyCode::Home => "home".to_string(),
+            KeyCode::End => "end".to_string(),
+            KeyCode::Backspace => "backspace".to_string(),
+            KeyCode::Delete => "delete".to_string(),
+            code => format!("{code:?}").to_lowercase(),
+        }
+    }
+
+    /// The name of the key with its modifiers, as written in the config file
+    fn config_name(&self) -> String {
+        format!("{}{}", self.prefix(), self.name())
+    }
+
+    /// The modifier prefix, e.g. `ctrl+`
+    fn prefix(&self) -> String {
+        let mut prefix = String::new();
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            prefix.push_str("ctrl+");
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            prefix.push_str("alt+");
+        }
+        prefix
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = match self.code {
+            KeyCode::Enter => "↵".to_string(),
+            KeyCode::Tab => "⇥".to_string(),
+            KeyCode::BackTab => "⇤".to_string(),
+            KeyCode::PageUp => "⤒".to_string(),
+            KeyCode::PageDown => "⤓".to_string(),
+            KeyCode::Up => "↑".to_string(),
+            KeyCode::Down => "↓".to_string(),
+            KeyCode::Left => "←".to_string(),
+            KeyCode::Right => "→".to_string(),
+            _ => self.name(),
+        };
+        write!(f, "{}{symbol}", self.prefix())
+    }
+}
+
+impl TryFrom<&str> for Key {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = value;
+        loop {
+            let lower = rest.to_lowercase();
+            if lower.starts_with("ctrl+") && rest.len() > 5 {
+                modifiers |= KeyModifiers::CONTROL;
+                rest = &rest[5..];
+            } else if lower.starts_with("alt+") && rest.len() > 4 {
+                modifiers |= KeyModifiers::ALT;
+                rest = &rest[4..];
+            } else {
+                break;
+            }
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_lowercase().as_str() {
+                "space" => KeyCode::Char(' '),
+                "enter" | "return" => KeyCode::Enter,
+                "tab" => KeyCode::Tab,
+                "shift+tab" | "backtab" => KeyCode::BackTab,
+                "esc" | "escape" => KeyCode::Esc,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "backspace" => KeyCode::Backspace,
+                "delete" => KeyCode::Delete,
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n) if (1..=12).contains(&n) => KeyCode::F(n),
+                    _ => return Err(Error::InvalidKey(value.to_string())),
+                },
+            },
+        };
+
+        Ok(Self { code, modifiers })
+    }
+}
+
+impl Serialize for Key {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.config_name())
+    }
+}
+
+impl<'de> Deserialize<'de> for Key {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Key::try_from(s.as_str()).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Maps named actions to the keys bound to them. Actions missing from the config file keep
+/// their default keys.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Keymap {
+    bindings: BTreeMap<Action, Vec<Key>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = [
+            (Action::Quit, vec![Key::char('q'), Key::char('Q')]),
+            (Action::ToggleLog, vec![Key::char('`')]),
+            (
+                Action::Up,
+                vec![Key::char('k'), Key::char('K'), Key::new(KeyCode::Up)],
+            ),
+            (
+                Action::Down,
+                vec![Key::char('j'), Key::char('J'), Key::new(KeyCode::Down)],
+            ),
+            (Action::Top, vec![Key::new(KeyCode::PageUp)]),
+            (Action::Bottom, vec![Key::new(KeyCode::PageDown)]),
+            (Action::Select, vec![Key::new(KeyCode::Enter)]),
+            (Action::Back, vec![Key::char('b'), Key::new(KeyCode::Esc)]),
+            (Action::NextFocus, vec![Key::new(KeyCode::Tab)]),
+            (Action::PreviousFocus, vec![Key::new(KeyCode::BackTab)]),
+            (Action::Check, vec![Key::char('c'), Key::char('C')]),
+            (Action::License, vec![Key::char('l'), Key::char('L')]),
+            (Action::Filter, vec![Key::char('f'), Key::char('F')]),
+            (Action::Homepage, vec![Key::char('w'), Key::char('W')]),
+            (Action::Split, vec![Key::char('s'), Key::char('S')]),
+            (Action::Watch, vec![Key::char('a'), Key::char('A')]),
+            (Action::Edit, vec![Key::char('e'), Key::char('E')]),
+            (Action::Restore, vec![Key::char('r'), Key::char('R')]),
+            (Action::Reset, vec![Key::char('t'), Key::char('T')]),
+            (Action::Settings, vec![Key::char('o'), Key::char('O')]),
+            (Action::Export, vec![Key::char('x'), Key::char('X')]),
+            (Action::Copy, vec![Key::char('y'), Key::char('Y')]),
+            (Action::Bundle, vec![Key::char('h'), Key::char('H')]),
+        ];
+        Self {
+            bindings: bindings.into_iter().collect(),
+        }
+    }
+}
+
+impl Keymap {
+    /// Get the action bound to a key event, if any
+    pub fn action(&self, event: &KeyEvent) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(_, keys)| keys.iter().any(|key| key.matches(event)))
+            .map(|(action, _)| *action)
+    }
+
+    /// Get the label of the first key bound to an action for showing in the status bar
+    pub fn key(&self, action: Action) -> String {
+        self.bindings
+            .get(&action)
+            .and_then(|keys| keys.first())
+            .map(|key| key.to_string())
+            .unwrap_or_else(|| "∅".to_string())
+    }
+
+    /// Find the keys that are bound to more than one action. A key only ever triggers the first
+    /// of its actions, so the others cannot be reached with it on any screen.
+    pub fn conflicts(&self) -> Vec<(String, Vec<Action>)> {
+        let mut actions = BTreeMap::<String, Vec<Action>>::new();
+        for (action, keys) in &self.bindings {
+            for key in keys {
+                let bound = actions.entry(key.config_name()).or_default();
+                if !bound.contains(action) {
+                    bound.push(*action);
+                }
+            }
+        }
+        actions
+            .into_iter()
+            .filter(|(_, actions)| actions.len() > 1)
+            .collect()
+    }
+}
+
+impl Serialize for Keymap {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.bindings.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Keymap {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let overrides = BTreeMap::<Action, Vec<Key>>::deserialize(deserializer)?;
+        let mut keymap = Keymap::default();
+        keymap.bindings.extend(overrides);
+        Ok(keymap)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyEventKind;
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new_with_kind(code, modifiers, KeyEventKind::Press)
+    }
+
+    #[test]
+    fn test_default_bindings() {
+        let keymap = Keymap::default();
+        let none = KeyModifiers::NONE;
+        assert_eq!(
+            keymap.action(&press(KeyCode::Char('q'), none)),
+            Some(Action::Quit)
+        );
+        assert_eq!(
+            keymap.action(&press(KeyCode::Char('J'), KeyModifiers::SHIFT)),
+            Some(Action::Down)
+        );
+        assert_eq!(
+            keymap.action(&press(KeyCode::Esc, none)),
+            Some(Action::Back)
+        );
+        assert_eq!(
+            keymap.action(&press(KeyCode::Tab, KeyModifiers::SHIFT)),
+            Some(Action::PreviousFocus)
+        );
+        assert_eq!(
+            keymap.action(&press(KeyCode::BackTab, KeyModifiers::SHIFT)),
+            Some(Action::PreviousFocus)
+        );
+        assert_eq!(
+            keymap.action(&press(KeyCode::Char('q'), KeyModifiers::CONTROL)),
+            None
+        );
+        assert_eq!(keymap.key(Action::Down), "j");
+        assert_eq!(keymap.key(Action::Select), "↵");
+    }
+
+    #[test]
+    fn test_parse_keys() {
+        assert_eq!(Key::try_from("q").unwrap(), Key::char('q'));
+        assert_eq!(Key::try_from("Enter").unwrap(), Key::new(KeyCode::Enter));
+        assert_eq!(
+            Key::try_from("shift+tab").unwrap(),
+            Key::new(KeyCode::BackTab)
+        );
+        assert_eq!(Key::try_from("f5").unwrap(), Key::new(KeyCode::F(5)));
+        assert_eq!(
+            Key::try_from("ctrl+n").unwrap(),
+            Key {
+                code: KeyCode::Char('n'),
+                modifiers: KeyModifiers::CONTROL,
+            }
+        );
+        assert!(Key::try_from("hyper").is_err());
+        assert!(Key::try_from("f13").is_err());
+    }
+
+    #[test]
+    fn test_config_overrides_defaults() {
+        let keymap: Keymap = serde_yaml::from_str("quit: [ctrl+q]\ndown: [n, down]\n").unwrap();
+        let none = KeyModifiers::NONE;
+        assert_eq!(keymap.action(&press(KeyCode::Char('q'), none)), None);
+        assert_eq!(
+            keymap.action(&press(KeyCode::Char('q'), KeyModifiers::CONTROL)),
+            Some(Action::Quit)
+        );
+        assert_eq!(
+            keymap.action(&press(KeyCode::Char('n'), none)),
+            Some(Action::Down)
+        );
+        assert_eq!(keymap.action(&press(KeyCode::Char('j'), none)), None);
+        // actions missing from the config keep their defaults
+        assert_eq!(
+            keymap.action(&press(KeyCode::Char('k'), none)),
+            Some(Action::Up)
+        );
+        assert_eq!(keymap.key(Action::Quit), "ctrl+q");
+
+        assert!(keymap.conflicts().is_empty());
+
+        // round trip through the config file format
+        let yaml = serde_yaml::to_string(&keymap).unwrap();
+        assert_eq!(serde_yaml::from_str::<Keymap>(&yaml).unwrap(), keymap);
+    }
+
+    #[test]
+    fn test_conflicts() {
+        assert!(Keymap::default().conflicts().is_empty());
+
+        // the check key shadows the default copy key, on every screen
+        let keymap: Keymap = serde_yaml::from_str("check: [c, y]\n").unwrap();
+        assert_eq!(
+            keymap.conflicts(),
+            vec![("y".to_string(), vec![Action::Check, Action::Copy])]
+        );
+        assert_eq!(Action::ToggleLog.to_string(), "toggle_log");
+        assert_eq!(
+            keymap.action(&press(KeyCode::Char('y'), KeyModifiers::NONE)),
+            Some(Action::Check)
+        );
+    }
+}
@@ -16,3 +16,10 @@ pub fn spoken_name(s: Option<spoken::Code>) -> String {
         None => "Any".to_string(),
     }
 }
+
+pub fn spoken_direction(s: Option<spoken::Code>) -> spoken::Direction {
+    match s {
+        Some(code) => code.get_text_direction(),
+        None => spoken::Direction::default(),
+    }
+}
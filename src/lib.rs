@@ -1,15 +1,21 @@
 pub mod app;
 pub use app::App;
+pub mod checkpoint;
 pub mod command;
 pub mod config;
 pub use config::Config;
+pub mod diagnostics;
+pub mod doctor;
+pub mod editor;
 pub mod error;
 pub use error::Error;
 pub mod fs;
+pub mod keymap;
 pub mod languages;
 pub mod log;
 pub use log::Log;
 pub mod models;
 pub mod status;
 pub use status::Status;
+pub mod support;
 pub mod ui;
@@ -3,9 +3,11 @@ use std::{
     cell::RefCell,
     fmt,
     fs::{File, OpenOptions},
-    io::Write,
-    path::Path,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
     sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
 };
 use tokio::sync::mpsc::{self, Receiver, Sender};
 use tracing::{
@@ -16,14 +18,155 @@ use tracing_subscriber::{
     filter::EnvFilter, layer::Context, prelude::*, registry::LookupSpan, Layer,
 };
 
+/// The name of the log file in the log directory
+pub const LOG_FILE: &str = "workshop.log";
+
+/// The size at which the log file is rotated
+const MAX_SIZE: u64 = 10 * 1024 * 1024;
+
+/// The number of rotated log files to keep
+const KEEP: usize = 5;
+
 thread_local! {
-    static INDENT_LEVEL: RefCell<usize> = const { RefCell::new(0) };
+    // the names of the spans entered on this thread, the innermost last
+    static SPANS: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+}
+
+/// How the lines in the log file are written
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Format {
+    /// the same lines as the log window
+    #[default]
+    Text,
+    /// one JSON object per line with the level, the message and the spans it happened in
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(Error::InvalidLogOption(format!(
+                "unknown format '{s}', expected text or json"
+            ))),
+        }
+    }
+}
+
+/// Where and how to log
+#[derive(Clone, Debug, Default)]
+pub struct Options {
+    /// the log file, if any
+    pub file: Option<PathBuf>,
+    /// the filter directives, e.g. `debug` or `workshop=trace`. Without it the `RUST_LOG`
+    /// environment variable is used.
+    pub level: Option<String>,
+    /// how the lines in the log file are written
+    pub format: Format,
+}
+
+// the rotated log file with the given number, e.g. `workshop.1.log`
+fn rotated(path: &Path, n: usize) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    match path.extension() {
+        Some(extension) => {
+            path.with_file_name(format!("{stem}.{n}.{}", extension.to_string_lossy()))
+        }
+        None => path.with_file_name(format!("{stem}.{n}")),
+    }
+}
+
+// move the log file to `.1`, the `.1` file to `.2` and so on, dropping the oldest
+fn rotate(path: &Path) -> io::Result<()> {
+    for n in (1..KEEP).rev() {
+        let from = rotated(path, n);
+        if from.exists() {
+            std::fs::rename(&from, rotated(path, n + 1))?;
+        }
+    }
+    if path.exists() {
+        std::fs::rename(path, rotated(path, 1))?;
+    }
+    Ok(())
+}
+
+// a log file that is rotated when it grows too big
+struct LogFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_size: u64,
+}
+
+impl LogFile {
+    // start a new log file, keeping the one from the last run
+    fn create(path: &Path, max_size: u64) -> io::Result<Self> {
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+        rotate(path)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            file: OpenOptions::new().write(true).create_new(true).open(path)?,
+            size: 0,
+            max_size,
+        })
+    }
+
+    fn write_line(&mut self, line: &str) -> io::Result<()> {
+        writeln!(self.file, "{line}")?;
+        self.file.flush()?;
+        self.size += line.len() as u64 + 1;
+        if self.size > self.max_size {
+            *self = Self::create(&self.path, self.max_size)?;
+        }
+        Ok(())
+    }
+}
+
+// the seconds since the Unix epoch, with milliseconds
+fn timestamp() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as f64 / 1000.0)
+        .unwrap_or_default()
+}
+
+// the current spans, outermost first
+fn spans() -> Vec<&'static str> {
+    SPANS.with(|spans| spans.borrow().clone())
+}
+
+// the indentation for the current spans
+fn indent() -> String {
+    SPANS.with(|spans| "  ".repeat(spans.borrow().len()))
 }
 
 // Custom tracing layer to send log events over mpsc
 struct MpscLayer {
     sender: Sender<String>,
-    file: Mutex<Option<File>>,
+    file: Mutex<Option<LogFile>>,
+    format: Format,
+}
+
+impl MpscLayer {
+    // write a line to the log file, if there is one. the json line is only built when it is used.
+    fn write(&self, text: &str, json: impl FnOnce() -> serde_json::Value) {
+        if let Ok(mut guard) = self.file.lock() {
+            if let Some(file) = guard.as_mut() {
+                let _ = match self.format {
+                    Format::Text => file.write_line(text),
+                    Format::Json => file.write_line(&json().to_string()),
+                };
+            }
+        }
+    }
 }
 
 // Implement a visitor to extract fields from the event
@@ -51,44 +194,40 @@ where
     fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
         // Log the span enter event
         if let Some(span) = ctx.span(id) {
-            let indent = INDENT_LEVEL.with(|l| "  ".repeat(*l.borrow()));
             let name = span.name();
-            let msg = format!("> {indent}{name}");
-            // if a file is provided, write the log message to it
-            if let Ok(mut guard) = self.file.lock() {
-                if let Some(file) = guard.as_mut() {
-                    writeln!(file, "{msg}").unwrap();
-                    let _ = file.flush();
-                }
-            }
+            let msg = format!("> {}{name}", indent());
+            self.write(&msg, || {
+                serde_json::json!({
+                    "time": timestamp(),
+                    "kind": "enter",
+                    "span": name,
+                    "spans": spans(),
+                })
+            });
             let _ = self.sender.try_send(msg);
-        }
 
-        // Increase the indent level when entering a span
-        INDENT_LEVEL.with(|level| {
-            *level.borrow_mut() += 1;
-        });
+            // the span is part of the context of everything logged inside of it
+            SPANS.with(|spans| spans.borrow_mut().push(name));
+        }
     }
 
     fn on_exit(&self, id: &Id, ctx: Context<S>) {
-        // Decrease the indent level when exiting a span
-        INDENT_LEVEL.with(|level| {
-            let mut level = level.borrow_mut();
-            *level = level.saturating_sub(1);
-        });
-
         if let Some(span) = ctx.span(id) {
-            // Log the span exit event
-            let indent = INDENT_LEVEL.with(|l| "  ".repeat(*l.borrow()));
+            // Leave the span before logging the exit event
+            SPANS.with(|spans| {
+                spans.borrow_mut().pop();
+            });
+
             let name = span.name();
-            let msg = format!("< {indent}{name}");
-            // if a file is provided, write the log message to it
-            if let Ok(mut guard) = self.file.lock() {
-                if let Some(file) = guard.as_mut() {
-                    writeln!(file, "{msg}").unwrap();
-                    let _ = file.flush();
-                }
-            }
+            let msg = format!("< {}{name}", indent());
+            self.write(&msg, || {
+                serde_json::json!({
+                    "time": timestamp(),
+                    "kind": "exit",
+                    "span": name,
+                    "spans": spans(),
+                })
+            });
             let _ = self.sender.try_send(msg);
         }
     }
@@ -112,8 +251,9 @@ where
         event.record(&mut visitor);
 
         // get the log message and format it
-        let indent = INDENT_LEVEL.with(|l| "  ".repeat(*l.borrow()));
-        let level = *event.metadata().level();
+        let indent = indent();
+        let metadata = event.metadata();
+        let level = *metadata.level();
         let message = visitor.message.unwrap_or_default();
 
         let msg = if starts_with_emoji(&message) {
@@ -130,12 +270,16 @@ where
         };
 
         // if a file is provided, write the log message to it
-        if let Ok(mut guard) = self.file.lock() {
-            if let Some(file) = guard.as_mut() {
-                writeln!(file, "{msg}").unwrap();
-                let _ = file.flush();
-            }
-        }
+        self.write(&msg, || {
+            serde_json::json!({
+                "time": timestamp(),
+                "kind": "event",
+                "level": level.as_str(),
+                "target": metadata.target(),
+                "message": message,
+                "spans": spans(),
+            })
+        });
 
         // send the log message over the mpsc channel
         let _ = self.sender.try_send(msg);
@@ -147,26 +291,73 @@ where
 pub struct Log;
 
 impl Log {
-    /// Starts the logger and returns the task handle and receiver for the log messages.
-    pub fn init<T: AsRef<Path>>(log: Option<T>) -> Result<Receiver<String>, Error> {
+    /// Get the default log file in the application's log directory
+    pub fn default_file() -> Result<PathBuf, Error> {
+        Ok(crate::fs::application::log_dir()?.join(LOG_FILE))
+    }
+
+    /// Starts the logger and returns the receiver for the log messages. The log file from the
+    /// last run is kept as a rotated file.
+    pub fn init(options: &Options) -> Result<Receiver<String>, Error> {
         let (sender, receiver) = mpsc::channel(16);
-        let file = if let Some(path) = log {
-            Mutex::new(Some(
-                OpenOptions::new()
-                    .write(true)
-                    .create(true)
-                    .truncate(true)
-                    .open(path.as_ref())?,
-            ))
-        } else {
-            Mutex::new(None)
+        let file = match &options.file {
+            Some(path) => Some(LogFile::create(path, MAX_SIZE)?),
+            None => None,
         };
 
-        let filter = EnvFilter::from_default_env();
-        let layer = MpscLayer { sender, file }.with_filter(filter);
+        let filter = match &options.level {
+            Some(level) => EnvFilter::try_new(level).map_err(|e| {
+                Error::InvalidLogOption(format!("invalid log level '{level}': {e}"))
+            })?,
+            None => EnvFilter::from_default_env(),
+        };
+        let layer = MpscLayer {
+            sender,
+            file: Mutex::new(file),
+            format: options.format,
+        }
+        .with_filter(filter);
 
         tracing_subscriber::registry().with(layer).init();
 
         Ok(receiver)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        let path = dir.join(LOG_FILE);
+        assert_eq!(rotated(&path, 2), dir.join("workshop.2.log"));
+
+        // every run starts a new file and keeps the older ones
+        for run in 0..KEEP + 2 {
+            let mut file = LogFile::create(&path, MAX_SIZE).unwrap();
+            file.write_line(&format!("run {run}")).unwrap();
+        }
+        let read = |path: PathBuf| std::fs::read_to_string(path).unwrap();
+        assert_eq!(read(path.clone()), format!("run {}\n", KEEP + 1));
+        assert_eq!(read(rotated(&path, 1)), format!("run {KEEP}\n"));
+        assert_eq!(read(rotated(&path, KEEP)), "run 1\n");
+        assert!(!rotated(&path, KEEP + 1).exists());
+
+        // a file that grows too big is rotated
+        let mut file = LogFile::create(&path, 8).unwrap();
+        file.write_line("0123456789").unwrap();
+        file.write_line("next").unwrap();
+        assert_eq!(read(path.clone()), "next\n");
+        assert_eq!(read(rotated(&path, 1)), "0123456789\n");
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!("JSON".parse::<Format>().unwrap(), Format::Json);
+        assert_eq!("text".parse::<Format>().unwrap(), Format::Text);
+        assert!("xml".parse::<Format>().is_err());
+    }
+}
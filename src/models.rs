@@ -4,5 +4,7 @@ pub use error::Error;
 pub mod lesson;
 pub use lesson::{Lesson, LessonData};
 
+pub mod tools;
+
 pub mod workshop;
 pub use workshop::{Loader, Workshop, WorkshopData};
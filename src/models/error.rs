@@ -89,4 +89,8 @@ pub enum Error {
     /// No lesson specified
     #[error("No lesson specified")]
     NoLessonSpecified,
+
+    /// Invalid tool requirement
+    #[error("Invalid tool requirement: {0}")]
+    InvalidToolRequirement(String),
 }
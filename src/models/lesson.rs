@@ -1,20 +1,24 @@
 use crate::{
-    fs::{LazyLoader, TryLoad},
+    fs::{
+        schema::{self, Migration, Versioned},
+        LazyLoader, TryLoad,
+    },
     languages::{programming, spoken},
     models::Error as ModelError,
     Error,
 };
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::BTreeMap,
     fmt,
-    path::{Path, PathBuf},
+    path::{Component, Path, PathBuf},
     sync::Arc,
 };
 use tokio::sync::RwLock;
-use tracing::trace;
+use tracing::{trace, warn};
 
-/// Represents the status of a Lesson
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+/// Represents the status of a Lesson, in the order of progress
+#[derive(Clone, Debug, Default, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
 pub enum Status {
     /// The lesson is not started
     #[default]
@@ -41,17 +45,253 @@ pub struct Lesson {
     pub title: String,
     pub description: String,
     pub status: Status,
+    /// A command run in the project root before the solution check, e.g. `cargo check`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_check: Option<String>,
+    /// The file the learner works on, relative to the project root, e.g. `src/main.rs`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub edit: Option<String>,
+    /// The lessons, by folder name, that must be completed before this one can be started.
+    /// Without it the lesson requires the previous lesson that is not optional
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requires: Option<Vec<String>>,
+    /// An optional lesson is not needed to finish the workshop or to unlock the lessons after it
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub optional: bool,
+}
+
+impl Lesson {
+    /// The file to open in the editor, joined to the project root. A path that is absolute or
+    /// that climbs out of the project root with `..` is rejected.
+    pub fn edit_path(&self, root: &Path) -> Option<PathBuf> {
+        let file = self.edit.as_deref()?;
+        let inside = Path::new(file)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+        if !inside {
+            warn!("Ignoring edit path outside of the project: {file}");
+            return None;
+        }
+        Some(root.join(file))
+    }
+}
+
+impl Versioned for Lesson {
+    const FILE: &'static str = "lesson.yaml";
+    const MIGRATIONS: &'static [Migration] = &[schema::unversioned];
 }
 
 #[async_trait::async_trait]
 impl TryLoad for Lesson {
     type Error = Error;
     async fn try_load(path: &Path) -> Result<Self, Error> {
-        let content = std::fs::read_to_string(path)?;
-        Ok(serde_yaml::from_str(&content)?)
+        schema::read(path)
     }
 }
 
+/// Where a lesson sits among lessons that are unlocked by the same prerequisites
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Branch {
+    /// there are more lessons in the branch after this one
+    Next,
+    /// the last lesson in the branch
+    Last,
+}
+
+/// A lesson in the prerequisites graph
+#[derive(Clone, Debug)]
+struct Node {
+    title: String,
+    completed: bool,
+    optional: bool,
+    requires: Vec<String>,
+}
+
+/// The lessons of a workshop and the lessons that each of them requires
+#[derive(Clone, Debug, Default)]
+pub struct Graph {
+    nodes: BTreeMap<String, Node>,
+}
+
+impl Graph {
+    /// Build the graph from the lessons keyed by their folder names
+    pub fn new<'a, I>(lessons: I) -> Self
+    where
+        I: IntoIterator<Item = (&'a String, &'a Lesson)>,
+    {
+        let sorted: BTreeMap<&String, &Lesson> = lessons.into_iter().collect();
+        let mut nodes = BTreeMap::new();
+        let mut previous: Option<&String> = None;
+        for (key, lesson) in sorted.iter() {
+            let requires = match &lesson.requires {
+                Some(requires) => requires
+                    .iter()
+                    .filter(|r| {
+                        let known = *r != *key && sorted.contains_key(r);
+                        if !known {
+                            warn!("Lesson {key} requires unknown lesson {r}, ignoring it");
+                        }
+                        known
+                    })
+                    .cloned()
+                    .collect(),
+                None => previous.into_iter().cloned().collect(),
+            };
+            if !lesson.optional {
+                previous = Some(key);
+            }
+            nodes.insert(
+                (*key).clone(),
+                Node {
+                    title: lesson.title.clone(),
+                    completed: matches!(lesson.status, Status::Completed),
+                    optional: lesson.optional,
+                    requires,
+                },
+            );
+        }
+
+        // lessons that require each other could never be started, so unlock them
+        let cyclic: Vec<String> = nodes
+            .keys()
+            .filter(|key| requires_itself(&nodes, key))
+            .cloned()
+            .collect();
+        for key in cyclic {
+            warn!("Lesson {key} requires itself through other lessons, unlocking it");
+            if let Some(node) = nodes.get_mut(&key) {
+                node.requires.clear();
+            }
+        }
+        Self { nodes }
+    }
+
+    /// The required lessons that are not completed yet
+    pub fn blockers(&self, key: &str) -> Vec<&str> {
+        self.nodes
+            .get(key)
+            .map(|node| {
+                node.requires
+                    .iter()
+                    .filter(|r| !self.nodes.get(*r).map(|n| n.completed).unwrap_or(true))
+                    .map(String::as_str)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Check if the lesson can be started
+    pub fn is_available(&self, key: &str) -> bool {
+        self.nodes.contains_key(key) && self.blockers(key).is_empty()
+    }
+
+    /// Check if the lesson is optional
+    pub fn is_optional(&self, key: &str) -> bool {
+        self.nodes.get(key).map(|n| n.optional).unwrap_or(false)
+    }
+
+    /// The titles of the lessons that the lesson requires
+    pub fn requires(&self, key: &str) -> Vec<&str> {
+        self.nodes
+            .get(key)
+            .map(|node| node.requires.iter().map(|r| self.title(r)).collect())
+            .unwrap_or_default()
+    }
+
+    /// The lessons that the lesson requires, the latest one first
+    pub fn predecessors(&self, key: &str) -> Vec<&str> {
+        let mut predecessors: Vec<&str> = self
+            .nodes
+            .get(key)
+            .map(|node| node.requires.iter().map(String::as_str).collect())
+            .unwrap_or_default();
+        predecessors.sort_unstable_by(|a, b| b.cmp(a));
+        predecessors
+    }
+
+    /// The titles of the lessons that require the lesson
+    pub fn unlocks(&self, key: &str) -> Vec<&str> {
+        self.nodes
+            .values()
+            .filter(|n| n.requires.iter().any(|r| r == key))
+            .map(|n| n.title.as_str())
+            .collect()
+    }
+
+    /// Explain why the lesson cannot be started, if it cannot
+    pub fn lock_cause(&self, key: &str) -> Option<String> {
+        let blockers: Vec<String> = self
+            .blockers(key)
+            .into_iter()
+            .map(|b| format!("\"{}\"", self.title(b)))
+            .collect();
+        complete_first(&blockers)
+    }
+
+    /// Find where the lesson sits among the other lessons with the same prerequisites. Lessons
+    /// that are the only one unlocked by their prerequisites are not in a branch
+    pub fn branch(&self, key: &str) -> Option<Branch> {
+        let node = self.nodes.get(key)?;
+        let siblings: Vec<&str> = self
+            .nodes
+            .iter()
+            .filter(|(_, n)| same_lessons(&n.requires, &node.requires))
+            .map(|(k, _)| k.as_str())
+            .collect();
+        match siblings.as_slice() {
+            [] | [_] => None,
+            [.., last] if *last == key => Some(Branch::Last),
+            _ => Some(Branch::Next),
+        }
+    }
+
+    /// Check if every lesson that is not optional is completed
+    pub fn is_finished(&self) -> bool {
+        self.nodes.values().all(|n| n.optional || n.completed)
+    }
+
+    // get the title of a lesson, falling back to its key
+    fn title<'a>(&'a self, key: &'a str) -> &'a str {
+        self.nodes.get(key).map(|n| n.title.as_str()).unwrap_or(key)
+    }
+}
+
+/// Tell the user what to complete before something is unlocked
+pub fn complete_first(blockers: &[String]) -> Option<String> {
+    match blockers {
+        [] => None,
+        [one] => Some(format!("Complete {one} first")),
+        [rest @ .., last] => Some(format!("Complete {} and {last} first", rest.join(", "))),
+    }
+}
+
+// check if a lesson is among the lessons it requires, directly or through other lessons
+fn requires_itself(nodes: &BTreeMap<String, Node>, key: &str) -> bool {
+    let mut visited = Vec::new();
+    let mut pending: Vec<&str> = vec![key];
+    while let Some(next) = pending.pop() {
+        for required in nodes
+            .get(next)
+            .map(|n| n.requires.as_slice())
+            .unwrap_or(&[])
+        {
+            if required == key {
+                return true;
+            }
+            if !visited.contains(&required.as_str()) {
+                visited.push(required.as_str());
+                pending.push(required);
+            }
+        }
+    }
+    false
+}
+
+// check if two lists name the same lessons, in any order
+fn same_lessons(a: &[String], b: &[String]) -> bool {
+    a.len() == b.len() && a.iter().all(|x| b.contains(x))
+}
+
 pub type Metadata = Arc<RwLock<LazyLoader<Lesson>>>;
 pub type LessonText = Arc<RwLock<LazyLoader<String>>>;
 
@@ -106,18 +346,25 @@ impl LessonData {
         metadata.try_load().await.cloned()
     }
 
-    /// updates the lesson status and saves it to the lesson.yaml file
-    pub async fn update_status(&self, new_status: Status) -> Result<(), Error> {
+    /// updates the lesson status and saves it to the lesson.yaml file unless it is read-only. The
+    /// file is read again first, so progress that another instance of the app saved is kept.
+    pub async fn update_status(&self, new_status: Status, read_only: bool) -> Result<(), Error> {
         let mut metadata = self.metadata.write().await;
 
         // Ensure metadata is loaded
         let mut lesson = metadata.try_load().await.cloned()?;
-        lesson.status = new_status;
-
-        // Save the updated metadata back to the file
         let lesson_yaml_path = self.path.join("lesson.yaml");
-        let content = serde_yaml::to_string(&lesson)?;
-        std::fs::write(&lesson_yaml_path, content)?;
+        if let Ok(saved) = schema::read::<Lesson>(&lesson_yaml_path) {
+            lesson = saved;
+        }
+        lesson.status = new_status.max(lesson.status);
+
+        // Save the updated metadata back to the file, unless another instance owns the state
+        if read_only {
+            warn!("Not saving {}: read-only", lesson_yaml_path.display());
+        } else {
+            schema::write(&lesson_yaml_path, &lesson)?;
+        }
 
         // Update the cached metadata
         *metadata = crate::fs::LazyLoader::Loaded(lesson);
@@ -254,3 +501,126 @@ impl Loader {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lesson(title: &str, status: Status, requires: Option<&[&str]>, optional: bool) -> Lesson {
+        Lesson {
+            title: title.to_string(),
+            description: String::default(),
+            status,
+            pre_check: None,
+            edit: None,
+            requires: requires.map(|r| r.iter().map(|s| s.to_string()).collect()),
+            optional,
+        }
+    }
+
+    #[test]
+    fn test_graph() {
+        let lessons: Vec<(String, Lesson)> = vec![
+            ("01-intro", lesson("Intro", Status::Completed, None, false)),
+            ("02-tcp", lesson("TCP", Status::Completed, None, false)),
+            ("03-extra", lesson("Extra", Status::NotStarted, None, true)),
+            (
+                "04-quic",
+                lesson("QUIC", Status::NotStarted, Some(&["01-intro"]), false),
+            ),
+            (
+                "05-final",
+                lesson(
+                    "Final",
+                    Status::NotStarted,
+                    Some(&["02-tcp", "04-quic", "99-gone"]),
+                    false,
+                ),
+            ),
+            ("06-next", lesson("Next", Status::NotStarted, None, false)),
+        ]
+        .into_iter()
+        .map(|(k, l)| (k.to_string(), l))
+        .collect();
+        let graph = Graph::new(lessons.iter().map(|(k, l)| (k, l)));
+
+        // optional lessons follow the linear default but do not unlock the next lesson
+        assert_eq!(graph.requires("03-extra"), vec!["TCP"]);
+        assert!(graph.is_available("03-extra"));
+        assert!(graph.is_optional("03-extra"));
+        assert_eq!(graph.requires("06-next"), vec!["Final"]);
+
+        // unknown lessons are ignored
+        assert_eq!(graph.requires("05-final"), vec!["TCP", "QUIC"]);
+        assert!(graph.is_available("04-quic"));
+        assert!(!graph.is_available("05-final"));
+        assert_eq!(
+            graph.lock_cause("05-final").as_deref(),
+            Some("Complete \"QUIC\" first")
+        );
+        assert_eq!(graph.lock_cause("04-quic"), None);
+        assert_eq!(graph.unlocks("01-intro"), vec!["TCP", "QUIC"]);
+
+        // 02-tcp and 04-quic both only need 01-intro
+        assert_eq!(graph.branch("02-tcp"), Some(Branch::Next));
+        assert_eq!(graph.branch("04-quic"), Some(Branch::Last));
+        assert_eq!(graph.branch("05-final"), None);
+
+        assert!(!graph.is_finished());
+    }
+
+    #[test]
+    fn test_edit_path() {
+        let root = Path::new("/project");
+        let mut lesson = lesson("Intro", Status::NotStarted, None, false);
+        assert_eq!(lesson.edit_path(root), None);
+
+        lesson.edit = Some("src/main.rs".to_string());
+        assert_eq!(
+            lesson.edit_path(root),
+            Some(PathBuf::from("/project/src/main.rs"))
+        );
+
+        for outside in ["/etc/passwd", "../other/main.rs", "src/../../main.rs"] {
+            lesson.edit = Some(outside.to_string());
+            assert_eq!(lesson.edit_path(root), None, "{outside}");
+        }
+    }
+
+    #[test]
+    fn test_graph_cycle() {
+        let lessons: Vec<(String, Lesson)> = vec![
+            ("01-intro", lesson("Intro", Status::NotStarted, None, false)),
+            (
+                "02-a",
+                lesson("A", Status::NotStarted, Some(&["03-b", "01-intro"]), false),
+            ),
+            (
+                "03-b",
+                lesson("B", Status::NotStarted, Some(&["02-a"]), false),
+            ),
+            (
+                "04-c",
+                lesson("C", Status::NotStarted, Some(&["03-b", "99-gone"]), false),
+            ),
+            (
+                "05-d",
+                lesson("D", Status::NotStarted, Some(&["99-gone"]), false),
+            ),
+        ]
+        .into_iter()
+        .map(|(k, l)| (k.to_string(), l))
+        .collect();
+        let graph = Graph::new(lessons.iter().map(|(k, l)| (k, l)));
+
+        // the lessons in the cycle are unlocked, the lessons after them are not
+        assert!(graph.is_available("02-a"));
+        assert!(graph.is_available("03-b"));
+        assert!(graph.requires("03-b").is_empty());
+        assert!(!graph.is_available("04-c"));
+        assert_eq!(graph.requires("04-c"), vec!["B"]);
+
+        // a lesson that only requires unknown lessons is unlocked
+        assert!(graph.is_available("05-d"));
+    }
+}
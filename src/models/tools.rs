@@ -0,0 +1,201 @@
+use crate::{fs::application::parse_version, models::Error};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+/// A tool on the host that workshops run
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Tool {
+    /// python runs the dependency and solution check scripts
+    Python,
+    /// docker compose builds and runs the solution checks
+    Docker,
+    /// git installs workshops and saves checkpoints
+    Git,
+}
+
+impl Tool {
+    /// Every tool, in the order they are detected
+    pub const ALL: [Tool; 3] = [Tool::Python, Tool::Docker, Tool::Git];
+
+    /// The name to show the user
+    pub fn name(&self) -> &'static str {
+        match self {
+            Tool::Python => "Python",
+            Tool::Docker => "Docker Compose",
+            Tool::Git => "Git",
+        }
+    }
+
+    /// The name used for the tool in `workshop.yaml`
+    pub fn key(&self) -> &'static str {
+        match self {
+            Tool::Python => "python",
+            Tool::Docker => "docker",
+            Tool::Git => "git",
+        }
+    }
+}
+
+impl fmt::Display for Tool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Tool {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "python" | "python3" => Ok(Tool::Python),
+            "docker" | "docker-compose" | "docker compose" => Ok(Tool::Docker),
+            "git" => Ok(Tool::Git),
+            _ => Err(Error::InvalidToolRequirement(s.to_string())),
+        }
+    }
+}
+
+/// A tool a workshop needs, written as `docker` or `python>=3.10` in `workshop.yaml`
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Requirement {
+    /// the tool
+    pub tool: Tool,
+    /// the oldest version that works, in addition to the minimum in the config
+    pub min_version: Option<Version>,
+}
+
+impl Requirement {
+    /// Require a tool at any version
+    pub fn new(tool: Tool) -> Self {
+        Self {
+            tool,
+            min_version: None,
+        }
+    }
+
+    /// Check if a detected version of the tool meets the requirement
+    pub fn is_met(&self, version: Option<&Version>) -> bool {
+        match (version, &self.min_version) {
+            (Some(version), Some(min_version)) => version >= min_version,
+            (Some(_), None) => true,
+            (None, _) => false,
+        }
+    }
+
+    /// Describe why the requirement is not met by the detected version of the tool
+    pub fn unmet(&self, version: Option<&Version>) -> String {
+        match version {
+            Some(version) => format!("{self} (found {version})"),
+            None => self.to_string(),
+        }
+    }
+}
+
+/// A tool as it was detected on the host
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Detected {
+    /// the tool
+    pub tool: Tool,
+    /// the executable that runs it, if one was found
+    pub executable: Option<String>,
+    /// the version the executable reported
+    pub version: Option<Version>,
+    /// the minimum version from the config
+    pub minimum: String,
+}
+
+/// The tools a workshop needs when its `workshop.yaml` does not say
+pub fn defaults() -> Vec<Requirement> {
+    vec![
+        Requirement::new(Tool::Python),
+        Requirement::new(Tool::Docker),
+    ]
+}
+
+impl fmt::Display for Requirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.min_version {
+            Some(min_version) => write!(f, "{} >= {min_version}", self.tool),
+            None => write!(f, "{}", self.tool),
+        }
+    }
+}
+
+impl FromStr for Requirement {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((tool, min_version)) = s.split_once(">=") else {
+            return Ok(Self::new(s.parse()?));
+        };
+        let min_version = parse_version(min_version.trim())
+            .ok_or_else(|| Error::InvalidToolRequirement(s.to_string()))?;
+        Ok(Self {
+            tool: tool.parse()?,
+            min_version: Some(min_version),
+        })
+    }
+}
+
+impl TryFrom<String> for Requirement {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Requirement> for String {
+    fn from(requirement: Requirement) -> Self {
+        match &requirement.min_version {
+            Some(min_version) => format!("{}>={min_version}", requirement.tool.key()),
+            None => requirement.tool.key().to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_requirements() {
+        let requirements: Vec<Requirement> =
+            serde_yaml::from_str("[docker, python>=3.10, git >= 2.40.1]").unwrap();
+        assert_eq!(
+            requirements,
+            vec![
+                Requirement::new(Tool::Docker),
+                Requirement {
+                    tool: Tool::Python,
+                    min_version: Some(Version::new(3, 10, 0)),
+                },
+                Requirement {
+                    tool: Tool::Git,
+                    min_version: Some(Version::new(2, 40, 1)),
+                },
+            ]
+        );
+        assert!(serde_yaml::from_str::<Vec<Requirement>>("[node]").is_err());
+        assert!(serde_yaml::from_str::<Vec<Requirement>>("[python>=three]").is_err());
+
+        // a requirement survives the round trip through yaml
+        let yaml = serde_yaml::to_string(&requirements).unwrap();
+        assert_eq!(
+            serde_yaml::from_str::<Vec<Requirement>>(&yaml).unwrap(),
+            requirements
+        );
+
+        let python = &requirements[1];
+        assert!(python.is_met(Some(&Version::new(3, 12, 3))));
+        assert!(!python.is_met(Some(&Version::new(3, 9, 6))));
+        assert!(!python.is_met(None));
+        assert_eq!(
+            python.unmet(Some(&Version::new(3, 9, 6))),
+            "Python >= 3.10.0 (found 3.9.6)"
+        );
+        assert_eq!(requirements[0].unmet(None), "Docker Compose");
+    }
+}
@@ -1,7 +1,10 @@
 use crate::{
-    fs::{Error as FsError, LazyLoader, TryLoad},
+    fs::{
+        schema::{self, Migration, Versioned},
+        Error as FsError, LazyLoader, TryLoad,
+    },
     languages::{programming, spoken},
-    models::{lesson, Error as ModelError, LessonData},
+    models::{lesson, tools, Error as ModelError, LessonData},
     Error,
 };
 use serde::{Deserialize, Serialize};
@@ -12,10 +15,10 @@ use std::{
     sync::Arc,
 };
 use tokio::sync::RwLock;
-use tracing::trace;
+use tracing::{trace, warn};
 
-/// Represents the status of a Workshop
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+/// Represents the status of a Workshop, in the order of progress
+#[derive(Clone, Debug, Default, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
 pub enum Status {
     /// The workshop is not started
     #[default]
@@ -46,6 +49,75 @@ pub struct Workshop {
     pub homepage: String,
     pub difficulty: String,
     pub status: Status,
+    /// The workshops in the same series that must be worked through before this one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub requires_workshops: Vec<Requirement>,
+    /// The tools on the host that the workshop needs, e.g. `[docker, python>=3.10]`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requires_tools: Option<Vec<tools::Requirement>>,
+}
+
+impl Workshop {
+    /// Get the tools the workshop needs, falling back to Python and Docker Compose
+    pub fn required_tools(&self) -> Vec<tools::Requirement> {
+        self.requires_tools.clone().unwrap_or_else(tools::defaults)
+    }
+}
+
+/// A workshop that must be worked through before another one can be started
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Requirement {
+    /// The folder name of the required workshop
+    pub name: String,
+    /// The number of its lessons that must be completed. Without it the workshop must be finished
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_lessons: Option<usize>,
+}
+
+impl Requirement {
+    /// Check if the progress in the required workshop meets the requirement
+    pub fn is_met(&self, progress: &Progress) -> bool {
+        match self.min_lessons {
+            Some(min_lessons) => progress.completed >= min_lessons,
+            None => progress.finished,
+        }
+    }
+}
+
+/// The progress through the lessons of a workshop
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Progress {
+    /// the number of completed lessons
+    pub completed: usize,
+    /// the number of lessons in progress
+    pub in_progress: usize,
+    /// every lesson that is not optional is completed
+    pub finished: bool,
+}
+
+/// Order workshops so that each one comes after the workshops it requires, otherwise by title
+pub fn series_order<'a, I>(workshops: I) -> Vec<&'a String>
+where
+    I: IntoIterator<Item = (&'a String, &'a Workshop)>,
+{
+    let mut remaining: Vec<(&String, &Workshop)> = workshops.into_iter().collect();
+    remaining.sort_by(|a, b| a.1.title.cmp(&b.1.title).then(a.0.cmp(b.0)));
+    let mut ordered: Vec<&String> = Vec::with_capacity(remaining.len());
+    while !remaining.is_empty() {
+        // take the first workshop whose requirements are already placed, or the first one if
+        // the requirements form a cycle
+        let next = remaining
+            .iter()
+            .position(|(_, w)| {
+                w.requires_workshops.iter().all(|r| {
+                    ordered.iter().any(|k| **k == r.name)
+                        || !remaining.iter().any(|(k, _)| **k == r.name)
+                })
+            })
+            .unwrap_or(0);
+        ordered.push(remaining.remove(next).0);
+    }
+    ordered
 }
 
 /// Represents the default spoken and programming language for a workshop
@@ -55,12 +127,16 @@ pub struct Defaults {
     pub programming_language: programming::Code,
 }
 
+impl Versioned for Workshop {
+    const FILE: &'static str = "workshop.yaml";
+    const MIGRATIONS: &'static [Migration] = &[schema::unversioned];
+}
+
 #[async_trait::async_trait]
 impl TryLoad for Workshop {
     type Error = Error;
     async fn try_load(path: &Path) -> Result<Self, Error> {
-        let content = std::fs::read_to_string(path)?;
-        Ok(serde_yaml::from_str(&content)?)
+        schema::read(path)
     }
 }
 
@@ -555,41 +631,74 @@ impl WorkshopData {
         let spoken = status_spoken.unwrap_or(self.defaults.spoken_language);
         let programming = status_programming.unwrap_or(self.defaults.programming_language);
 
+        let progress = self.get_progress(Some(spoken), Some(programming)).await?;
+
+        if progress.finished {
+            Ok(Status::Completed)
+        } else if progress.in_progress > 0 || progress.completed > 0 {
+            Ok(Status::InProgress)
+        } else {
+            Ok(Status::NotStarted)
+        }
+    }
+
+    /// The lessons that the given lesson requires, the latest one first. Their checkpoints hold
+    /// the project from before the lesson was started
+    pub async fn get_predecessors(
+        &self,
+        lesson: &str,
+        spoken_language: Option<spoken::Code>,
+        programming_language: Option<programming::Code>,
+    ) -> Result<Vec<String>, Error> {
         let lessons = self
-            .get_lessons_data(Some(spoken), Some(programming))
+            .get_lessons_data(spoken_language, programming_language)
             .await?;
-
-        if lessons.is_empty() {
-            return Ok(Status::NotStarted);
+        let mut metadata = Vec::with_capacity(lessons.len());
+        for (key, lesson_data) in lessons.iter() {
+            metadata.push((key, lesson_data.get_metadata().await?));
         }
+        let graph = lesson::Graph::new(metadata.iter().map(|(k, l)| (*k, l)));
+        Ok(graph
+            .predecessors(lesson)
+            .into_iter()
+            .map(String::from)
+            .collect())
+    }
 
-        let mut completed_count = 0;
-        let mut in_progress_count = 0;
-        let total_count = lessons.len();
+    /// Count the completed lessons for the given languages
+    pub async fn get_progress(
+        &self,
+        spoken_language: Option<spoken::Code>,
+        programming_language: Option<programming::Code>,
+    ) -> Result<Progress, Error> {
+        let lessons = self
+            .get_lessons_data(spoken_language, programming_language)
+            .await?;
 
-        for lesson_data in lessons.values() {
+        let mut progress = Progress::default();
+        let mut metadata = Vec::with_capacity(lessons.len());
+        for (key, lesson_data) in lessons.iter() {
             let lesson = lesson_data.get_metadata().await?;
             match lesson.status {
-                lesson::Status::Completed => completed_count += 1,
-                lesson::Status::InProgress => in_progress_count += 1,
+                lesson::Status::Completed => progress.completed += 1,
+                lesson::Status::InProgress => progress.in_progress += 1,
                 lesson::Status::NotStarted => {}
             }
+            metadata.push((key, lesson));
         }
 
-        if completed_count == total_count {
-            Ok(Status::Completed)
-        } else if in_progress_count > 0 || completed_count > 0 {
-            Ok(Status::InProgress)
-        } else {
-            Ok(Status::NotStarted)
-        }
+        // optional lessons are not needed to finish the workshop
+        progress.finished = !metadata.is_empty()
+            && lesson::Graph::new(metadata.iter().map(|(k, l)| (*k, l))).is_finished();
+        Ok(progress)
     }
 
-    /// updates the workshop status and saves it to the workshop.yaml file
+    /// updates the workshop status and saves it to the workshop.yaml file unless it is read-only
     pub async fn update_status(
         &self,
         status_spoken: Option<spoken::Code>,
         new_status: Status,
+        read_only: bool,
     ) -> Result<(), Error> {
         // Use status languages or fall back to defaults
         let spoken = status_spoken.unwrap_or(self.defaults.spoken_language);
@@ -614,14 +723,21 @@ impl WorkshopData {
             .write() // get a write lock on the Arc<RwLock<LazyLoader<Workshop>>>
             .await;
 
-        // Ensure workshop is loaded
+        // Ensure workshop is loaded, then read it again to keep the progress that another
+        // instance of the app saved
         let mut workshop = metadata.try_load().await.cloned()?;
-        workshop.status = new_status;
-
-        // Save the updated metadata back to the file
         let workshop_yaml_path = self.get_workshop_path(Some(spoken))?;
-        let content = serde_yaml::to_string(&workshop)?;
-        std::fs::write(&workshop_yaml_path, content)?;
+        if let Ok(saved) = schema::read::<Workshop>(&workshop_yaml_path) {
+            workshop = saved;
+        }
+        workshop.status = new_status.max(workshop.status);
+
+        // Save the updated metadata back to the file, unless another instance owns the state
+        if read_only {
+            warn!("Not saving {}: read-only", workshop_yaml_path.display());
+        } else {
+            schema::write(&workshop_yaml_path, &workshop)?;
+        }
 
         // Update the cached metadata
         *metadata = crate::fs::LazyLoader::Loaded(workshop);
@@ -880,3 +996,71 @@ impl Loader {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workshop(title: &str, requires: &[(&str, Option<usize>)]) -> Workshop {
+        Workshop {
+            title: title.to_string(),
+            authors: Vec::default(),
+            copyright: String::default(),
+            license: String::default(),
+            homepage: String::default(),
+            difficulty: String::default(),
+            status: Status::NotStarted,
+            requires_workshops: requires
+                .iter()
+                .map(|(name, min_lessons)| Requirement {
+                    name: name.to_string(),
+                    min_lessons: *min_lessons,
+                })
+                .collect(),
+            requires_tools: None,
+        }
+    }
+
+    #[test]
+    fn test_series_order() {
+        let workshops: HashMap<String, Workshop> = [
+            ("advanced", workshop("A: Advanced", &[("basics", None)])),
+            ("basics", workshop("Z: Basics", &[])),
+            ("extras", workshop("M: Extras", &[("missing", None)])),
+            ("expert", workshop("B: Expert", &[("advanced", Some(2))])),
+        ]
+        .into_iter()
+        .map(|(k, w)| (k.to_string(), w))
+        .collect();
+
+        let order: Vec<&str> = series_order(workshops.iter())
+            .into_iter()
+            .map(String::as_str)
+            .collect();
+        assert_eq!(order, vec!["extras", "basics", "advanced", "expert"]);
+    }
+
+    #[test]
+    fn test_requirement_is_met() {
+        let finished = Requirement {
+            name: "basics".to_string(),
+            min_lessons: None,
+        };
+        let two = Requirement {
+            name: "basics".to_string(),
+            min_lessons: Some(2),
+        };
+        let progress = Progress {
+            completed: 2,
+            in_progress: 1,
+            finished: false,
+        };
+        assert!(!finished.is_met(&progress));
+        assert!(two.is_met(&progress));
+        assert!(finished.is_met(&Progress {
+            finished: true,
+            ..progress
+        }));
+        assert!(!two.is_met(&Progress::default()));
+    }
+}
@@ -1,6 +1,12 @@
 use crate::{
-    fs,
+    config::Field,
+    fs::{
+        self,
+        schema::{self, Migration, Versioned},
+    },
     languages::{programming, spoken},
+    models::tools::Tool,
+    ui::tui::{Keymap, ThemeConfig},
     Config, Error,
 };
 use serde::{Deserialize, Serialize};
@@ -21,21 +27,31 @@ pub struct Status {
     lesson: Option<String>,
     #[serde(skip)]
     config: Config,
+    /// the status as it was loaded, to merge with the changes another instance saved
+    #[serde(skip)]
+    loaded: Option<Box<Status>>,
+    /// another instance of the app owns the project's state, so it is not saved
+    #[serde(skip)]
+    read_only: bool,
+}
+
+impl Versioned for Status {
+    const FILE: &'static str = "status.yaml";
+    const MIGRATIONS: &'static [Migration] = &[schema::unversioned];
 }
 
 impl Status {
-    /// load/create status
-    pub fn load() -> Result<Self, Error> {
+    /// load/create status with the loaded config
+    pub fn load(config: Config) -> Result<Self, Error> {
         let span = info_span!("Config");
         let _enter = span.enter();
 
-        let config = Config::load()?;
         if let Some(path) = fs::workshops::data_dir().map(|d| d.join("status.yaml")) {
             if path.exists() {
                 // try to load it from the file
-                let mut status: Status = serde_yaml::from_reader(std::fs::File::open(&path)?)?;
+                let mut status: Status = fs::schema::read(&path)?;
                 status.config = config;
-                return Ok(status);
+                return Ok(status.remember());
             }
         }
 
@@ -49,22 +65,109 @@ impl Status {
             workshop: None,
             lesson: None,
             config,
-        })
+            loaded: None,
+            read_only: false,
+        }
+        .remember())
+    }
+
+    // keep a copy of the status as it is now to merge with later
+    fn remember(mut self) -> Self {
+        let mut loaded = self.clone();
+        loaded.loaded = None;
+        self.loaded = Some(Box::new(loaded));
+        self
+    }
+
+    // merge with the status another instance saved, keeping the fields this instance changed
+    // since it was loaded and taking the other instance's value for the rest
+    fn merged(&self, saved: Status) -> Status {
+        fn pick<T: Clone + PartialEq>(ours: &T, loaded: &T, theirs: T) -> T {
+            if ours == loaded {
+                theirs
+            } else {
+                ours.clone()
+            }
+        }
+
+        let Some(loaded) = &self.loaded else {
+            return self.clone();
+        };
+        // a lesson only makes sense in its workshop, so they are merged together
+        let (workshop, lesson) = pick(
+            &(self.workshop.clone(), self.lesson.clone()),
+            &(loaded.workshop.clone(), loaded.lesson.clone()),
+            (saved.workshop, saved.lesson),
+        );
+        Status {
+            python_executable: pick(
+                &self.python_executable,
+                &loaded.python_executable,
+                saved.python_executable,
+            ),
+            docker_compose_executable: pick(
+                &self.docker_compose_executable,
+                &loaded.docker_compose_executable,
+                saved.docker_compose_executable,
+            ),
+            git_executable: pick(
+                &self.git_executable,
+                &loaded.git_executable,
+                saved.git_executable,
+            ),
+            spoken_language: pick(
+                &self.spoken_language,
+                &loaded.spoken_language,
+                saved.spoken_language,
+            ),
+            programming_language: pick(
+                &self.programming_language,
+                &loaded.programming_language,
+                saved.programming_language,
+            ),
+            workshop,
+            lesson,
+            config: self.config.clone(),
+            loaded: None,
+            read_only: self.read_only,
+        }
     }
 
-    /// save the status to the given path
-    pub fn save(&self) -> Result<(), Error> {
+    /// save the status to the given path, merged with any changes another instance saved. The
+    /// merged status is what this instance continues with.
+    pub fn save(&mut self) -> Result<(), Error> {
         // if there is a workshops data directory, save the status there
         if let Some(path) = fs::workshops::data_dir().map(|d| d.join("status.yaml")) {
-            std::fs::create_dir_all(path.parent().unwrap())?;
-            info!("Status saved to: {}", path.display());
-            serde_yaml::to_writer(std::fs::File::create(path)?, &self)?;
+            if self.read_only {
+                info!("Not saving the status: another instance owns it");
+            } else {
+                std::fs::create_dir_all(path.parent().unwrap())?;
+                let status = match fs::schema::read::<Status>(&path) {
+                    Ok(saved) => self.merged(saved),
+                    Err(_) => self.clone(),
+                };
+                fs::schema::write(&path, &status)?;
+                info!("Status saved to: {}", path.display());
+
+                // later changes are merged with what is on disk now
+                *self = status.remember();
+            }
         }
         // save the config as well
         self.config.save()?;
         Ok(())
     }
 
+    /// Check if another instance of the app owns the project's state, so it must not be saved
+    pub fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Set whether another instance of the app owns the project's state
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+
     /// Get the minimum required Python version
     pub fn python_minimum_version(&self) -> &str {
         self.config.python_minimum_version()
@@ -95,6 +198,49 @@ impl Status {
         self.config.git_minimum_version()
     }
 
+    /// Get the preferred executable for a tool
+    pub fn executable(&self, tool: Tool) -> Option<&str> {
+        match tool {
+            Tool::Python => self.python_executable(),
+            Tool::Docker => self.docker_compose_executable(),
+            Tool::Git => self.git_executable(),
+        }
+    }
+
+    /// Get the minimum required version of a tool
+    pub fn minimum_version(&self, tool: Tool) -> &str {
+        match tool {
+            Tool::Python => self.python_minimum_version(),
+            Tool::Docker => self.docker_compose_minimum_version(),
+            Tool::Git => self.git_minimum_version(),
+        }
+    }
+
+    /// Get the config the status was created from
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Get the key bindings
+    pub fn keymap(&self) -> &Keymap {
+        self.config.keymap()
+    }
+
+    /// Get the color theme
+    pub fn theme(&self) -> &ThemeConfig {
+        self.config.theme()
+    }
+
+    /// Check if the solution should be checked when the editor exits
+    pub fn check_after_edit(&self) -> bool {
+        self.config.check_after_edit()
+    }
+
+    /// Check if the project should be committed and tagged when a lesson is completed
+    pub fn checkpoints(&self) -> bool {
+        self.config.checkpoints()
+    }
+
     /// Get the preferred spoken language
     pub fn spoken_language(&self) -> Option<spoken::Code> {
         self.spoken_language
@@ -144,6 +290,41 @@ impl Status {
         }
     }
 
+    /// Set or clear the preferred executable for a tool with optional default
+    pub fn set_executable(&mut self, tool: Tool, executable: Option<&str>, default: bool) {
+        let field = match tool {
+            Tool::Python => &mut self.python_executable,
+            Tool::Docker => &mut self.docker_compose_executable,
+            Tool::Git => &mut self.git_executable,
+        };
+        *field = executable.map(String::from);
+        if default {
+            self.config.set_executable(tool, executable);
+        }
+    }
+
+    /// Set a config field from text, or reset it to its default. The languages and executables
+    /// in the status follow the new value.
+    pub fn set_config(&mut self, field: Field, value: Option<&str>) -> Result<(), Error> {
+        match value {
+            Some(value) => self.config.set(field, value)?,
+            None => self.config.reset(field),
+        }
+        match field {
+            Field::SpokenLanguage => self.spoken_language = self.config.spoken_language(),
+            Field::ProgrammingLanguage => {
+                self.programming_language = self.config.programming_language()
+            }
+            Field::PythonExecutable => self.python_executable = self.config.python_executable(),
+            Field::DockerComposeExecutable => {
+                self.docker_compose_executable = self.config.docker_compose_executable()
+            }
+            Field::GitExecutable => self.git_executable = self.config.git_executable(),
+            _ => {}
+        }
+        Ok(())
+    }
+
     /// Set the spoken language with optional default
     pub fn set_spoken_language(&mut self, spoken_language: Option<spoken::Code>, default: bool) {
         self.spoken_language = spoken_language;
@@ -174,3 +355,36 @@ impl Status {
         self.lesson = lesson;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merged() {
+        let status = Status {
+            workshop: Some("rust-basics".to_string()),
+            lesson: Some("01".to_string()),
+            ..Default::default()
+        };
+        let mut ours = status.clone().remember();
+        ours.set_lesson(Some("02".to_string()));
+
+        // another instance picked a different workshop and lesson in the meantime
+        let theirs = Status {
+            spoken_language: Some(spoken::Code::de),
+            workshop: Some("rust-async".to_string()),
+            lesson: Some("05".to_string()),
+            ..Default::default()
+        };
+        let merged = ours.merged(theirs.clone());
+        assert_eq!(merged.spoken_language(), Some(spoken::Code::de));
+        assert_eq!(merged.workshop(), Some("rust-basics"));
+        assert_eq!(merged.lesson(), Some("02"));
+
+        // without a change here their workshop comes with their lesson
+        let merged = status.remember().merged(theirs);
+        assert_eq!(merged.workshop(), Some("rust-async"));
+        assert_eq!(merged.lesson(), Some("05"));
+    }
+}
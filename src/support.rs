@@ -0,0 +1,163 @@
+use crate::{fs::application, models::tools::Detected, Error};
+use std::{
+    fmt,
+    fs::OpenOptions,
+    io::{ErrorKind, Write},
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The folder in the log directory that saved logs and support bundles go to
+pub const EXPORTS: &str = "exports";
+
+/// Save text to a new file in the exports folder, named after what it holds and when it was
+/// saved, e.g. `log-1760000000.txt`. Returns the path of the file.
+pub fn save(name: &str, contents: &str) -> Result<PathBuf, Error> {
+    let dir = application::log_dir()?.join(EXPORTS);
+    std::fs::create_dir_all(&dir)?;
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+
+    // never overwrite a file saved earlier in the same second
+    for n in 1.. {
+        let path = match n {
+            1 => dir.join(format!("{name}-{secs}.txt")),
+            n => dir.join(format!("{name}-{secs}-{n}.txt")),
+        };
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                file.write_all(contents.as_bytes())?;
+                return Ok(path);
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    unreachable!()
+}
+
+/// Everything someone helping a learner needs to know, written as one text file that can be
+/// attached to a question or an issue
+#[derive(Clone, Debug, Default)]
+pub struct Bundle {
+    /// the selected workshop
+    pub workshop: Option<String>,
+    /// the selected lesson
+    pub lesson: Option<String>,
+    /// the last line of the last command, if it failed
+    pub failed: Option<String>,
+    /// the tools as they were detected, left out if detecting them failed
+    pub tools: Vec<Detected>,
+    /// the contents of the project's `status.yaml`
+    pub status: Option<String>,
+    /// the lines of the log
+    pub log: Vec<String>,
+}
+
+impl fmt::Display for Bundle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let or_none = |value: &Option<String>| value.clone().unwrap_or("none".to_string());
+        writeln!(f, "# Workshop support bundle")?;
+        writeln!(f)?;
+        writeln!(f, "version: {}", env!("CARGO_PKG_VERSION"))?;
+        writeln!(
+            f,
+            "system: {} {}",
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        )?;
+        writeln!(f, "workshop: {}", or_none(&self.workshop))?;
+        writeln!(f, "lesson: {}", or_none(&self.lesson))?;
+        match &self.failed {
+            Some(failed) => writeln!(f, "last command: failed: {failed}")?,
+            None => writeln!(f, "last command: did not fail")?,
+        }
+
+        if !self.tools.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "## Tools")?;
+            writeln!(f)?;
+        }
+        for detected in &self.tools {
+            let version = match &detected.version {
+                Some(version) => version.to_string(),
+                None => "not found".to_string(),
+            };
+            write!(
+                f,
+                "{}: {version} (minimum {})",
+                detected.tool, detected.minimum
+            )?;
+            match &detected.executable {
+                Some(executable) => writeln!(f, " at {executable}")?,
+                None => writeln!(f)?,
+            }
+        }
+
+        writeln!(f)?;
+        writeln!(f, "## status.yaml")?;
+        writeln!(f)?;
+        match &self.status {
+            Some(status) => writeln!(f, "{}", status.trim_end())?,
+            None => writeln!(f, "not saved yet")?,
+        }
+
+        writeln!(f)?;
+        writeln!(f, "## Log")?;
+        writeln!(f)?;
+        for line in &self.log {
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::tools::Tool;
+    use semver::Version;
+
+    #[test]
+    fn test_bundle() {
+        let bundle = Bundle {
+            workshop: Some("intro".to_string()),
+            lesson: Some("02-loops".to_string()),
+            failed: Some("1 test failed".to_string()),
+            tools: vec![
+                Detected {
+                    tool: Tool::Python,
+                    executable: Some("/usr/bin/python3".to_string()),
+                    version: Some(Version::new(3, 12, 1)),
+                    minimum: "3.10.0".to_string(),
+                },
+                Detected {
+                    tool: Tool::Git,
+                    executable: None,
+                    version: None,
+                    minimum: "2.0.0".to_string(),
+                },
+            ],
+            status: None,
+            log: vec!["❌ check failed".to_string()],
+        };
+        let text = bundle.to_string();
+        assert!(text.contains("lesson: 02-loops\n"));
+        assert!(text.contains("last command: failed: 1 test failed\n"));
+        assert!(text.contains("3.12.1 (minimum 3.10.0) at /usr/bin/python3\n"));
+        assert!(text.contains("not found (minimum 2.0.0)\n"));
+        assert!(text.contains("## status.yaml\n\nnot saved yet\n"));
+        assert!(text.ends_with("## Log\n\n❌ check failed\n"));
+
+        // the tools are left out when they could not be detected
+        let text = Bundle {
+            tools: Vec::new(),
+            ..bundle
+        }
+        .to_string();
+        assert!(!text.contains("## Tools"));
+        assert!(text.contains("1 test failed\n\n## status.yaml\n"));
+    }
+}
@@ -1,7 +1,14 @@
+pub mod clipboard;
+
 pub mod events;
 pub use events::{Event, Evt};
 
+pub use crate::keymap::{Action, Keymap};
+
 pub mod screens;
 pub use screens::{Screen, Screens};
 
+pub mod theme;
+pub use theme::{Theme, ThemeConfig};
+
 pub mod widgets;
@@ -0,0 +1,66 @@
+use crate::Error;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use std::io::Write;
+
+/// Build the OSC 52 escape sequence that asks the terminal to put text on the clipboard. Inside
+/// tmux the sequence is wrapped so that tmux passes it on to the terminal.
+pub fn osc52(text: &str, tmux: bool) -> String {
+    let sequence = format!("\x1b]52;c;{}\x07", STANDARD.encode(text));
+    if tmux {
+        format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+    } else {
+        sequence
+    }
+}
+
+/// The most text that is copied at once. Many terminals and tmux drop OSC 52 sequences that are
+/// much longer than this.
+pub const MAX_COPY: usize = 64 * 1024;
+
+/// Copy text to the clipboard through the terminal, which also works over SSH. Terminals that do
+/// not support OSC 52 ignore it. Text longer than [`MAX_COPY`] is cut to its last lines that fit.
+/// Returns if the text was cut.
+pub fn copy(text: &str) -> Result<bool, Error> {
+    let (text, cut) = tail(text, MAX_COPY);
+    let tmux = std::env::var_os("TMUX").is_some();
+    let mut stdout = std::io::stdout();
+    stdout.write_all(osc52(text, tmux).as_bytes())?;
+    stdout.flush()?;
+    Ok(cut)
+}
+
+// the end of the text that fits in the given number of bytes, starting at a line if it can
+fn tail(text: &str, max: usize) -> (&str, bool) {
+    if text.len() <= max {
+        return (text, false);
+    }
+    let mut start = text.len() - max;
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    let start = text[start..]
+        .find('\n')
+        .map(|i| start + i + 1)
+        .filter(|start| *start < text.len())
+        .unwrap_or(start);
+    (&text[start..], true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_osc52() {
+        assert_eq!(osc52("hi", false), "\x1b]52;c;aGk=\x07");
+        assert_eq!(osc52("hi", true), "\x1bPtmux;\x1b\x1b]52;c;aGk=\x07\x1b\\");
+    }
+
+    #[test]
+    fn test_tail() {
+        assert_eq!(tail("one\ntwo", 10), ("one\ntwo", false));
+        assert_eq!(tail("one\ntwo\nthree", 10), ("two\nthree", true));
+        // a line that is too long is cut in the middle, but not inside of a character
+        assert_eq!(tail("ab❌cd", 4), ("cd", true));
+    }
+}
@@ -1,9 +1,12 @@
 use crate::{
     command::CommandResult,
+    config::Field,
+    diagnostics::Diagnostic,
     languages::{programming, spoken},
+    models::tools::Detected,
     ui::tui::{screens::Screens, widgets::StatusMode},
 };
-use std::collections::HashMap;
+use std::{collections::HashMap, path::PathBuf};
 use tokio::time::Duration;
 
 /// a type alias defining a targeted event
@@ -82,17 +85,64 @@ pub enum Event {
     /// check dependendcies for the specified workshop
     CheckDeps(String, Option<Evt>, Option<Evt>),
     /// check the solutionto the lesson
-    CheckSolution(Option<Evt>, Option<Evt>),
+    CheckSolution(
+        bool,        // show the log while checking?
+        Option<Evt>, // the event to send when the check passes
+        Option<Evt>, // the event to send when the check fails
+    ),
+    /// the watched project files changed
+    FilesChanged,
+    /// check the solution to the current lesson
+    CheckLesson,
+    /// replace the project's versions of the starter files with the starter code
+    ResetStarter,
     /// the solution is correct
     SolutionComplete,
     /// the solution is incorrect
     SolutionIncomplete,
+    /// commit and tag the project as the checkpoint for a completed lesson
+    Checkpoint(
+        String, // the workshop
+        String, // the lesson
+    ),
+    /// check out the checkpoint from before a lesson into a new branch
+    RestoreCheckpoint(
+        String, // the workshop
+        String, // the lesson
+    ),
     /// command started (show log screen)
     CommandStarted(StatusMode, String),
     /// command output
     CommandOutput(String, Option<u8>),
     /// command completed
     CommandCompleted(CommandResult, Option<Evt>, Option<Evt>),
+    /// detect the tools and show them with the config in the settings
+    LoadSettings,
+    /// show the config fields and the detected tools in the settings
+    ShowSettings(
+        Vec<(Field, String)>,
+        Vec<Detected>,
+        Option<String>, // the outcome of the last change
+    ),
+    /// set a config field and save the config, or reset the field to its default if there is no
+    /// value
+    SetConfig(Field, Option<String>),
+    /// save a support bundle with the log, the status and the tool versions
+    SupportBundle(
+        Vec<String>,    // the lines of the log
+        Option<String>, // the last line of the last command, if it failed
+    ),
     /// install a workshop from a URL
     InstallWorkshop(String, Option<Evt>),
+    /// forget the diagnostics from the last check
+    ClearDiagnostics,
+    /// a compiler diagnostic found in the command output
+    Diagnostic(Diagnostic),
+    /// open a file in the user's editor
+    OpenEditor(
+        PathBuf,
+        Option<usize>, // the line to go to
+        Option<usize>, // the column to go to
+        Option<Evt>,   // the event to send when the editor exits
+    ),
 }
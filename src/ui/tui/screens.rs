@@ -10,6 +10,8 @@ pub mod programming;
 pub use programming::Programming;
 pub mod set_default;
 pub use set_default::SetDefault;
+pub mod settings;
+pub use settings::Settings;
 pub mod spoken;
 pub use spoken::Spoken;
 pub mod welcome;
@@ -17,7 +19,10 @@ pub use welcome::Welcome;
 pub mod workshops;
 pub use workshops::Workshops;
 
-use crate::{ui::tui, Error, Status};
+use crate::{
+    ui::tui::{self, Theme},
+    Error, Status,
+};
 use crossterm::event;
 use ratatui::{buffer::Buffer, layout::Rect};
 use std::{
@@ -40,11 +45,12 @@ pub enum Screens {
     SetDefault,
     Lessons,
     Lesson,
+    Settings,
 }
 
 impl Screens {
     pub fn iter() -> impl Iterator<Item = Screens> {
-        (0..=8).map(Screens::from)
+        (0..=9).map(Screens::from)
     }
 }
 
@@ -60,6 +66,7 @@ impl fmt::Display for Screens {
             Screens::SetDefault => write!(f, "Set Default"),
             Screens::Lessons => write!(f, "Lessons"),
             Screens::Lesson => write!(f, "Lesson"),
+            Screens::Settings => write!(f, "Settings"),
         }
     }
 }
@@ -82,6 +89,7 @@ impl From<u8> for Screens {
             6 => Screens::SetDefault,
             7 => Screens::Lessons,
             8 => Screens::Lesson,
+            9 => Screens::Settings,
             _ => panic!("Invalid screen value"),
         }
     }
@@ -123,6 +131,11 @@ pub trait Screen: Send + Sync {
         status: Arc<Mutex<Status>>,
     ) -> Result<(), Error>;
 
-    /// Render the screen
-    fn render_screen(&mut self, area: Rect, buf: &mut Buffer) -> Result<(), Error>;
+    /// Render the screen with the given colors
+    fn render_screen(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) -> Result<(), Error>;
+
+    /// Check if the screen is taking text input, so every key goes to it
+    fn is_typing(&self) -> bool {
+        false
+    }
 }
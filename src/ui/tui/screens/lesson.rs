@@ -1,28 +1,41 @@
 use crate::{
     command::CommandResult,
-    evt, fs,
-    languages::{programming, spoken},
+    evt,
+    fs::{self, Watcher},
+    languages::{self, programming, spoken},
     models::{lesson, workshop, Error as ModelError, LessonData},
     ui::tui::{
         self,
-        screens::{self, Screens},
-        widgets::{LessonBox, LessonBoxState},
-        Screen,
+        screens::{self, log, Screens},
+        widgets::{mouse, LessonBox, LessonBoxState, ScrollLog},
+        Action, Keymap, Screen, Theme,
     },
     Error, Status,
 };
-use crossterm::event::{self, KeyCode};
+use crossterm::event;
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Constraint, Flex, Layout, Rect},
-    style::{Color, Style},
+    style::Style,
     symbols::border::Set,
     text::{Line, Span},
     widgets::{block::Position, Block, Borders, Padding, StatefulWidget, Widget},
 };
-use std::sync::{Arc, Mutex};
-use tokio::sync::mpsc::Sender;
-use tracing::{debug, info};
+use std::{
+    collections::VecDeque,
+    fmt,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+use tokio::{
+    sync::mpsc::Sender,
+    time::{Duration, Instant},
+};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info};
+
+/// how long the project files must stay unchanged before the solution is checked
+const WATCH_DEBOUNCE: Duration = Duration::from_secs(1);
 
 const TOP_BORDER: Set = Set {
     top_left: "┌",
@@ -46,102 +59,525 @@ const STATUS_BORDER: Set = Set {
     horizontal_bottom: "─",
 };
 
+/// The panes of the split view that can have focus
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+enum FocusedView {
+    #[default]
+    Lesson,
+    Output,
+}
+
+impl FocusedView {
+    /// the other pane
+    fn toggle(self) -> Self {
+        match self {
+            FocusedView::Lesson => FocusedView::Output,
+            FocusedView::Output => FocusedView::Lesson,
+        }
+    }
+}
+
+impl fmt::Display for FocusedView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FocusedView::Lesson => write!(f, "Lesson"),
+            FocusedView::Output => write!(f, "Check Output"),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct Lesson {
     /// the title of the workshop
     workshop_title: String,
     /// the title of the lesson
     lesson_title: String,
+    /// the lesson directory that relative image paths are resolved against
+    lesson_path: PathBuf,
     /// the lesson box state for rendering markdown content
     lesson_state: LessonBoxState,
+    /// the cached inner rect of the lesson box from last render
+    lesson_area: Rect,
+    /// the file or project directory to open in the editor
+    edit_path: Option<PathBuf>,
+    /// the project is committed and tagged when a lesson is completed
+    checkpoints: bool,
+    /// the lesson's starter code directory, if it has one
+    starter: Option<PathBuf>,
+    /// show the last check output beside the lesson
+    split: bool,
+    /// the focused pane when split
+    focused: FocusedView,
+    /// the output of the most recent solution check
+    output: VecDeque<(Option<String>, String)>,
+    /// the scroll log widget for the check output
+    output_log: ScrollLog<'static>,
+    /// the cached rect of the check output pane from last render
+    output_area: Rect,
+    /// collecting the output of a solution check that is running
+    checking: bool,
+    /// the cancelation token for watching the project files
+    watch: Option<CancellationToken>,
+    /// the running check was started by a file change
+    watch_check: bool,
+    /// the watch status shown in the status bar
+    watch_status: String,
     /// the currently selected spoken language
     spoken_language: Option<spoken::Code>,
     /// the currently selected programming language
     programming_language: Option<programming::Code>,
+    /// the key bindings
+    keymap: Keymap,
 }
 
 impl Lesson {
+    /// create a new lesson screen with the given key bindings
+    pub fn new(keymap: Keymap) -> Self {
+        Self {
+            keymap,
+            ..Default::default()
+        }
+    }
+
     /// set the lessons
     async fn init<S: AsRef<str>>(
         &mut self,
         workshop_title: S,
         lesson_title: S,
+        lesson_path: &Path,
         text: S,
         spoken_language: Option<spoken::Code>,
         programming_language: Option<programming::Code>,
     ) -> Result<(), Error> {
+        // the check output belongs to the lesson it was run for
+        if self.lesson_path != lesson_path {
+            self.output.clear();
+            self.focused = FocusedView::Lesson;
+            self.stop_watching();
+        }
         self.workshop_title = workshop_title.as_ref().to_string();
         self.lesson_title = lesson_title.as_ref().to_string();
+        self.lesson_path = lesson_path.to_path_buf();
         self.lesson_state = LessonBoxState::from_markdown(text.as_ref());
+        self.lesson_state
+            .set_direction(languages::spoken_direction(spoken_language));
         self.spoken_language = spoken_language;
         self.programming_language = programming_language;
         Ok(())
     }
 
-    /// check if all lessons in the workshop are completed
+    /// open the highlighted image, if any, in the system viewer or browser
+    fn open_highlighted_link(&self) {
+        let Some(link) = self.lesson_state.highlighted_link() else {
+            return;
+        };
+        let target = if link.contains("://") {
+            link.to_string()
+        } else {
+            self.lesson_path.join(link).display().to_string()
+        };
+        info!("Open image: {}", target);
+        if let Err(e) = webbrowser::open(&target) {
+            error!("Failed to open image: {}", e);
+        }
+    }
+
+    /// check the solution, showing the log unless the check was started by a file change
+    async fn check(&mut self, watch: bool, to_ui: &Sender<screens::Event>) -> Result<(), Error> {
+        // start collecting the output of this check
+        self.output.clear();
+        self.output_log.scroll_newest();
+        self.checking = true;
+        self.watch_check = watch;
+
+        let success = evt!(Screens::Lesson, tui::Event::SolutionComplete);
+        let check_solution = if watch {
+            self.watch_status = "checking".to_string();
+            evt!(None, tui::Event::CheckSolution(false, Some(success), None))
+        } else {
+            let failure = evt!(Screens::Lesson, tui::Event::SolutionIncomplete);
+            evt!(
+                None,
+                tui::Event::CheckSolution(true, Some(success), Some(failure)),
+            )
+        };
+        to_ui.send(check_solution.into()).await?;
+        Ok(())
+    }
+
+    /// open the lesson's file in the editor, checking the solution when it exits if configured
+    async fn edit(
+        &self,
+        to_ui: &Sender<screens::Event>,
+        status: &Arc<Mutex<Status>>,
+    ) -> Result<(), Error> {
+        let Some(path) = self.edit_path.clone() else {
+            error!("No project root found for: {}", self.lesson_path.display());
+            return Ok(());
+        };
+        let check_after_edit = status
+            .lock()
+            .map_err(|e| Error::StatusLock(e.to_string()))?
+            .check_after_edit();
+        let next = check_after_edit.then(|| evt!(Screens::Lesson, tui::Event::CheckLesson));
+        to_ui
+            .send((None, tui::Event::OpenEditor(path, None, None, next)).into())
+            .await?;
+        Ok(())
+    }
+
+    /// check out the checkpoint from before this lesson into a new branch
+    async fn restore(
+        &mut self,
+        to_ui: &Sender<screens::Event>,
+        status: &Arc<Mutex<Status>>,
+    ) -> Result<(), Error> {
+        if !self.checkpoints {
+            return Ok(());
+        }
+        let (workshop, lesson) = {
+            let status = status
+                .lock()
+                .map_err(|e| Error::StatusLock(e.to_string()))?;
+            (
+                status
+                    .workshop()
+                    .map(String::from)
+                    .ok_or(ModelError::NoWorkshopSpecified)?,
+                status
+                    .lesson()
+                    .map(String::from)
+                    .ok_or(ModelError::NoLessonSpecified)?,
+            )
+        };
+        // the restored files would trigger a check
+        self.stop_watching();
+        to_ui
+            .send(evt!(None, tui::Event::RestoreCheckpoint(workshop, lesson)).into())
+            .await?;
+        Ok(())
+    }
+
+    /// copy the lesson's starter code into the project, keeping the user's changed files
+    async fn seed_starter(&self, to_ui: &Sender<screens::Event>) -> Result<(), Error> {
+        let (Some(starter), Some(root)) = (
+            self.starter.as_deref(),
+            fs::watch::project_root(&self.lesson_path),
+        ) else {
+            return Ok(());
+        };
+        let overlay = match fs::starter::copy(starter, root, false) {
+            Ok(overlay) => overlay,
+            Err(e) => {
+                let reason = format!("Could not copy the starter code: {e}");
+                return report_failure(to_ui, reason).await;
+            }
+        };
+        info!(
+            "Copied {} starter files, {} conflicts",
+            overlay.copied.len(),
+            overlay.conflicts.len()
+        );
+        if overlay.conflicts.is_empty() {
+            return Ok(());
+        }
+
+        let mut messages: Vec<String> = overlay
+            .copied
+            .iter()
+            .map(|f| format!("v Copied {}", f.display()))
+            .collect();
+        messages.extend(
+            overlay
+                .conflicts
+                .iter()
+                .map(|f| format!("^ Kept your version of {}", f.display())),
+        );
+        let result = CommandResult {
+            success: false,
+            exit_code: 1,
+            last_line: format!(
+                "{} of your files differ from the starter code, press {} to replace them",
+                overlay.conflicts.len(),
+                self.keymap.key(Action::Reset)
+            ),
+            cancelled: false,
+        };
+        report(to_ui, messages, result).await
+    }
+
+    /// ask before replacing the user's versions of the starter files
+    async fn confirm_reset(&self, to_ui: &Sender<screens::Event>) -> Result<(), Error> {
+        if self.starter.is_none() {
+            return Ok(());
+        }
+        let back = evt!(None, tui::Event::Show(Screens::Lesson));
+        let reset = evt!(Screens::Lesson, tui::Event::ResetStarter);
+        let confirm = evt!(
+            Screens::SetDefault,
+            tui::Event::SetDefault(
+                "Reset to the starter code?".to_string(),
+                Some(reset),
+                Some(back),
+            )
+        );
+        to_ui.send(confirm.into()).await?;
+        Ok(())
+    }
+
+    /// back up the user's versions of the starter files and copy the starter code over them
+    async fn reset_starter(&mut self, to_ui: &Sender<screens::Event>) -> Result<(), Error> {
+        let Some(starter) = self.starter.clone() else {
+            return Ok(());
+        };
+        let Some(root) = fs::watch::project_root(&self.lesson_path).map(Path::to_path_buf) else {
+            error!("No project root found for: {}", self.lesson_path.display());
+            return Ok(());
+        };
+        // the reset files would trigger a check
+        self.stop_watching();
+
+        let stamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let lesson = self
+            .lesson_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let backup_dir = root
+            .join(".workshops")
+            .join("backups")
+            .join(format!("{lesson}-{stamp}"));
+
+        let backed_up = match fs::starter::backup(&starter, &root, &backup_dir) {
+            Ok(backed_up) => backed_up,
+            Err(e) => {
+                let reason = format!("Could not back up your files, nothing was reset: {e}");
+                return report_failure(to_ui, reason).await;
+            }
+        };
+        let overlay = match fs::starter::copy(&starter, &root, true) {
+            Ok(overlay) => overlay,
+            Err(e) => {
+                let reason = format!(
+                    "Could not reset the starter code, your files are backed up in {}: {e}",
+                    backup_dir.display()
+                );
+                return report_failure(to_ui, reason).await;
+            }
+        };
+        info!(
+            "Backed up {} files to {} and reset {} files",
+            backed_up.len(),
+            backup_dir.display(),
+            overlay.copied.len()
+        );
+
+        let mut messages = Vec::new();
+        if !backed_up.is_empty() {
+            messages.push(format!(
+                "i Backed up {} files to {}",
+                backed_up.len(),
+                backup_dir.display()
+            ));
+        }
+        messages.extend(
+            overlay
+                .copied
+                .iter()
+                .map(|f| format!("v Reset {}", f.display())),
+        );
+        let result = CommandResult {
+            success: true,
+            exit_code: 0,
+            last_line: format!("Reset {} files to the starter code", overlay.copied.len()),
+            cancelled: false,
+        };
+        report(to_ui, messages, result).await
+    }
+
+    /// start or stop watching the project files for changes
+    fn toggle_watch(&mut self, to_ui: &Sender<screens::Event>) {
+        if self.watch.is_some() {
+            self.stop_watching();
+            return;
+        }
+
+        let Some(root) = fs::watch::project_root(&self.lesson_path).map(Path::to_path_buf) else {
+            error!("No project root found for: {}", self.lesson_path.display());
+            return;
+        };
+        info!("Watching for changes: {}", root.display());
+
+        let token = CancellationToken::new();
+        self.watch = Some(token.clone());
+        self.watch_status = "watching".to_string();
+        tokio::spawn(watch(root, token, to_ui.clone()));
+    }
+
+    /// stop watching the project files
+    fn stop_watching(&mut self) {
+        if let Some(token) = self.watch.take() {
+            info!("Stopped watching for changes");
+            token.cancel();
+        }
+        self.watch_check = false;
+        self.watch_status.clear();
+    }
+
+    /// add a message to the check output if a check is running
+    fn add_output<S: AsRef<str>>(&mut self, msg: S) {
+        if !self.checking {
+            return;
+        }
+        if let Some(entry) = log::entry(msg) {
+            self.output.push_back(entry);
+        }
+    }
+
+    /// check if all lessons in the workshop that are not optional are completed
     async fn check_all_lessons_completed(
         &self,
         lessons: &std::collections::HashMap<String, LessonData>,
     ) -> Result<bool, Error> {
-        for lesson_data in lessons.values() {
-            let lesson = lesson_data.get_metadata().await?;
-            if !matches!(lesson.status, lesson::Status::Completed) {
-                return Ok(false);
-            }
+        let mut metadata = Vec::with_capacity(lessons.len());
+        for (key, lesson_data) in lessons.iter() {
+            metadata.push((key, lesson_data.get_metadata().await?));
+        }
+        Ok(lesson::Graph::new(metadata.iter().map(|(k, l)| (*k, l))).is_finished())
+    }
+
+    /// render the lesson, with the check output beside it when split
+    fn render_panes(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
+        if self.split {
+            let [lesson_area, output_area] =
+                Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                    .areas(area);
+            self.render_lesson(lesson_area, buf, theme);
+            self.render_output(output_area, buf, theme);
+        } else {
+            self.output_area = Rect::default();
+            self.render_lesson(area, buf, theme);
+        }
+    }
+
+    /// get the title color of a pane based on what is focused
+    fn title_style(&self, view: FocusedView, theme: &Theme) -> Style {
+        if !self.split || self.focused == view {
+            theme.text()
+        } else {
+            theme.border()
         }
-        Ok(true)
     }
 
     /// render the lesson
-    fn render_lesson(&mut self, area: Rect, buf: &mut Buffer) {
+    fn render_lesson(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
+        let style = self.title_style(FocusedView::Lesson, theme);
         let title = Line::from(vec![
-            Span::styled("─", Style::default().fg(Color::DarkGray)),
-            Span::styled(
-                format!("/ {} /", self.lesson_title),
-                Style::default().fg(Color::White),
-            ),
+            Span::styled("─", theme.border()),
+            Span::styled(format!("/ {} /", self.lesson_title), style),
         ]);
         let block = Block::default()
             .title(title)
-            .title_style(Style::default().fg(Color::White))
+            .title_style(style)
             .padding(Padding::uniform(1))
-            .style(Style::default().fg(Color::DarkGray))
+            .style(theme.border())
             .borders(Borders::LEFT | Borders::TOP | Borders::RIGHT)
             .border_set(TOP_BORDER);
+        self.lesson_area = block.inner(area);
 
         let lesson_widget = LessonBox::new()
             .block(block)
-            .style(Style::default().fg(Color::White));
+            .style(theme.text())
+            .theme(theme);
 
         // render the lesson box
         StatefulWidget::render(lesson_widget, area, buf, &mut self.lesson_state);
     }
 
+    /// render the output of the last solution check
+    fn render_output(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
+        let style = self.title_style(FocusedView::Output, theme);
+        let title = Line::from(vec![
+            Span::styled("─", theme.border()),
+            Span::styled(format!("/ {} /", FocusedView::Output), style),
+        ]);
+        let block = Block::default()
+            .title(title)
+            .title_style(style)
+            .padding(Padding::horizontal(1))
+            .style(theme.border())
+            .borders(Borders::LEFT | Borders::TOP | Borders::RIGHT)
+            .border_set(TOP_BORDER);
+        self.output_area = area;
+
+        self.output_log.block(block);
+        self.output_log.style(theme.text());
+
+        // render the scroll log
+        StatefulWidget::render(&mut self.output_log, area, buf, &mut self.output);
+    }
+
     // render the status bar at the bottom
-    fn render_status(&mut self, area: Rect, buf: &mut Buffer) {
+    fn render_status(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
         // render the status bar at the bottom
         let [keys_area, langs_area] =
             Layout::horizontal([Constraint::Min(1), Constraint::Length(46)]).areas(area);
 
-        self.render_keys(keys_area, buf);
-        self.render_langs(langs_area, buf);
+        self.render_keys(keys_area, buf, theme);
+        self.render_langs(langs_area, buf, theme);
     }
 
     // render the keyboard shortcuts
-    fn render_keys(&mut self, area: Rect, buf: &mut Buffer) {
-        let title = Line::from(vec![
-            Span::styled("─", Style::default().fg(Color::DarkGray)),
-            Span::styled(
-                "/ j,k scroll / ↵ expand hint / c check / b back / q quit /",
-                Style::default().fg(Color::White),
+    fn render_keys(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
+        let mut spans = vec![Span::styled("─", theme.border())];
+
+        // show the result of the last check while watching
+        if !self.watch_status.is_empty() {
+            let style = if self.watch_check {
+                Style::default().fg(theme.busy)
+            } else {
+                theme.text()
+            };
+            spans.push(Span::styled(format!("/ {} ", self.watch_status), style));
+        }
+
+        // the restore and reset keys only do something when checkpoints are turned on or the
+        // lesson has starter code
+        let mut restore = String::default();
+        if self.checkpoints {
+            restore.push_str(&format!("/ {} restore ", self.keymap.key(Action::Restore)));
+        }
+        if self.starter.is_some() {
+            restore.push_str(&format!("/ {} reset ", self.keymap.key(Action::Reset)));
+        }
+
+        spans.push(Span::styled(
+            format!(
+                "/ {},{} scroll / {} expand hint, open image / {} edit / {} check / {} {} / {} {} {restore}/ {} back / {} quit /",
+                self.keymap.key(Action::Down),
+                self.keymap.key(Action::Up),
+                self.keymap.key(Action::Select),
+                self.keymap.key(Action::Edit),
+                self.keymap.key(Action::Check),
+                self.keymap.key(Action::Watch),
+                if self.watch.is_some() { "unwatch" } else { "watch" },
+                self.keymap.key(Action::Split),
+                if self.split { "unsplit" } else { "split" },
+                self.keymap.key(Action::Back),
+                self.keymap.key(Action::Quit)
             ),
-        ]);
+            theme.text(),
+        ));
+        let title = Line::from(spans);
         let block = Block::default()
             .title(title)
-            .title_style(Style::default().fg(Color::White))
+            .title_style(theme.text())
             .title_position(Position::Bottom)
             .title_alignment(Alignment::Left)
-            .style(Style::default().fg(Color::DarkGray))
+            .style(theme.border())
             .borders(Borders::LEFT | Borders::BOTTOM)
             .border_set(STATUS_BORDER)
             .padding(Padding::horizontal(1));
@@ -150,7 +586,7 @@ impl Lesson {
     }
 
     // render the selected languages
-    fn render_langs(&mut self, area: Rect, buf: &mut Buffer) {
+    fn render_langs(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
         let spoken = match self.spoken_language {
             Some(code) => code.get_name_in_english().to_string(),
             None => "All".to_string(),
@@ -164,17 +600,17 @@ impl Lesson {
         let title = Line::from(vec![
             Span::styled(
                 format!("/ {} / {spoken} / {programming} /", self.workshop_title),
-                Style::default().fg(Color::White),
+                theme.text(),
             ),
-            Span::styled("─", Style::default().fg(Color::DarkGray)),
+            Span::styled("─", theme.border()),
         ]);
 
         let block = Block::default()
             .title(title)
-            .title_style(Style::default().fg(Color::White))
+            .title_style(theme.text())
             .title_position(Position::Bottom)
             .title_alignment(Alignment::Right)
-            .style(Style::default().fg(Color::DarkGray))
+            .style(theme.border())
             .borders(Borders::RIGHT | Borders::BOTTOM)
             .border_set(STATUS_BORDER)
             .padding(Padding::horizontal(1));
@@ -192,7 +628,7 @@ impl Lesson {
         match event {
             tui::Event::LoadLesson => {
                 debug!("Loading lessons");
-                let (spoken, programming, workshop, lesson) = {
+                let (spoken, programming, workshop, lesson, read_only) = {
                     let status = status
                         .lock()
                         .map_err(|e| Error::StatusLock(e.to_string()))?;
@@ -207,6 +643,7 @@ impl Lesson {
                             .lesson()
                             .map(String::from)
                             .ok_or(ModelError::NoLessonSpecified)?,
+                        status.read_only(),
                     )
                 };
                 if let Some(workshop_data) = fs::workshops::load(&workshop) {
@@ -219,11 +656,12 @@ impl Lesson {
                     let lesson_text = lesson_data.get_text().await?;
                     let lesson_metadata = lesson_data.get_metadata().await?;
                     let lesson_title = lesson_metadata.title.clone();
+                    let first_open = matches!(lesson_metadata.status, lesson::Status::NotStarted);
 
                     // Set lesson status to InProgress if it's NotStarted
                     if matches!(lesson_metadata.status, lesson::Status::NotStarted) {
                         lesson_data
-                            .update_status(lesson::Status::InProgress)
+                            .update_status(lesson::Status::InProgress, read_only)
                             .await?;
                         debug!("Updated lesson status to InProgress: {}", lesson_title);
                     }
@@ -231,21 +669,43 @@ impl Lesson {
                     self.init(
                         &workshop_title,
                         &lesson_title,
+                        lesson_data.get_path(),
                         &lesson_text,
                         spoken,
                         programming,
                     )
                     .await?;
+
+                    // open the lesson's file in the editor, or the whole project
+                    self.edit_path = fs::watch::project_root(lesson_data.get_path()).map(|root| {
+                        lesson_metadata
+                            .edit_path(root)
+                            .unwrap_or_else(|| root.to_path_buf())
+                    });
+                    self.checkpoints = status
+                        .lock()
+                        .map_err(|e| Error::StatusLock(e.to_string()))?
+                        .checkpoints();
+                    self.starter = Some(lesson_data.get_path().join(fs::starter::STARTER_DIR))
+                        .filter(|dir| dir.is_dir());
+
                     to_ui
                         .send((None, tui::Event::Show(screens::Screens::Lesson)).into())
                         .await?;
+
+                    // seed the project with the starter code the first time the lesson is opened
+                    if first_open {
+                        self.seed_starter(&to_ui).await?;
+                    }
                 } else {
                     info!("Failed to load workshop data for: {}", &workshop);
                 }
             }
             tui::Event::SolutionComplete => {
+                self.stop_watching();
+
                 // Set the lesson status to completed
-                let (spoken, programming, workshop, lesson) = {
+                let (spoken, programming, workshop, lesson, read_only) = {
                     let status = status
                         .lock()
                         .map_err(|e| Error::StatusLock(e.to_string()))?;
@@ -260,22 +720,32 @@ impl Lesson {
                             .lesson()
                             .map(String::from)
                             .ok_or(ModelError::NoLessonSpecified)?,
+                        status.read_only(),
                     )
                 };
 
                 if let Some(workshop_data) = fs::workshops::load(&workshop) {
                     let lessons = workshop_data.get_lessons_data(spoken, programming).await?;
                     if let Some(lesson_data) = lessons.get(&lesson) {
-                        lesson_data.update_status(lesson::Status::Completed).await?;
+                        lesson_data
+                            .update_status(lesson::Status::Completed, read_only)
+                            .await?;
                         debug!("Updated lesson status to Completed: {}", lesson);
 
+                        // snapshot the project if checkpoints are turned on
+                        let checkpoint = evt!(
+                            None,
+                            tui::Event::Checkpoint(workshop.clone(), lesson.clone())
+                        );
+                        to_ui.send(checkpoint.into()).await?;
+
                         // Check if all lessons are completed
                         let all_completed = self.check_all_lessons_completed(&lessons).await?;
 
                         if all_completed {
                             // Set the workshop as complete
                             workshop_data
-                                .update_status(spoken, workshop::Status::Completed)
+                                .update_status(spoken, workshop::Status::Completed, read_only)
                                 .await?;
                             // Return to workshops screen if all lessons are completed
                             let set_workshop = evt!(
@@ -289,7 +759,8 @@ impl Lesson {
                                     CommandResult {
                                         success: true,
                                         exit_code: 0,
-                                        last_line: "All lessons completed!".to_string()
+                                        last_line: "All lessons completed!".to_string(),
+                                        cancelled: false,
                                     },
                                     Some(hide_log),
                                     None
@@ -305,6 +776,46 @@ impl Lesson {
                     }
                 }
             }
+            tui::Event::CommandStarted(..)
+            | tui::Event::ClearDiagnostics
+            | tui::Event::Diagnostic(_) => {}
+            tui::Event::Log(msg) => self.add_output(msg),
+            tui::Event::CommandOutput(msg, _) => self.add_output(msg),
+            // a newer check replaced the cancelled one and is already running
+            tui::Event::CommandCompleted(result, _, _) if result.cancelled => {}
+            tui::Event::CommandCompleted(result, _, _) => {
+                if result.success {
+                    self.add_output(format!("y {}", result.last_line));
+                } else {
+                    self.add_output(format!("n {}", result.last_line));
+                }
+                self.checking = false;
+
+                if self.watch_check {
+                    self.watch_check = false;
+                    if result.success {
+                        // show the log so the lesson can be completed
+                        self.watch_status = "check passed".to_string();
+                        to_ui.send((None, tui::Event::ShowLog(None)).into()).await?;
+                    } else {
+                        self.watch_status = "check failed".to_string();
+                    }
+                }
+            }
+            tui::Event::CheckLesson => self.check(false, &to_ui).await?,
+            tui::Event::ResetStarter => {
+                to_ui
+                    .send((None, tui::Event::Show(Screens::Lesson)).into())
+                    .await?;
+                self.reset_starter(&to_ui).await?;
+            }
+            tui::Event::FilesChanged => {
+                if self.watch.is_some() {
+                    // a check that is still running is cancelled and replaced by this one
+                    debug!("Project files changed, checking solution");
+                    self.check(true, &to_ui).await?;
+                }
+            }
             tui::Event::SolutionIncomplete => {
                 let load_lesson = evt!(Screens::Lesson, tui::Event::LoadLesson);
                 let hide_log = evt!(None, tui::Event::HideLog(Some(load_lesson)));
@@ -322,44 +833,131 @@ impl Lesson {
         &mut self,
         event: event::Event,
         to_ui: Sender<screens::Event>,
-        _status: Arc<Mutex<Status>>,
+        status: Arc<Mutex<Status>>,
     ) -> Result<(), Error> {
-        if let event::Event::Key(key) = event {
-            match key.code {
-                KeyCode::PageUp => self.lesson_state.scroll_top(),
-                KeyCode::PageDown => self.lesson_state.scroll_bottom(),
-                KeyCode::Char('j') | KeyCode::Char('J') | KeyCode::Down => {
-                    self.lesson_state.highlight_down()
+        match event {
+            event::Event::Key(key) => match self.keymap.action(&key) {
+                Some(Action::Split) => {
+                    self.split = !self.split;
+                    self.focused = FocusedView::Lesson;
+                }
+                Some(Action::NextFocus) | Some(Action::PreviousFocus) if self.split => {
+                    self.focused = self.focused.toggle();
                 }
-                KeyCode::Char('k') | KeyCode::Char('K') | KeyCode::Up => {
-                    self.lesson_state.highlight_up()
+                Some(Action::Top) if self.focused == FocusedView::Output => {
+                    self.output_log.scroll_oldest()
                 }
-                KeyCode::Enter => {
-                    // Toggle hint if highlighted line is a hint title
-                    self.lesson_state.toggle_highlighted_hint(80); // Default width, could be dynamic
+                Some(Action::Bottom) if self.focused == FocusedView::Output => {
+                    self.output_log.scroll_newest()
                 }
-                KeyCode::Char('c') | KeyCode::Char('C') => {
-                    // Check solution
-                    let success = evt!(Screens::Lesson, tui::Event::SolutionComplete);
-                    let failure = evt!(Screens::Lesson, tui::Event::SolutionIncomplete);
-                    let check_solution = evt!(
-                        None,
-                        tui::Event::CheckSolution(Some(success), Some(failure)),
-                    );
-                    to_ui.send(check_solution.into()).await?;
+                Some(Action::Down) if self.focused == FocusedView::Output => {
+                    self.output_log.scroll_newer()
                 }
-                KeyCode::Char('b') | KeyCode::Esc => {
+                Some(Action::Up) if self.focused == FocusedView::Output => {
+                    self.output_log.scroll_older()
+                }
+                Some(Action::Top) => self.lesson_state.scroll_top(),
+                Some(Action::Bottom) => self.lesson_state.scroll_bottom(),
+                Some(Action::Down) => self.lesson_state.highlight_down(),
+                Some(Action::Up) => self.lesson_state.highlight_up(),
+                Some(Action::Select) => {
+                    // Open the image or toggle the hint on the highlighted line
+                    if self.lesson_state.highlighted_link().is_some() {
+                        self.open_highlighted_link();
+                    } else {
+                        let width = self.lesson_state.width();
+                        self.lesson_state.toggle_highlighted_hint(width);
+                    }
+                }
+                Some(Action::Check) => self.check(false, &to_ui).await?,
+                Some(Action::Watch) => self.toggle_watch(&to_ui),
+                Some(Action::Edit) => self.edit(&to_ui, &status).await?,
+                Some(Action::Restore) => self.restore(&to_ui, &status).await?,
+                Some(Action::Reset) => self.confirm_reset(&to_ui).await?,
+                Some(Action::Back) => {
+                    self.stop_watching();
                     to_ui
                         .send((None, tui::Event::SetLesson(None)).into())
                         .await?;
                 }
                 _ => {}
+            },
+            event::Event::Mouse(mouse) if mouse::contains(self.output_area, &mouse) => {
+                match mouse.kind {
+                    event::MouseEventKind::ScrollDown => self.output_log.scroll_newer(),
+                    event::MouseEventKind::ScrollUp => self.output_log.scroll_older(),
+                    event::MouseEventKind::Down(event::MouseButton::Left) => {
+                        self.focused = FocusedView::Output
+                    }
+                    _ => {}
+                }
             }
+            event::Event::Mouse(mouse) => match mouse.kind {
+                event::MouseEventKind::ScrollDown => self.lesson_state.scroll_down(),
+                event::MouseEventKind::ScrollUp => self.lesson_state.scroll_up(),
+                event::MouseEventKind::Down(event::MouseButton::Left) => {
+                    self.focused = FocusedView::Lesson;
+                    // highlight the clicked line and toggle it if it is a hint title
+                    if let Some(row) = mouse::row(self.lesson_area, &mouse) {
+                        if self.lesson_state.highlight_at(row) {
+                            let width = self.lesson_state.width();
+                            self.lesson_state.toggle_highlighted_hint(width);
+                        }
+                    }
+                }
+                _ => {}
+            },
+            _ => {}
         }
         Ok(())
     }
 }
 
+/// watch the project files and send a FilesChanged event once they stop changing
+async fn watch(root: PathBuf, token: CancellationToken, to_ui: Sender<screens::Event>) {
+    // walk the project off of the async runtime to set up the watches
+    let watcher = tokio::task::spawn_blocking(move || {
+        let watcher = Watcher::new(&root);
+        (root, watcher)
+    })
+    .await;
+    let mut watcher = match watcher {
+        Ok((_, Ok(watcher))) => watcher,
+        Ok((root, Err(e))) => {
+            error!("Cannot watch {}: {}", root.display(), e);
+            return;
+        }
+        Err(_) => return,
+    };
+    let mut changed_at: Option<Instant> = None;
+
+    loop {
+        let settled = async {
+            match changed_at {
+                Some(t) => tokio::time::sleep_until(t + WATCH_DEBOUNCE).await,
+                None => std::future::pending().await,
+            }
+        };
+        tokio::select! {
+            _ = token.cancelled() => break,
+            changed = watcher.changed() => match changed {
+                Some(path) => {
+                    debug!("Changed: {}", path.display());
+                    changed_at = Some(Instant::now());
+                }
+                None => break,
+            },
+            _ = settled => {
+                changed_at = None;
+                let changed = evt!(Screens::Lesson, tui::Event::FilesChanged);
+                if to_ui.send(changed.into()).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
 #[async_trait::async_trait]
 impl Screen for Lesson {
     async fn handle_event(
@@ -376,16 +974,51 @@ impl Screen for Lesson {
         }
     }
 
-    fn render_screen(&mut self, area: Rect, buf: &mut Buffer) -> Result<(), Error> {
+    fn render_screen(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) -> Result<(), Error> {
         // this splits the screen into a top area and a one-line bottom area
         let [lesson_area, status_area] =
             Layout::vertical([Constraint::Percentage(100), Constraint::Min(1)])
                 .flex(Flex::End)
                 .areas(area);
 
-        self.render_lesson(lesson_area, buf);
-        self.render_status(status_area, buf);
+        self.render_panes(lesson_area, buf, theme);
+        self.render_status(status_area, buf, theme);
 
         Ok(())
     }
 }
+
+/// show the messages and result of a file operation in the log until the user continues
+async fn report(
+    to_ui: &Sender<screens::Event>,
+    messages: Vec<String>,
+    result: CommandResult,
+) -> Result<(), Error> {
+    to_ui
+        .send(evt!(None, tui::Event::ShowLog(None)).into())
+        .await?;
+    for msg in messages {
+        to_ui
+            .send(evt!(Screens::Log, tui::Event::Log(msg)).into())
+            .await?;
+    }
+    let hide_log = evt!(None, tui::Event::HideLog(None));
+    let completed = evt!(
+        Screens::Log,
+        tui::Event::CommandCompleted(result, Some(hide_log.clone()), Some(hide_log))
+    );
+    to_ui.send(completed.into()).await?;
+    Ok(())
+}
+
+/// show why a starter code action failed in the log
+async fn report_failure(to_ui: &Sender<screens::Event>, reason: String) -> Result<(), Error> {
+    error!("{}", reason);
+    let result = CommandResult {
+        success: false,
+        exit_code: 1,
+        last_line: reason,
+        cancelled: false,
+    };
+    report(to_ui, Vec::new(), result).await
+}
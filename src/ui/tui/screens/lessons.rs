@@ -2,14 +2,18 @@ use crate::{
     fs,
     languages::{self, programming, spoken},
     models::{lesson, workshop, Error as ModelError, Lesson, LessonData},
-    ui::tui::{self, screens, widgets::ScrollBox, Screen, Screens},
+    ui::tui::{
+        self, screens,
+        widgets::{mouse, ScrollBox},
+        Action, Keymap, Screen, Screens, Theme,
+    },
     Error, Status,
 };
-use crossterm::event::{self, KeyCode};
+use crossterm::event;
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Constraint, Flex, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     symbols::border::Set,
     text::{Line, Span},
     widgets::{block::Position, Block, Borders, List, ListState, Padding, StatefulWidget, Widget},
@@ -92,6 +96,8 @@ pub struct Lessons<'a> {
     selected: Option<Lesson>,
     /// the title of the workshop
     workshop_title: String,
+    /// the prerequisites of the lessons
+    graph: lesson::Graph,
     /// the map of lesson titles to lesson keys
     titles_map: BTreeMap<String, String>,
     /// the cached list
@@ -102,15 +108,21 @@ pub struct Lessons<'a> {
     boxes: HashMap<FocusedView, ScrollBox<'a>>,
     /// currently focused view
     focused: FocusedView,
+    /// the cached rects of the views from last render
+    areas: HashMap<FocusedView, Rect>,
+    /// the cached inner rect of the lesson list from last render
+    list_area: Rect,
     /// the currently selected spoken language
     spoken_language: Option<spoken::Code>,
     /// the currently selected programming language
     programming_language: Option<programming::Code>,
+    /// the key bindings
+    keymap: Keymap,
 }
 
 impl Lessons<'_> {
     /// create a new Lessons instance
-    pub fn new() -> Self {
+    pub fn new(keymap: Keymap) -> Self {
         Lessons {
             boxes: [
                 (FocusedView::Metadata, ScrollBox::default()),
@@ -118,6 +130,7 @@ impl Lessons<'_> {
             ]
             .into_iter()
             .collect(),
+            keymap,
             ..Default::default()
         }
     }
@@ -143,15 +156,7 @@ impl Lessons<'_> {
 
         // get the list of titles
         let titles = self.get_titles().await?;
-        self.titles = List::new(titles)
-            .highlight_style(
-                Style::default()
-                    .fg(Color::Black)
-                    .bg(Color::White)
-                    .add_modifier(Modifier::BOLD),
-            )
-            .style(Style::default().fg(Color::White))
-            .highlight_symbol("> ");
+        self.titles = List::new(titles).highlight_symbol("> ");
 
         // cache all of the data for the selected lesson
         self.cache_selected().await?;
@@ -159,60 +164,49 @@ impl Lessons<'_> {
         Ok(())
     }
 
-    // get the lesson titles with status indicators
+    // get the lesson titles with status, lock and branch indicators
     async fn get_titles(&mut self) -> Result<Vec<String>, Error> {
         info!("Caching lesson titles");
         self.titles_map.clear();
 
-        // Get lessons in sorted order
-        let mut lessons_with_status: Vec<(String, String, lesson::Status)> = Vec::new();
+        let mut lessons: Vec<(String, Lesson)> = Vec::new();
         for (key, ld) in self.lessons.iter() {
             let lesson = ld.get_metadata().await?;
-            let status = lesson.status.clone();
             debug!(
-                "lesson key: {key}, title: {}, status: {status}",
-                lesson.title
+                "lesson key: {key}, title: {}, status: {}",
+                lesson.title, lesson.status
             );
-            lessons_with_status.push((key.clone(), lesson.title.clone(), status));
+            lessons.push((key.clone(), lesson));
         }
+        self.graph = lesson::Graph::new(lessons.iter().map(|(k, l)| (k, l)));
 
-        // Sort by lesson key (which includes ordering like 01-, 02-, etc.)
-        lessons_with_status.sort_by(|a, b| a.0.cmp(&b.0));
-
-        for (key, title, status) in lessons_with_status.iter() {
-            let status_indicator = match status {
+        for (key, lesson) in lessons.iter() {
+            let status_indicator = match lesson.status {
                 lesson::Status::Completed => "✅ ",
                 lesson::Status::InProgress => "🤔 ",
+                lesson::Status::NotStarted if !self.graph.is_available(key) => "🔒 ",
                 lesson::Status::NotStarted => "   ",
             };
+            let branch = match self.graph.branch(key) {
+                Some(lesson::Branch::Next) => "├ ",
+                Some(lesson::Branch::Last) => "└ ",
+                None => "",
+            };
+            let optional = if lesson.optional { " (optional)" } else { "" };
 
-            let title_with_status = format!("{status_indicator}{title}");
-            self.titles_map
-                .insert(key.clone(), title_with_status.clone());
+            let title_with_status = format!("{status_indicator}{branch}{}{optional}", lesson.title);
+            self.titles_map.insert(key.clone(), title_with_status);
         }
 
         Ok(self.titles_map.values().cloned().collect())
     }
 
     // check if a lesson can be selected based on its index
-    async fn can_select_lesson(&self, lesson_index: usize) -> Result<bool, Error> {
-        let lesson_keys = self.get_lesson_keys();
-
-        // First lesson can always be selected
-        if lesson_index == 0 {
-            return Ok(true);
-        }
-
-        // For other lessons, check if the previous lesson is completed
-        if lesson_index > 0 && lesson_index < lesson_keys.len() {
-            let prev_lesson_key = &lesson_keys[lesson_index - 1];
-            if let Some(prev_lesson_data) = self.lessons.get(prev_lesson_key) {
-                let prev_lesson = prev_lesson_data.get_metadata().await?;
-                return Ok(matches!(prev_lesson.status, lesson::Status::Completed));
-            }
-        }
-
-        Ok(false)
+    fn can_select_lesson(&self, lesson_index: usize) -> bool {
+        self.get_lesson_keys()
+            .get(lesson_index)
+            .map(|key| self.graph.is_available(key))
+            .unwrap_or(false)
     }
 
     // check if a lesson has been completed
@@ -237,9 +231,10 @@ impl Lessons<'_> {
         if let Some(lesson_key) = self.get_selected_lesson_key() {
             if let Some(lesson_data) = self.lessons.get(&lesson_key) {
                 let lesson = lesson_data.get_metadata().await?;
+                let metadata = self.metadata_text(&lesson_key, &lesson);
                 for (v, b) in self.boxes.iter_mut() {
                     match v {
-                        FocusedView::Metadata => b.set_text(format!("Status: {}", lesson.status)),
+                        FocusedView::Metadata => b.set_text(&metadata),
                         FocusedView::Description => b.set_text(&lesson.description),
                         _ => {}
                     }
@@ -263,6 +258,26 @@ impl Lessons<'_> {
         Ok(())
     }
 
+    // describe the status of a lesson and where it sits in the workshop
+    fn metadata_text(&self, key: &str, lesson: &Lesson) -> String {
+        let mut text = format!("Status: {}", lesson.status);
+        if lesson.optional {
+            text.push_str("\nOptional: this lesson is not needed to finish the workshop");
+        }
+        let requires = self.graph.requires(key);
+        if !requires.is_empty() {
+            text.push_str(&format!("\nRequires: {}", requires.join(", ")));
+        }
+        let unlocks = self.graph.unlocks(key);
+        if !unlocks.is_empty() {
+            text.push_str(&format!("\nUnlocks: {}", unlocks.join(", ")));
+        }
+        if let Some(cause) = self.graph.lock_cause(key) {
+            text.push_str(&format!("\nLocked: {cause}"));
+        }
+        text
+    }
+
     async fn first(&mut self) -> Result<(), Error> {
         match &self.focused {
             FocusedView::List => {
@@ -354,48 +369,85 @@ impl Lessons<'_> {
         self.titles_map.keys().cloned().collect()
     }
 
+    // get the view under the mouse
+    fn view_at(&self, mouse: &event::MouseEvent) -> Option<FocusedView> {
+        self.areas
+            .iter()
+            .find(|(_, area)| mouse::contains(**area, mouse))
+            .map(|(view, _)| view.clone())
+    }
+
+    // select the lesson that was clicked on, or choose it if it was already selected
+    async fn click(
+        &mut self,
+        mouse: &event::MouseEvent,
+        to_ui: &Sender<screens::Event>,
+    ) -> Result<(), Error> {
+        let len = self.lessons.len();
+        if let Some(index) = mouse::list_index(self.list_area, &self.titles_state, len, mouse) {
+            if self.titles_state.selected() == Some(index) {
+                return self.select(to_ui).await;
+            }
+            self.titles_state.select(Some(index));
+            self.cache_selected().await?;
+        }
+        Ok(())
+    }
+
     /// render the lesson list and info
-    fn render_lessons(&mut self, area: Rect, buf: &mut Buffer) {
+    fn render_lessons(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
         let [lesson_titles_area, lesson_info_area] =
             Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(70)])
                 .areas(area);
 
-        self.render_lesson_titles(lesson_titles_area, buf);
-        self.render_lesson_info(lesson_info_area, buf);
+        self.render_lesson_titles(lesson_titles_area, buf, theme);
+        self.render_lesson_info(lesson_info_area, buf, theme);
     }
 
     /// render the list of lesson titles
-    fn render_lesson_titles(&mut self, area: Rect, buf: &mut Buffer) {
+    fn render_lesson_titles(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
         // figure out the titles list border fg color based on what is focused
         let fg = match self.focused {
-            FocusedView::List => Color::White,
-            _ => Color::DarkGray,
+            FocusedView::List => theme.text,
+            _ => theme.border,
         };
 
         let title = Line::from(vec![
-            Span::styled("─", Style::default().fg(Color::DarkGray)),
+            Span::styled("─", theme.border()),
             Span::styled("/ Select a Lesson /", Style::default().fg(fg)),
         ]);
-        let titles = self.titles.clone().block(
-            Block::default()
-                .title(title)
-                .title_style(Style::default().fg(fg))
-                .padding(Padding::uniform(1))
-                .style(Style::default().fg(Color::DarkGray))
-                .borders(Borders::LEFT | Borders::TOP | Borders::RIGHT)
-                .border_set(TOP_LEFT_BORDER),
-        );
+        let block = Block::default()
+            .title(title)
+            .title_style(Style::default().fg(fg))
+            .padding(Padding::uniform(1))
+            .style(theme.border())
+            .borders(Borders::LEFT | Borders::TOP | Borders::RIGHT)
+            .border_set(TOP_LEFT_BORDER);
+        self.areas.insert(FocusedView::List, area);
+        self.list_area = block.inner(area);
+        let titles = self
+            .titles
+            .clone()
+            .highlight_style(theme.selection().add_modifier(Modifier::BOLD))
+            .style(theme.text())
+            .block(block);
 
         StatefulWidget::render(&titles, area, buf, &mut self.titles_state);
     }
 
     /// render the lesson info
-    fn render_lesson_info(&mut self, area: Rect, buf: &mut Buffer) {
+    fn render_lesson_info(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
         let areas: [Rect; 2] =
             Layout::vertical([Constraint::Percentage(30), Constraint::Percentage(70)]).areas(area);
 
-        self.render_lesson_box(areas[0], buf, FocusedView::Metadata, TOP_BOX_BORDER);
-        self.render_lesson_box(areas[1], buf, FocusedView::Description, BOTTOM_BOX_BORDER);
+        self.render_lesson_box(areas[0], buf, FocusedView::Metadata, TOP_BOX_BORDER, theme);
+        self.render_lesson_box(
+            areas[1],
+            buf,
+            FocusedView::Description,
+            BOTTOM_BOX_BORDER,
+            theme,
+        );
     }
 
     // render the lesson box
@@ -405,28 +457,31 @@ impl Lessons<'_> {
         buf: &mut Buffer,
         view: FocusedView,
         border_set: Set,
+        theme: &Theme,
     ) {
+        self.areas.insert(view.clone(), area);
         if let Some(b) = self.boxes.get_mut(&view) {
             let fg = if self.focused == view {
-                Color::White
+                theme.text
             } else {
-                Color::DarkGray
+                theme.border
             };
 
             let title = Line::from(vec![
-                Span::styled("─", Style::default().fg(Color::DarkGray)),
+                Span::styled("─", theme.border()),
                 Span::styled(format!("/ {view} /"), Style::default().fg(fg)),
             ]);
             let block = Block::default()
                 .title(title)
                 .title_style(Style::default().fg(fg))
                 .padding(Padding::top(1))
-                .style(Style::default().fg(Color::DarkGray))
+                .style(theme.border())
                 .borders(Borders::LEFT | Borders::TOP | Borders::RIGHT)
                 .border_set(border_set);
 
             b.block(block);
-            b.style(Style::default().fg(Color::White));
+            b.style(theme.text());
+            b.direction(languages::spoken_direction(self.spoken_language));
 
             // render the scroll text
             Widget::render(b, area, buf);
@@ -436,30 +491,38 @@ impl Lessons<'_> {
     }
 
     // render the status bar at the bottom
-    fn render_status(&mut self, area: Rect, buf: &mut Buffer) {
+    fn render_status(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
         // render the status bar at the bottom
         let [keys_area, langs_area] =
             Layout::horizontal([Constraint::Min(1), Constraint::Length(40)]).areas(area);
 
-        self.render_keys(keys_area, buf);
-        self.render_langs(langs_area, buf);
+        self.render_keys(keys_area, buf, theme);
+        self.render_langs(langs_area, buf, theme);
     }
 
     // render the keyboard shortcuts
-    fn render_keys(&mut self, area: Rect, buf: &mut Buffer) {
+    fn render_keys(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
         let title = Line::from(vec![
-            Span::styled("─", Style::default().fg(Color::DarkGray)),
+            Span::styled("─", theme.border()),
             Span::styled(
-                "/ j,k scroll / ⇥ focus / ↵ select / b back / q quit /",
-                Style::default().fg(Color::White),
+                format!(
+                    "/ {},{} scroll / {} focus / {} select / {} back / {} quit /",
+                    self.keymap.key(Action::Down),
+                    self.keymap.key(Action::Up),
+                    self.keymap.key(Action::NextFocus),
+                    self.keymap.key(Action::Select),
+                    self.keymap.key(Action::Back),
+                    self.keymap.key(Action::Quit)
+                ),
+                theme.text(),
             ),
         ]);
         let block = Block::default()
             .title(title)
-            .title_style(Style::default().fg(Color::White))
+            .title_style(theme.text())
             .title_position(Position::Bottom)
             .title_alignment(Alignment::Left)
-            .style(Style::default().fg(Color::DarkGray))
+            .style(theme.border())
             .borders(Borders::LEFT | Borders::BOTTOM)
             .border_set(STATUS_BORDER)
             .padding(Padding::horizontal(1));
@@ -468,23 +531,23 @@ impl Lessons<'_> {
     }
 
     // render the frames per second
-    fn render_langs(&mut self, area: Rect, buf: &mut Buffer) {
+    fn render_langs(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
         let spoken = languages::spoken_name(self.spoken_language);
         let programming = languages::programming_name(self.programming_language);
         let title = Line::from(vec![
             Span::styled(
                 format!("/ {} / {spoken} / {programming} /", self.workshop_title),
-                Style::default().fg(Color::White).bg(Color::Black),
+                theme.text().bg(theme.background),
             ),
-            Span::styled("─", Style::default().fg(Color::DarkGray).bg(Color::Black)),
+            Span::styled("─", theme.border().bg(theme.background)),
         ]);
 
         let block = Block::default()
             .title(title)
-            .title_style(Style::default().bg(Color::Black).fg(Color::White))
+            .title_style(Style::default().bg(theme.background).fg(theme.text))
             .title_position(Position::Bottom)
             .title_alignment(Alignment::Right)
-            .style(Style::default().fg(Color::DarkGray).bg(Color::Black))
+            .style(theme.border().bg(theme.background))
             .borders(Borders::RIGHT | Borders::BOTTOM)
             .border_set(STATUS_BORDER)
             .padding(Padding::horizontal(1));
@@ -503,7 +566,7 @@ impl Lessons<'_> {
             tui::Event::LoadLessons => {
                 let span = info_span!("Lessons");
                 let _enter = span.enter();
-                let (spoken, programming, workshop) = {
+                let (spoken, programming, workshop, read_only) = {
                     let status = status
                         .lock()
                         .map_err(|e| Error::StatusLock(e.to_string()))?;
@@ -514,6 +577,7 @@ impl Lessons<'_> {
                             .workshop()
                             .map(String::from)
                             .ok_or(ModelError::NoWorkshopSpecified)?,
+                        status.read_only(),
                     )
                 };
                 if let Some(workshop_data) = fs::workshops::load(&workshop) {
@@ -531,7 +595,7 @@ impl Lessons<'_> {
                     debug!("Workshop status: {:?}", workshop_metadata.status);
                     if matches!(workshop_metadata.status, workshop::Status::NotStarted) {
                         workshop_data
-                            .update_status(spoken, workshop::Status::InProgress)
+                            .update_status(spoken, workshop::Status::InProgress, read_only)
                             .await?;
                         debug!("Updated workshop status to InProgress: {workshop_title}");
                     }
@@ -552,6 +616,23 @@ impl Lessons<'_> {
         Ok(())
     }
 
+    /// choose the selected lesson
+    async fn select(&mut self, to_ui: &Sender<screens::Event>) -> Result<(), Error> {
+        if let Some(selected_index) = self.titles_state.selected() {
+            // Check if the lesson can be selected and is not completed
+            let can_select = self.can_select_lesson(selected_index);
+            let is_completed = self.is_lesson_completed(selected_index).await?;
+
+            if can_select && !is_completed {
+                to_ui
+                    .send((None, tui::Event::SetLesson(self.get_selected_lesson_key())).into())
+                    .await?;
+            }
+            // If lesson cannot be selected or is completed, do nothing (ignore the input)
+        }
+        Ok(())
+    }
+
     /// handle input events
     pub async fn handle_input_event(
         &mut self,
@@ -559,53 +640,58 @@ impl Lessons<'_> {
         to_ui: Sender<screens::Event>,
         _status: Arc<Mutex<Status>>,
     ) -> Result<(), Error> {
-        if let event::Event::Key(key) = event {
-            match key.code {
-                KeyCode::PageUp => self.first().await?,
-                KeyCode::PageDown => self.last().await?,
-                KeyCode::Char('j') | KeyCode::Char('J') | KeyCode::Down => self.next().await?,
-                KeyCode::Char('k') | KeyCode::Char('K') | KeyCode::Up => self.prev().await?,
-                KeyCode::Char('b') | KeyCode::Esc => {
+        match event {
+            event::Event::Key(key) => match self.keymap.action(&key) {
+                Some(Action::Top) => self.first().await?,
+                Some(Action::Bottom) => self.last().await?,
+                Some(Action::Down) => self.next().await?,
+                Some(Action::Up) => self.prev().await?,
+                Some(Action::Back) => {
                     to_ui
                         .send((Some(Screens::Workshops), tui::Event::LoadWorkshops).into())
                         .await?;
                 }
-                KeyCode::Tab => {
-                    if key.modifiers.contains(event::KeyModifiers::SHIFT) {
-                        // switch focus to the previous view
-                        self.focused = match self.focused {
-                            FocusedView::List => FocusedView::Description,
-                            FocusedView::Metadata => FocusedView::List,
-                            FocusedView::Description => FocusedView::Metadata,
-                        };
-                    } else {
-                        // switch focus to the next view
-                        self.focused = match self.focused {
-                            FocusedView::List => FocusedView::Metadata,
-                            FocusedView::Metadata => FocusedView::Description,
-                            FocusedView::Description => FocusedView::List,
-                        };
-                    }
+                Some(Action::PreviousFocus) => {
+                    // switch focus to the previous view
+                    self.focused = match self.focused {
+                        FocusedView::List => FocusedView::Description,
+                        FocusedView::Metadata => FocusedView::List,
+                        FocusedView::Description => FocusedView::Metadata,
+                    };
                 }
-                KeyCode::Enter => {
-                    if let Some(selected_index) = self.titles_state.selected() {
-                        // Check if the lesson can be selected and is not completed
-                        let can_select = self.can_select_lesson(selected_index).await?;
-                        let is_completed = self.is_lesson_completed(selected_index).await?;
-
-                        if can_select && !is_completed {
-                            to_ui
-                                .send(
-                                    (None, tui::Event::SetLesson(self.get_selected_lesson_key()))
-                                        .into(),
-                                )
-                                .await?;
-                        }
-                        // If lesson cannot be selected or is completed, do nothing (ignore the input)
-                    }
+                Some(Action::NextFocus) => {
+                    // switch focus to the next view
+                    self.focused = match self.focused {
+                        FocusedView::List => FocusedView::Metadata,
+                        FocusedView::Metadata => FocusedView::Description,
+                        FocusedView::Description => FocusedView::List,
+                    };
                 }
+                Some(Action::Select) => self.select(&to_ui).await?,
                 _ => {}
+            },
+            event::Event::Mouse(mouse) => {
+                // mouse events go to the view under the mouse and focus it
+                let Some(view) = self.view_at(&mouse) else {
+                    return Ok(());
+                };
+                match mouse.kind {
+                    event::MouseEventKind::ScrollDown => {
+                        self.focused = view;
+                        self.next().await?;
+                    }
+                    event::MouseEventKind::ScrollUp => {
+                        self.focused = view;
+                        self.prev().await?;
+                    }
+                    event::MouseEventKind::Down(event::MouseButton::Left) => {
+                        self.focused = view;
+                        self.click(&mouse, &to_ui).await?;
+                    }
+                    _ => {}
+                }
             }
+            _ => {}
         }
         Ok(())
     }
@@ -627,15 +713,15 @@ impl Screen for Lessons<'_> {
         }
     }
 
-    fn render_screen(&mut self, area: Rect, buf: &mut Buffer) -> Result<(), Error> {
+    fn render_screen(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) -> Result<(), Error> {
         // this splits the screen into a top area and a one-line bottom area
         let [lessons_area, status_area] =
             Layout::vertical([Constraint::Percentage(100), Constraint::Min(1)])
                 .flex(Flex::End)
                 .areas(area);
 
-        self.render_lessons(lessons_area, buf);
-        self.render_status(status_area, buf);
+        self.render_lessons(lessons_area, buf, theme);
+        self.render_status(status_area, buf, theme);
 
         Ok(())
     }
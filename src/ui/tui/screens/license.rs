@@ -3,16 +3,15 @@ use crate::{
     ui::tui::{
         self,
         screens::{self, Screens},
-        widgets::ScrollText,
-        Screen,
+        widgets::{mouse, ScrollText},
+        Action, Keymap, Screen, Theme,
     },
     Error, Status,
 };
-use crossterm::event::{self, KeyCode};
+use crossterm::event;
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Constraint, Flex, Layout, Rect},
-    style::{Color, Style},
     symbols::border::Set,
     text::{Line, Span},
     widgets::{block::Position, Block, Borders, Clear, Padding, StatefulWidget, Widget},
@@ -55,11 +54,13 @@ pub struct License<'a> {
     st: ScrollText<'a>,
     /// the currently selected spoken language
     spoken_language: Option<spoken::Code>,
+    /// the key bindings
+    keymap: Keymap,
 }
 
 impl License<'_> {
     /// Create a new log Screen
-    pub fn new() -> Self {
+    pub fn new(keymap: Keymap) -> Self {
         let mut st = ScrollText::default();
         st.scroll_top();
         Self {
@@ -68,6 +69,7 @@ impl License<'_> {
             centered: Rect::default(),
             st,
             spoken_language: None,
+            keymap,
         }
     }
 
@@ -101,44 +103,53 @@ impl License<'_> {
     }
 
     // render the log messages
-    fn render_license(&mut self, area: Rect, buf: &mut Buffer) {
+    fn render_license(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
         Widget::render(Clear, area, buf);
 
         let title = Line::from(vec![
-            Span::styled("─", Style::default().fg(Color::DarkGray)),
-            Span::styled("/ License /", Style::default().fg(Color::White)),
+            Span::styled("─", theme.border()),
+            Span::styled("/ License /", theme.text()),
         ]);
 
         let block = Block::default()
             .title(title)
-            .title_style(Style::default().fg(Color::White))
+            .title_style(theme.text())
             .padding(Padding::horizontal(1))
-            .style(Style::default().fg(Color::DarkGray))
+            .style(theme.border())
             .borders(Borders::LEFT | Borders::RIGHT | Borders::TOP)
             .border_set(TOP_DIALOG_BORDER);
 
         self.st.block(block);
-        self.st.style(Style::default().fg(Color::White));
+        self.st.style(theme.text());
 
         // render the scroll text
         StatefulWidget::render(&mut self.st, area, buf, &mut self.text);
     }
 
     // render the status bar at the bottom
-    fn render_status(&mut self, area: Rect, buf: &mut Buffer) {
+    fn render_status(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
+        let k = &self.keymap;
         let line = Line::from(vec![
-            Span::styled("─", Style::default().fg(Color::DarkGray)),
+            Span::styled("─", theme.border()),
             Span::styled(
-                "/ j,k scroll / ⤒ top / ⤓ bottom / b back / q quit /",
-                Style::default().fg(Color::White),
+                format!(
+                    "/ {},{} scroll / {} top / {} bottom / {} back / {} quit /",
+                    k.key(Action::Down),
+                    k.key(Action::Up),
+                    k.key(Action::Top),
+                    k.key(Action::Bottom),
+                    k.key(Action::Back),
+                    k.key(Action::Quit)
+                ),
+                theme.text(),
             ),
         ]);
         let block = Block::default()
             .title(line)
-            .title_style(Style::default().fg(Color::White))
+            .title_style(theme.text())
             .title_position(Position::Bottom)
             .title_alignment(Alignment::Left)
-            .style(Style::default().fg(Color::DarkGray))
+            .style(theme.border())
             .borders(Borders::LEFT | Borders::BOTTOM | Borders::RIGHT)
             .border_set(STATUS_BORDER)
             .padding(Padding::horizontal(1));
@@ -179,19 +190,27 @@ impl License<'_> {
         to_ui: Sender<screens::Event>,
         _status: Arc<Mutex<Status>>,
     ) -> Result<(), Error> {
-        if let event::Event::Key(key) = event {
-            match key.code {
-                KeyCode::PageUp => self.st.scroll_top(),
-                KeyCode::PageDown => self.st.scroll_bottom(),
-                KeyCode::Char('b') | KeyCode::Esc => {
+        match event {
+            event::Event::Key(key) => match self.keymap.action(&key) {
+                Some(Action::Top) => self.st.scroll_top(),
+                Some(Action::Bottom) => self.st.scroll_bottom(),
+                Some(Action::Back) => {
                     to_ui
                         .send((Some(Screens::Workshops), tui::Event::LoadWorkshops).into())
                         .await?;
                 }
-                KeyCode::Char('j') | KeyCode::Down => self.st.scroll_down(),
-                KeyCode::Char('k') | KeyCode::Up => self.st.scroll_up(),
+                Some(Action::Down) => self.st.scroll_down(),
+                Some(Action::Up) => self.st.scroll_up(),
                 _ => {}
+            },
+            event::Event::Mouse(mouse) if mouse::contains(self.centered, &mouse) => {
+                match mouse.kind {
+                    event::MouseEventKind::ScrollDown => self.st.scroll_down(),
+                    event::MouseEventKind::ScrollUp => self.st.scroll_up(),
+                    _ => {}
+                }
             }
+            _ => {}
         }
         Ok(())
     }
@@ -213,7 +232,7 @@ impl Screen for License<'_> {
         }
     }
 
-    fn render_screen(&mut self, area: Rect, buf: &mut Buffer) -> Result<(), Error> {
+    fn render_screen(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) -> Result<(), Error> {
         self.recalculate_rect(area);
 
         // clear area around the popup
@@ -224,8 +243,8 @@ impl Screen for License<'_> {
                 .flex(Flex::End)
                 .areas(self.centered);
 
-        self.render_license(license_area, buf);
-        self.render_status(status_area, buf);
+        self.render_license(license_area, buf, theme);
+        self.render_status(status_area, buf, theme);
         Ok(())
     }
 }
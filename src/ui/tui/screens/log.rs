@@ -1,28 +1,33 @@
 use crate::{
+    diagnostics::Diagnostic,
     languages::spoken,
+    support,
     ui::tui::{
-        self,
+        self, clipboard,
         events::Evt,
         screens,
-        widgets::{ScrollLog, StatusBar, StatusMode},
-        Screen,
+        widgets::{mouse, ScrollLog, StatusBar, StatusMode},
+        Action, Keymap, Screen, Theme,
     },
     Error, Status,
 };
-use crossterm::event::{self, KeyCode};
+use crossterm::event;
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Constraint, Flex, Layout, Rect},
-    style::{Color, Style},
     symbols::border::Set,
     text::{Line, Span},
-    widgets::{block::Position, Block, Borders, Clear, Padding, StatefulWidget, Widget},
+    widgets::{
+        block::Position, Block, Borders, Clear, List, ListItem, ListState, Padding, StatefulWidget,
+        Widget,
+    },
 };
 use std::{
     collections::{HashMap, VecDeque},
     sync::{Arc, Mutex, OnceLock},
 };
 use tokio::sync::mpsc::Sender;
+use tracing::{error, info, warn};
 
 const TOP_DIALOG_BORDER: Set = Set {
     top_left: "┌",
@@ -46,6 +51,9 @@ const STATUS_BORDER: Set = Set {
     horizontal_bottom: "─",
 };
 
+// the most rows of compiler diagnostics shown below the log
+const MAX_PROBLEM_ROWS: u16 = 8;
+
 // maps the log line prefix to the associated emoji
 static EMOJIS: OnceLock<HashMap<&'static str, String>> = OnceLock::new();
 
@@ -67,6 +75,16 @@ fn emoji() -> &'static HashMap<&'static str, String> {
     })
 }
 
+/// Split a log message into the emoji for its two character prefix and the message text. Returns
+/// None if the message is too short to have a prefix.
+pub fn entry<S: AsRef<str>>(msg: S) -> Option<(Option<String>, String)> {
+    let msg = msg.as_ref();
+    if msg.len() < 2 || !msg.is_char_boundary(2) {
+        return None;
+    }
+    Some((emoji().get(&msg[0..2]).cloned(), msg[2..].to_string()))
+}
+
 #[derive(Clone, Debug)]
 pub struct Log<'a> {
     /// the log messages
@@ -85,20 +103,33 @@ pub struct Log<'a> {
     spoken_language: Option<spoken::Code>,
     /// waiting on enter key press
     on_enter: Option<Evt>,
+    /// the compiler diagnostics found in the output of the last check
+    diagnostics: Vec<Diagnostic>,
+    /// the state of the diagnostics list
+    problems: ListState,
+    /// the diagnostics list has focus instead of the log
+    problems_focused: bool,
+    /// the cached inner rect of the diagnostics list from last render
+    problems_area: Rect,
+    /// the index of the first message of the last command
+    command_start: Option<usize>,
+    /// the messages of the last command, kept as they arrive so that showing only them does not
+    /// copy the log on every frame
+    command_log: VecDeque<(Option<String>, String)>,
+    /// only the last command's output is shown, saved and copied
+    last_command: bool,
+    /// the last line of the last command, if it failed
+    failed: Option<String>,
+    /// the key bindings
+    keymap: Keymap,
 }
 
 impl Log<'_> {
     /// Create a new log Screen
-    pub fn new(max_log: usize) -> Self {
+    pub fn new(max_log: usize, keymap: Keymap) -> Self {
         let mut st = ScrollLog::default();
         st.scroll_newest();
-        let mut sb = StatusBar::new();
-        let block = Block::default()
-            .padding(Padding::horizontal(1))
-            .style(Style::default().fg(Color::DarkGray))
-            .borders(Borders::LEFT | Borders::RIGHT)
-            .border_set(TOP_DIALOG_BORDER);
-        sb.set_block(block);
+        let sb = StatusBar::new();
 
         Self {
             log: VecDeque::default(),
@@ -109,6 +140,15 @@ impl Log<'_> {
             centered: Rect::default(),
             spoken_language: None,
             on_enter: None,
+            diagnostics: Vec::default(),
+            problems: ListState::default(),
+            problems_focused: false,
+            problems_area: Rect::default(),
+            command_start: None,
+            command_log: VecDeque::default(),
+            last_command: false,
+            failed: None,
+            keymap,
         }
     }
 
@@ -131,76 +171,235 @@ impl Log<'_> {
     }
 
     fn add_message<S: AsRef<str>>(&mut self, msg: S) {
-        if msg.as_ref().len() < 2 {
-            // if the message is too short, we can't determine the type
+        // if the message is too short, we can't determine the type
+        let Some(entry) = entry(msg) else {
             return;
-        }
+        };
 
         // add the message to the log
-        let msg = msg.as_ref().to_string();
-        self.log
-            .push_back((emoji().get(&msg[0..2]).cloned(), msg[2..].to_string()));
+        if self.command_start.is_some() {
+            self.command_log.push_back(entry.clone());
+        }
+        self.log.push_back(entry);
 
         // if the log is too long, remove the oldest message
         if self.log.len() > self.max_log {
             self.log.pop_front();
+            if self.command_start == Some(0) {
+                self.command_log.pop_front();
+            }
+            self.command_start = self.command_start.map(|start| start.saturating_sub(1));
+        }
+    }
+
+    // the index of the first message that is shown, saved and copied
+    fn first_message(&self) -> usize {
+        match self.command_start {
+            Some(start) if self.last_command => start,
+            _ => 0,
+        }
+    }
+
+    // the messages from the given index as plain text lines, with their emoji
+    fn lines(&self, first: usize) -> Vec<String> {
+        self.log
+            .range(first..)
+            .map(|(emoji, message)| match emoji {
+                Some(emoji) => format!("{} {message}", emoji.trim_end()),
+                None => message.clone(),
+            })
+            .collect()
+    }
+
+    // save the log to a file
+    fn export(&self) {
+        let name = if self.last_command { "command" } else { "log" };
+        let mut contents = self.lines(self.first_message()).join("\n");
+        contents.push('\n');
+        match support::save(name, &contents) {
+            Ok(path) => info!("Saved the log to {}", path.display()),
+            Err(e) => error!("Failed to save the log: {}", e),
+        }
+    }
+
+    // copy the log to the clipboard
+    fn copy(&self) {
+        match clipboard::copy(&self.lines(self.first_message()).join("\n")) {
+            Ok(false) => info!("Copied the log to the clipboard"),
+            Ok(true) => warn!(
+                "The log is too long for the clipboard, copied only its end. Press {} to save all \
+                 of it",
+                self.keymap.key(Action::Export)
+            ),
+            Err(e) => error!("Failed to copy the log: {}", e),
         }
     }
 
     // render the log messages
-    fn render_log(&mut self, area: Rect, buf: &mut Buffer) {
+    fn render_log(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
         // clear
         Widget::render(Clear, area, buf);
 
         let [log_area, status_bar_area] =
             Layout::vertical([Constraint::Percentage(100), Constraint::Min(1)]).areas(area);
 
+        // make room for the diagnostics below the log
+        let log_area = if self.diagnostics.is_empty() {
+            self.problems_area = Rect::default();
+            log_area
+        } else {
+            let rows = (self.diagnostics.len() as u16).min(MAX_PROBLEM_ROWS) + 1;
+            let [log_area, problems_area] =
+                Layout::vertical([Constraint::Min(1), Constraint::Length(rows)]).areas(log_area);
+            self.render_problems(problems_area, buf, theme);
+            log_area
+        };
+
+        let style = if self.problems_focused {
+            theme.border()
+        } else {
+            theme.text()
+        };
+        let title = if self.last_command {
+            "/ Log: last command /"
+        } else {
+            "/ Log /"
+        };
         let title = Line::from(vec![
-            Span::styled("─", Style::default().fg(Color::DarkGray)),
-            Span::styled("/ Log /", Style::default().fg(Color::White)),
+            Span::styled("─", theme.border()),
+            Span::styled(title, style),
         ]);
 
         let block = Block::default()
             .title(title)
-            .title_style(Style::default().fg(Color::White))
+            .title_style(style)
             .padding(Padding::horizontal(1))
-            .style(Style::default().fg(Color::DarkGray))
+            .style(theme.border())
             .borders(Borders::LEFT | Borders::RIGHT | Borders::TOP)
             .border_set(TOP_DIALOG_BORDER);
 
         self.st.block(block);
-        self.st.style(Style::default().fg(Color::White));
+        self.st.style(theme.text());
 
         // render the scroll text
-        StatefulWidget::render(&mut self.st, log_area, buf, &mut self.log);
+        let log = if self.last_command && self.command_start.is_some() {
+            &mut self.command_log
+        } else {
+            &mut self.log
+        };
+        StatefulWidget::render(&mut self.st, log_area, buf, log);
 
         // render the command status line
+        let block = Block::default()
+            .padding(Padding::horizontal(1))
+            .style(theme.border())
+            .borders(Borders::LEFT | Borders::RIGHT)
+            .border_set(TOP_DIALOG_BORDER);
+        self.sb.set_block(block);
+        self.sb.set_theme(theme);
         Widget::render(&mut self.sb, status_bar_area, buf);
     }
 
-    // render the status bar at the bottom
-    fn render_status(&mut self, area: Rect, buf: &mut Buffer) {
-        let keys = if self.on_enter.is_some() {
-            Span::styled(
-                "/ j,k scroll / ⤒ top / ⤓ bottom / ↵ continue / q quit /",
-                Style::default().fg(Color::White),
-            )
+    // render the compiler diagnostics
+    fn render_problems(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
+        let style = if self.problems_focused {
+            theme.text()
         } else {
-            Span::styled(
-                "/ j,k scroll / ⤒ top / ⤓ bottom / ` back / q quit /",
-                Style::default().fg(Color::White),
-            )
+            theme.border()
         };
-        let line = Line::from(vec![
-            Span::styled("─", Style::default().fg(Color::DarkGray)),
-            keys,
+        let title = Line::from(vec![
+            Span::styled("─", theme.border()),
+            Span::styled(format!("/ Problems ({}) /", self.diagnostics.len()), style),
         ]);
+        let block = Block::default()
+            .title(title)
+            .title_style(style)
+            .padding(Padding::horizontal(1))
+            .style(theme.border())
+            .borders(Borders::LEFT | Borders::RIGHT | Borders::TOP)
+            .border_set(TOP_DIALOG_BORDER);
+        self.problems_area = block.inner(area);
+
+        let items: Vec<ListItem> = self
+            .diagnostics
+            .iter()
+            .map(|d| ListItem::new(d.to_string()))
+            .collect();
+        let mut list = List::new(items)
+            .block(block)
+            .style(theme.text())
+            .highlight_symbol("> ");
+        if self.problems_focused {
+            list = list.highlight_style(theme.selection());
+        }
+
+        StatefulWidget::render(list, area, buf, &mut self.problems);
+    }
+
+    // open the selected diagnostic in the editor
+    async fn open_problem(&self, to_ui: &Sender<screens::Event>) -> Result<(), Error> {
+        if let Some(d) = self
+            .problems
+            .selected()
+            .and_then(|i| self.diagnostics.get(i))
+        {
+            let open = tui::Event::OpenEditor(d.path.clone(), Some(d.line), d.column, None);
+            to_ui.send((None, open).into()).await?;
+        }
+        Ok(())
+    }
+
+    // render the status bar at the bottom
+    fn render_status(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
+        let k = &self.keymap;
+        let mut keys = format!(
+            "/ {},{} scroll / {} top / {} bottom /",
+            k.key(Action::Down),
+            k.key(Action::Up),
+            k.key(Action::Top),
+            k.key(Action::Bottom),
+        );
+        if self.problems_focused {
+            keys.push_str(&format!(
+                " {} open / {} log /",
+                k.key(Action::Select),
+                k.key(Action::NextFocus)
+            ));
+        } else if !self.diagnostics.is_empty() {
+            keys.push_str(&format!(" {} problems /", k.key(Action::NextFocus)));
+        }
+        if self.command_start.is_some() {
+            let shown = if self.last_command {
+                "whole log"
+            } else {
+                "last command"
+            };
+            keys.push_str(&format!(" {} {shown} /", k.key(Action::Filter)));
+        }
+        keys.push_str(&format!(
+            " {} save / {} copy / {} bundle /",
+            k.key(Action::Export),
+            k.key(Action::Copy),
+            k.key(Action::Bundle)
+        ));
+        // enter opens the editor instead of continuing while the diagnostics have focus
+        if !self.problems_focused {
+            if self.on_enter.is_some() {
+                keys.push_str(&format!(" {} continue /", k.key(Action::Select)));
+            } else {
+                keys.push_str(&format!(" {} back /", k.key(Action::ToggleLog)));
+            }
+        }
+        keys.push_str(&format!(" {} quit /", k.key(Action::Quit)));
+
+        let keys = Span::styled(keys, theme.text());
+        let line = Line::from(vec![Span::styled("─", theme.border()), keys]);
         let block = Block::default()
             .title(line)
-            .title_style(Style::default().fg(Color::White))
+            .title_style(theme.text())
             .title_position(Position::Bottom)
             .title_alignment(Alignment::Left)
-            .style(Style::default().fg(Color::DarkGray))
+            .style(theme.border())
             .borders(Borders::LEFT | Borders::BOTTOM | Borders::RIGHT)
             .border_set(STATUS_BORDER)
             .padding(Padding::horizontal(1));
@@ -217,7 +416,22 @@ impl Log<'_> {
     ) -> Result<(), Error> {
         match event {
             tui::Event::Log(msg) => self.add_message(msg),
+            tui::Event::ClearDiagnostics => {
+                self.diagnostics.clear();
+                self.problems.select(None);
+                self.problems_focused = false;
+            }
+            tui::Event::Diagnostic(diagnostic) => {
+                if !self.diagnostics.contains(&diagnostic) {
+                    self.diagnostics.push(diagnostic);
+                }
+                if self.problems.selected().is_none() {
+                    self.problems.select_first();
+                }
+            }
             tui::Event::CommandStarted(mode, message) => {
+                self.command_start = Some(self.log.len());
+                self.command_log.clear();
                 match mode {
                     StatusMode::Blank => {
                         // Do nothing - StatusBar stays in Blank mode
@@ -243,6 +457,9 @@ impl Log<'_> {
             }
             tui::Event::CommandCompleted(result, success, failure) => {
                 self.sb.set_blank();
+                if !result.cancelled {
+                    self.failed = (!result.success).then(|| result.last_line.clone());
+                }
                 if result.success {
                     self.add_message(format!("y {}", result.last_line));
                     self.add_message("< Press ↵ Enter to continue");
@@ -265,20 +482,71 @@ impl Log<'_> {
         to_ui: Sender<screens::Event>,
         _status: Arc<Mutex<Status>>,
     ) -> Result<(), Error> {
-        if let event::Event::Key(key) = event {
-            match key.code {
-                KeyCode::PageUp => self.st.scroll_oldest(),
-                KeyCode::PageDown => self.st.scroll_newest(),
-                KeyCode::Char('j') | KeyCode::Char('J') | KeyCode::Down => self.st.scroll_newer(),
-                KeyCode::Char('k') | KeyCode::Char('K') | KeyCode::Up => self.st.scroll_older(),
-                KeyCode::Char('`') => to_ui.send((None, tui::Event::ToggleLog).into()).await?,
-                KeyCode::Enter => {
+        match event {
+            event::Event::Key(key) => match self.keymap.action(&key) {
+                Some(Action::NextFocus) | Some(Action::PreviousFocus)
+                    if !self.diagnostics.is_empty() =>
+                {
+                    self.problems_focused = !self.problems_focused;
+                }
+                Some(Action::Top) if self.problems_focused => self.problems.select_first(),
+                Some(Action::Bottom) if self.problems_focused => self.problems.select_last(),
+                Some(Action::Down) if self.problems_focused => self.problems.select_next(),
+                Some(Action::Up) if self.problems_focused => self.problems.select_previous(),
+                Some(Action::Select) if self.problems_focused => self.open_problem(&to_ui).await?,
+                Some(Action::Top) => self.st.scroll_oldest(),
+                Some(Action::Bottom) => self.st.scroll_newest(),
+                Some(Action::Down) => self.st.scroll_newer(),
+                Some(Action::Up) => self.st.scroll_older(),
+                Some(Action::ToggleLog) => to_ui.send((None, tui::Event::ToggleLog).into()).await?,
+                Some(Action::Filter) if self.command_start.is_some() => {
+                    self.last_command = !self.last_command;
+                    self.st.scroll_newest();
+                }
+                Some(Action::Export) => self.export(),
+                Some(Action::Copy) => self.copy(),
+                Some(Action::Bundle) => {
+                    // the bundle has the whole log, whatever is shown
+                    let bundle = tui::Event::SupportBundle(self.lines(0), self.failed.clone());
+                    to_ui.send((None, bundle).into()).await?
+                }
+                Some(Action::Select) => {
                     if let Some(on_enter) = self.on_enter.take() {
                         to_ui.send(on_enter.into()).await?
                     }
                 }
                 _ => {}
+            },
+            event::Event::Mouse(mouse) if mouse::contains(self.problems_area, &mouse) => {
+                match mouse.kind {
+                    event::MouseEventKind::ScrollDown => self.problems.select_next(),
+                    event::MouseEventKind::ScrollUp => self.problems.select_previous(),
+                    event::MouseEventKind::Down(event::MouseButton::Left) => {
+                        // clicking the selected diagnostic opens it
+                        self.problems_focused = true;
+                        let len = self.diagnostics.len();
+                        let index =
+                            mouse::list_index(self.problems_area, &self.problems, len, &mouse);
+                        if index.is_some() && index == self.problems.selected() {
+                            self.open_problem(&to_ui).await?;
+                        } else if index.is_some() {
+                            self.problems.select(index);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            event::Event::Mouse(mouse) if mouse::contains(self.centered, &mouse) => {
+                match mouse.kind {
+                    event::MouseEventKind::ScrollDown => self.st.scroll_newer(),
+                    event::MouseEventKind::ScrollUp => self.st.scroll_older(),
+                    event::MouseEventKind::Down(event::MouseButton::Left) => {
+                        self.problems_focused = false
+                    }
+                    _ => {}
+                }
             }
+            _ => {}
         }
         Ok(())
     }
@@ -307,7 +575,7 @@ impl Screen for Log<'_> {
         }
     }
 
-    fn render_screen(&mut self, area: Rect, buf: &mut Buffer) -> Result<(), Error> {
+    fn render_screen(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) -> Result<(), Error> {
         self.recalculate_rect(area);
 
         // clear area around the popup
@@ -318,8 +586,54 @@ impl Screen for Log<'_> {
                 .flex(Flex::End)
                 .areas(self.centered);
 
-        self.render_log(list_area, buf);
-        self.render_status(status_area, buf);
+        self.render_log(list_area, buf, theme);
+        self.render_status(status_area, buf, theme);
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_maps_prefix_to_emoji() {
+        assert_eq!(
+            entry("x check failed"),
+            Some((Some("❌".to_string()), "check failed".to_string()))
+        );
+        assert_eq!(entry("- plain"), Some((None, "plain".to_string())));
+        assert_eq!(entry("x"), None);
+        // a multi-byte first character has no prefix to split off
+        assert_eq!(entry("❌ failed"), None);
+    }
+
+    #[test]
+    fn test_last_command_lines() {
+        let mut log = Log::new(3, Keymap::default());
+        log.add_message("i before");
+        log.command_start = Some(log.log.len());
+        log.add_message("  compiling");
+        log.add_message("x check failed");
+        log.last_command = true;
+        assert_eq!(
+            log.lines(log.first_message()),
+            ["compiling", "❌ check failed"]
+        );
+
+        // the start of the command moves with the oldest messages that are dropped
+        log.add_message("  more");
+        log.add_message("  output");
+        assert_eq!(log.command_start, Some(0));
+        assert_eq!(log.lines(0).len(), 3);
+
+        // the messages shown for the last command are the same as the log's
+        log.add_message("  done");
+        assert_eq!(log.command_start, Some(0));
+        assert_eq!(log.command_log, log.log);
+
+        log.last_command = false;
+        log.command_start = Some(2);
+        assert_eq!(log.lines(log.first_message()).len(), 3);
+    }
+}
@@ -3,15 +3,16 @@ use crate::{
     ui::tui::{
         self,
         screens::{self, Screens},
-        Evt, Screen,
+        widgets::mouse,
+        Action, Evt, Keymap, Screen, Theme,
     },
     Error, Status,
 };
-use crossterm::event::{self, KeyCode};
+use crossterm::event;
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Constraint, Flex, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::Modifier,
     symbols::border::Set,
     text::{Line, Span},
     widgets::{
@@ -54,6 +55,8 @@ pub struct SetDefault<'a> {
     centered: Rect,
     /// the cached list
     list: List<'a>,
+    /// the cached inner rect of the list from last render
+    list_area: Rect,
     /// programming language list state
     list_state: ListState,
     /// event to send if they select "yes"
@@ -62,9 +65,19 @@ pub struct SetDefault<'a> {
     no: Option<Evt>,
     /// the currently selected spoken language
     spoken_language: Option<spoken::Code>,
+    /// the key bindings
+    keymap: Keymap,
 }
 
 impl SetDefault<'_> {
+    /// create a new screen with the given key bindings
+    pub fn new(keymap: Keymap) -> Self {
+        Self {
+            keymap,
+            ..Default::default()
+        }
+    }
+
     async fn init(
         &mut self,
         title: &str,
@@ -77,32 +90,8 @@ impl SetDefault<'_> {
         self.yes = yes;
         self.no = no;
 
-        let title = Line::from(vec![
-            Span::styled("─", Style::default().fg(Color::DarkGray)),
-            Span::styled(
-                format!("/ {} /", self.title),
-                Style::default().fg(Color::White),
-            ),
-        ]);
         self.list_state.select(Some(0));
-        self.list = List::new(vec!["Yes", "No"])
-            .block(
-                Block::default()
-                    .title(title)
-                    .title_style(Style::default().fg(Color::White))
-                    .padding(Padding::uniform(1))
-                    .style(Style::default().fg(Color::DarkGray))
-                    .borders(Borders::LEFT | Borders::RIGHT | Borders::TOP)
-                    .border_set(TOP_DIALOG_BORDER),
-            )
-            .highlight_style(
-                Style::default()
-                    .fg(Color::Black)
-                    .bg(Color::White)
-                    .add_modifier(Modifier::BOLD),
-            )
-            .style(Style::default().fg(Color::White))
-            .highlight_symbol("> ");
+        self.list = List::new(vec!["Yes", "No"]).highlight_symbol("> ");
 
         Ok(())
     }
@@ -126,29 +115,53 @@ impl SetDefault<'_> {
     }
 
     // render the list
-    fn render_list(&mut self, area: Rect, buf: &mut Buffer) {
+    fn render_list(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
         // clear popup area
         Widget::render(Clear, area, buf);
 
+        let title = Line::from(vec![
+            Span::styled("─", theme.border()),
+            Span::styled(format!("/ {} /", self.title), theme.text()),
+        ]);
+        let block = Block::default()
+            .title(title)
+            .title_style(theme.text())
+            .padding(Padding::uniform(1))
+            .style(theme.border())
+            .borders(Borders::LEFT | Borders::RIGHT | Borders::TOP)
+            .border_set(TOP_DIALOG_BORDER);
+        self.list_area = block.inner(area);
+        Widget::render(block, area, buf);
+
         // render the list of programming language names
-        StatefulWidget::render(&self.list, area, buf, &mut self.list_state);
+        let list = self
+            .list
+            .clone()
+            .highlight_style(theme.selection().add_modifier(Modifier::BOLD))
+            .style(theme.text());
+        StatefulWidget::render(&list, self.list_area, buf, &mut self.list_state);
     }
 
     // render the status bar at the bottom
-    fn render_status(&mut self, area: Rect, buf: &mut Buffer) {
+    fn render_status(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
         let line = Line::from(vec![
-            Span::styled("─", Style::default().fg(Color::DarkGray)),
+            Span::styled("─", theme.border()),
             Span::styled(
-                "/ j,k scroll / ↵ select /",
-                Style::default().fg(Color::White),
+                format!(
+                    "/ {},{} scroll / {} select /",
+                    self.keymap.key(Action::Down),
+                    self.keymap.key(Action::Up),
+                    self.keymap.key(Action::Select)
+                ),
+                theme.text(),
             ),
         ]);
         let block = Block::default()
             .title(line)
-            .title_style(Style::default().fg(Color::White))
+            .title_style(theme.text())
             .title_position(Position::Bottom)
             .title_alignment(Alignment::Left)
-            .style(Style::default().fg(Color::DarkGray))
+            .style(theme.border())
             .borders(Borders::LEFT | Borders::BOTTOM | Borders::RIGHT)
             .border_set(STATUS_BORDER)
             .padding(Padding::horizontal(1));
@@ -182,6 +195,28 @@ impl SetDefault<'_> {
         Ok(())
     }
 
+    /// answer yes or no
+    async fn select(&mut self, to_ui: &Sender<screens::Event>) -> Result<(), Error> {
+        // take the events leaving None in their place
+        let yes = self.yes.take();
+        let no = self.no.take();
+        match self.list_state.selected() {
+            Some(0) => {
+                if let Some(yes) = yes {
+                    debug!("Setting default: {:?}", yes);
+                    to_ui.send(yes.into()).await?;
+                }
+            }
+            Some(_) | None => {
+                if let Some(no) = no {
+                    debug!("Clearing default: {:?}", no);
+                    to_ui.send(no.into()).await?;
+                }
+            }
+        };
+        Ok(())
+    }
+
     /// handle input events
     pub async fn handle_input_event(
         &mut self,
@@ -189,31 +224,34 @@ impl SetDefault<'_> {
         to_ui: Sender<screens::Event>,
         _status: Arc<Mutex<Status>>,
     ) -> Result<(), Error> {
-        if let event::Event::Key(key) = event {
-            match key.code {
-                KeyCode::Char('j') | KeyCode::Down => self.list_state.select_next(),
-                KeyCode::Char('k') | KeyCode::Up => self.list_state.select_previous(),
-                KeyCode::Enter => {
-                    // take the events leaving None in their place
-                    let yes = self.yes.take();
-                    let no = self.no.take();
-                    match self.list_state.selected() {
-                        Some(0) => {
-                            if let Some(yes) = yes {
-                                debug!("Setting default: {:?}", yes);
-                                to_ui.send(yes.into()).await?;
-                            }
-                        }
-                        Some(_) | None => {
-                            if let Some(no) = no {
-                                debug!("Clearing default: {:?}", no);
-                                to_ui.send(no.into()).await?;
+        match event {
+            event::Event::Key(key) => match self.keymap.action(&key) {
+                Some(Action::Down) => self.list_state.select_next(),
+                Some(Action::Up) => self.list_state.select_previous(),
+                Some(Action::Select) => self.select(&to_ui).await?,
+                _ => {}
+            },
+            event::Event::Mouse(mouse) if mouse::contains(self.centered, &mouse) => {
+                match mouse.kind {
+                    event::MouseEventKind::ScrollDown => self.list_state.select_next(),
+                    event::MouseEventKind::ScrollUp => self.list_state.select_previous(),
+                    event::MouseEventKind::Down(event::MouseButton::Left) => {
+                        let len = self.list.len();
+                        if let Some(index) =
+                            mouse::list_index(self.list_area, &self.list_state, len, &mouse)
+                        {
+                            // clicking the selected row chooses it
+                            if self.list_state.selected() == Some(index) {
+                                self.select(&to_ui).await?;
+                            } else {
+                                self.list_state.select(Some(index));
                             }
                         }
-                    };
+                    }
+                    _ => {}
                 }
-                _ => {}
             }
+            _ => {}
         }
         Ok(())
     }
@@ -235,7 +273,7 @@ impl Screen for SetDefault<'_> {
         }
     }
 
-    fn render_screen(&mut self, area: Rect, buf: &mut Buffer) -> Result<(), Error> {
+    fn render_screen(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) -> Result<(), Error> {
         self.recalculate_rect(area);
 
         // clear area around the popup
@@ -246,8 +284,8 @@ impl Screen for SetDefault<'_> {
                 .flex(Flex::End)
                 .areas(self.centered);
 
-        self.render_list(list_area, buf);
-        self.render_status(status_area, buf);
+        self.render_list(list_area, buf, theme);
+        self.render_status(status_area, buf, theme);
         Ok(())
     }
 }
@@ -0,0 +1,501 @@
+use crate::{
+    config::Field,
+    models::tools::Detected,
+    ui::tui::{self, screens, widgets::mouse, Action, Keymap, Screen, Theme},
+    Error, Status,
+};
+use crossterm::event::{self, KeyCode};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Flex, Layout, Rect},
+    style::Modifier,
+    symbols::border::Set,
+    text::{Line, Span},
+    widgets::{
+        block::Position, Block, Borders, Clear, List, ListState, Padding, Paragraph,
+        StatefulWidget, Widget,
+    },
+};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc::Sender;
+use tracing::debug;
+
+const TOP_DIALOG_BORDER: Set = Set {
+    top_left: "┌",
+    top_right: "┐",
+    bottom_left: "│",
+    bottom_right: "│",
+    vertical_left: "│",
+    vertical_right: "│",
+    horizontal_top: "─",
+    horizontal_bottom: " ",
+};
+
+const STATUS_BORDER: Set = Set {
+    top_left: " ",
+    top_right: " ",
+    bottom_left: "└",
+    bottom_right: "┘",
+    vertical_left: " ",
+    vertical_right: " ",
+    horizontal_top: " ",
+    horizontal_bottom: "─",
+};
+
+/// the choices for a field that is picked from a list
+#[derive(Clone, Debug, Default)]
+struct Picker {
+    /// the values and their names
+    choices: Vec<(String, String)>,
+    /// choice list state
+    list_state: ListState,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Settings {
+    /// the config fields and their values
+    fields: Vec<(Field, String)>,
+    /// the detected tools
+    detected: Vec<Detected>,
+    /// the outcome of the last change
+    message: Option<String>,
+    /// the value being typed for the selected field
+    input: Option<String>,
+    /// the choices for the selected field
+    picker: Option<Picker>,
+    /// the cached rect from last render
+    area: Rect,
+    /// the cached calculated rect
+    centered: Rect,
+    /// the cached inner rect of the list from last render
+    list_area: Rect,
+    /// field list state
+    list_state: ListState,
+    /// the key bindings
+    keymap: Keymap,
+}
+
+impl Settings {
+    /// create a new screen with the given key bindings
+    pub fn new(keymap: Keymap) -> Self {
+        Self {
+            keymap,
+            ..Default::default()
+        }
+    }
+
+    /// show the config and the detected tools, keeping the selection
+    fn init(
+        &mut self,
+        fields: Vec<(Field, String)>,
+        detected: Vec<Detected>,
+        message: Option<String>,
+    ) {
+        self.fields = fields;
+        self.detected = detected;
+        self.message = message;
+        self.input = None;
+        self.picker = None;
+        self.area = Rect::default();
+        if self.list_state.selected().is_none() {
+            self.list_state.select_first();
+        }
+    }
+
+    /// the selected field and its value
+    fn selected(&self) -> Option<&(Field, String)> {
+        self.list_state
+            .selected()
+            .and_then(|selected| self.fields.get(selected))
+    }
+
+    // the line for a field in the list, with the value being typed in place of the current one
+    fn field_line(&self, index: usize, field: Field, value: &str) -> String {
+        let value = match &self.input {
+            Some(input) if self.list_state.selected() == Some(index) => format!("{input}█"),
+            _ if !field.is_editable() => format!("{value} (edit config.yaml)"),
+            _ => value.to_string(),
+        };
+        // show what an executable turned out to be
+        let detail = self
+            .detected
+            .iter()
+            .find(|detected| field.is_executable() && field.tool() == Some(detected.tool))
+            .map(|detected| match &detected.version {
+                Some(version) => format!(" ({version})"),
+                None => " (not found)".to_string(),
+            })
+            .unwrap_or_default();
+        format!("{:<32}{value}{detail}", field.label())
+    }
+
+    fn recalculate_rect(&mut self, area: Rect) {
+        if self.area != area {
+            let [_, hc, _] = Layout::horizontal([
+                Constraint::Fill(1),
+                Constraint::Max(80),
+                Constraint::Fill(1),
+            ])
+            .areas(area);
+            [_, self.centered, _] = Layout::vertical([
+                Constraint::Fill(1),
+                Constraint::Length(self.fields.len() as u16 + 7),
+                Constraint::Fill(1),
+            ])
+            .areas(hc);
+            self.area = area;
+        }
+    }
+
+    // render the list of fields, or the choices for the selected one, and the outcome of the
+    // last change
+    fn render_list(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
+        // clear popup area
+        Widget::render(Clear, area, buf);
+
+        let title = Line::from(vec![
+            Span::styled("─", theme.border()),
+            Span::styled("/ Settings /", theme.text()),
+        ]);
+        let block = Block::default()
+            .title(title)
+            .title_style(theme.text())
+            .padding(Padding::uniform(1))
+            .style(theme.border())
+            .borders(Borders::LEFT | Borders::RIGHT | Borders::TOP)
+            .border_set(TOP_DIALOG_BORDER);
+        let inner = block.inner(area);
+        Widget::render(block, area, buf);
+
+        let [list_area, message_area] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(2)]).areas(inner);
+        self.list_area = list_area;
+
+        let lines: Vec<String> = match &self.picker {
+            Some(picker) => picker
+                .choices
+                .iter()
+                .map(|(value, name)| format!("{value:<8}{name}"))
+                .collect(),
+            None => self
+                .fields
+                .iter()
+                .enumerate()
+                .map(|(i, (field, value))| self.field_line(i, *field, value))
+                .collect(),
+        };
+        let list = List::new(lines)
+            .highlight_symbol("> ")
+            .highlight_style(theme.selection().add_modifier(Modifier::BOLD))
+            .style(theme.text());
+        let list_state = match self.picker.as_mut() {
+            Some(picker) => &mut picker.list_state,
+            None => &mut self.list_state,
+        };
+        StatefulWidget::render(&list, self.list_area, buf, list_state);
+
+        if let Some(message) = &self.message {
+            let [_, message_area] =
+                Layout::vertical([Constraint::Length(1), Constraint::Length(1)])
+                    .areas(message_area);
+            Widget::render(
+                Paragraph::new(message.as_str()).style(theme.text()),
+                message_area,
+                buf,
+            );
+        }
+    }
+
+    // render the status bar at the bottom
+    fn render_status(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
+        let keys = if self.input.is_some() {
+            match self.selected() {
+                Some((field, _)) if field.is_executable() => {
+                    "/ ↵ save / esc cancel / save it empty to detect it again /".to_string()
+                }
+                _ => "/ ↵ save / esc cancel /".to_string(),
+            }
+        } else if self.picker.is_some() {
+            format!(
+                "/ {},{} scroll / ↵ pick / esc cancel /",
+                self.keymap.key(Action::Down),
+                self.keymap.key(Action::Up)
+            )
+        } else {
+            format!(
+                "/ {},{} scroll / {} edit / {} reset to default / {} back /",
+                self.keymap.key(Action::Down),
+                self.keymap.key(Action::Up),
+                self.keymap.key(Action::Select),
+                self.keymap.key(Action::Reset),
+                self.keymap.key(Action::Back)
+            )
+        };
+        let line = Line::from(vec![
+            Span::styled("─", theme.border()),
+            Span::styled(keys, theme.text()),
+        ]);
+        let block = Block::default()
+            .title(line)
+            .title_style(theme.text())
+            .title_position(Position::Bottom)
+            .title_alignment(Alignment::Left)
+            .style(theme.border())
+            .borders(Borders::LEFT | Borders::BOTTOM | Borders::RIGHT)
+            .border_set(STATUS_BORDER)
+            .padding(Padding::horizontal(1));
+
+        Widget::render(block, area, buf);
+    }
+
+    /// handle UI events
+    pub async fn handle_ui_event(
+        &mut self,
+        event: tui::Event,
+        to_ui: Sender<screens::Event>,
+        _status: Arc<Mutex<Status>>,
+    ) -> Result<(), Error> {
+        match event {
+            tui::Event::ShowSettings(fields, detected, message) => {
+                debug!("Showing settings");
+                self.init(fields, detected, message);
+                to_ui
+                    .send((None, tui::Event::Show(screens::Screens::Settings)).into())
+                    .await?;
+            }
+            _ => {
+                debug!("Ignoring UI event: {:?}", event);
+            }
+        }
+        Ok(())
+    }
+
+    /// change the selected field: booleans are toggled, fields with choices open a picker, and
+    /// the rest are typed in
+    async fn edit(&mut self, to_ui: &Sender<screens::Event>) -> Result<(), Error> {
+        let Some((field, value)) = self.selected().cloned() else {
+            return Ok(());
+        };
+        if !field.is_editable() {
+            self.message = Some(format!("Change the {} in config.yaml", field.key()));
+            return Ok(());
+        }
+        self.message = None;
+        match field.choices() {
+            Some(_) if matches!(field, Field::CheckAfterEdit | Field::Checkpoints) => {
+                let toggled = (value != "true").to_string();
+                self.set(field, Some(toggled), to_ui).await?;
+            }
+            Some(choices) => {
+                // start on the current value
+                let mut list_state = ListState::default();
+                list_state.select(Some(
+                    choices
+                        .iter()
+                        .position(|(choice, _)| {
+                            value.split_whitespace().next() == Some(choice.as_str())
+                        })
+                        .unwrap_or_default(),
+                ));
+                self.picker = Some(Picker {
+                    choices,
+                    list_state,
+                });
+            }
+            None => self.input = Some(value),
+        }
+        Ok(())
+    }
+
+    /// set the selected field, or reset it to its default if there is no value
+    async fn set(
+        &mut self,
+        field: Field,
+        value: Option<String>,
+        to_ui: &Sender<screens::Event>,
+    ) -> Result<(), Error> {
+        debug!("Setting {} to {:?}", field.key(), value);
+        to_ui
+            .send((None, tui::Event::SetConfig(field, value)).into())
+            .await?;
+        Ok(())
+    }
+
+    /// use the typed value for the selected field, an empty executable is detected again
+    async fn save(&mut self, to_ui: &Sender<screens::Event>) -> Result<(), Error> {
+        let input = self.input.take().unwrap_or_default();
+        if let Some((field, _)) = self.selected().cloned() {
+            let input = input.trim().to_string();
+            let value = if field.is_executable() && input.is_empty() {
+                None
+            } else {
+                Some(input)
+            };
+            self.set(field, value, to_ui).await?;
+        }
+        Ok(())
+    }
+
+    /// use the picked choice for the selected field
+    async fn pick(&mut self, to_ui: &Sender<screens::Event>) -> Result<(), Error> {
+        let Some(picker) = self.picker.take() else {
+            return Ok(());
+        };
+        let choice = picker
+            .list_state
+            .selected()
+            .and_then(|selected| picker.choices.get(selected));
+        if let (Some((field, _)), Some((value, _))) = (self.selected().cloned(), choice) {
+            self.set(field, Some(value.clone()), to_ui).await?;
+        }
+        Ok(())
+    }
+
+    /// handle the keys while typing a value
+    async fn handle_input_key(
+        &mut self,
+        key: event::KeyEvent,
+        to_ui: &Sender<screens::Event>,
+    ) -> Result<(), Error> {
+        if let Some(input) = self.input.as_mut() {
+            match key.code {
+                KeyCode::Char(c) => input.push(c),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Esc => self.input = None,
+                KeyCode::Enter => self.save(to_ui).await?,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// handle the keys while picking a choice
+    async fn handle_picker_key(
+        &mut self,
+        key: event::KeyEvent,
+        to_ui: &Sender<screens::Event>,
+    ) -> Result<(), Error> {
+        if key.code == KeyCode::Esc {
+            self.picker = None;
+            return Ok(());
+        }
+        let action = self.keymap.action(&key);
+        if let Some(picker) = self.picker.as_mut() {
+            match action {
+                Some(Action::Top) => picker.list_state.select_first(),
+                Some(Action::Bottom) => picker.list_state.select_last(),
+                Some(Action::Down) => picker.list_state.select_next(),
+                Some(Action::Up) => picker.list_state.select_previous(),
+                Some(Action::Select) => self.pick(to_ui).await?,
+                Some(Action::Back) => self.picker = None,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// handle input events
+    pub async fn handle_input_event(
+        &mut self,
+        event: event::Event,
+        to_ui: Sender<screens::Event>,
+        _status: Arc<Mutex<Status>>,
+    ) -> Result<(), Error> {
+        match event {
+            event::Event::Key(key) if self.input.is_some() => {
+                self.handle_input_key(key, &to_ui).await?
+            }
+            event::Event::Key(key) if self.picker.is_some() => {
+                self.handle_picker_key(key, &to_ui).await?
+            }
+            event::Event::Key(key) => match self.keymap.action(&key) {
+                Some(Action::Top) => self.list_state.select_first(),
+                Some(Action::Bottom) => self.list_state.select_last(),
+                Some(Action::Back) => {
+                    debug!("Back to previous screen");
+                    to_ui
+                        .send((Some(screens::Screens::Workshops), tui::Event::LoadWorkshops).into())
+                        .await?;
+                }
+                Some(Action::Down) => self.list_state.select_next(),
+                Some(Action::Up) => self.list_state.select_previous(),
+                Some(Action::Select) => self.edit(&to_ui).await?,
+                Some(Action::Reset) => {
+                    if let Some((field, _)) = self.selected().cloned() {
+                        if field.is_editable() {
+                            self.set(field, None, &to_ui).await?;
+                        }
+                    }
+                }
+                _ => {}
+            },
+            event::Event::Mouse(mouse)
+                if self.input.is_none() && mouse::contains(self.centered, &mouse) =>
+            {
+                let (list_state, len) = match self.picker.as_mut() {
+                    Some(picker) => (&mut picker.list_state, picker.choices.len()),
+                    None => (&mut self.list_state, self.fields.len()),
+                };
+                match mouse.kind {
+                    event::MouseEventKind::ScrollDown => list_state.select_next(),
+                    event::MouseEventKind::ScrollUp => list_state.select_previous(),
+                    event::MouseEventKind::Down(event::MouseButton::Left) => {
+                        if let Some(index) =
+                            mouse::list_index(self.list_area, list_state, len, &mouse)
+                        {
+                            // clicking the selected row edits or picks it
+                            if list_state.selected() != Some(index) {
+                                list_state.select(Some(index));
+                            } else if self.picker.is_some() {
+                                self.pick(&to_ui).await?;
+                            } else {
+                                self.edit(&to_ui).await?;
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Screen for Settings {
+    async fn handle_event(
+        &mut self,
+        event: screens::Event,
+        to_ui: Sender<screens::Event>,
+        status: Arc<Mutex<Status>>,
+    ) -> Result<(), Error> {
+        match event {
+            screens::Event::Input(input_event) => {
+                self.handle_input_event(input_event, to_ui, status).await
+            }
+            screens::Event::Ui(_, ui_event) => self.handle_ui_event(ui_event, to_ui, status).await,
+        }
+    }
+
+    fn render_screen(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) -> Result<(), Error> {
+        self.recalculate_rect(area);
+
+        // clear area around the popup
+        Widget::render(Clear, self.centered, buf);
+
+        let [list_area, status_area] =
+            Layout::vertical([Constraint::Percentage(100), Constraint::Min(1)])
+                .flex(Flex::End)
+                .areas(self.centered);
+
+        self.render_list(list_area, buf, theme);
+        self.render_status(status_area, buf, theme);
+        Ok(())
+    }
+    fn is_typing(&self) -> bool {
+        self.input.is_some()
+    }
+}
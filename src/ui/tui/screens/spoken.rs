@@ -1,13 +1,13 @@
 use crate::{
     languages::spoken,
-    ui::tui::{self, screens, Evt, Screen},
+    ui::tui::{self, screens, widgets::mouse, Action, Evt, Keymap, Screen, Theme},
     Error, Status,
 };
-use crossterm::event::{self, KeyCode};
+use crossterm::event;
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Constraint, Flex, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::Modifier,
     symbols::border::Set,
     text::{Line, Span},
     widgets::{
@@ -58,11 +58,23 @@ pub struct Spoken<'a> {
     centered: Rect,
     /// the cached list
     list: List<'a>,
+    /// the cached inner rect of the list from last render
+    list_area: Rect,
     /// spoken language list state
     list_state: ListState,
+    /// the key bindings
+    keymap: Keymap,
 }
 
 impl Spoken<'_> {
+    /// create a new screen with the given key bindings
+    pub fn new(keymap: Keymap) -> Self {
+        Self {
+            keymap,
+            ..Default::default()
+        }
+    }
+
     /// set initialize the screen
     async fn init(
         &mut self,
@@ -83,31 +95,7 @@ impl Spoken<'_> {
         self.area = Rect::default();
         self.centered = Rect::default();
 
-        let title = Line::from(vec![
-            Span::styled("─", Style::default().fg(Color::DarkGray)),
-            Span::styled(
-                "/ Select a Spoken Language /",
-                Style::default().fg(Color::White),
-            ),
-        ]);
-        self.list = List::new(self.language_names())
-            .block(
-                Block::default()
-                    .title(title)
-                    .title_style(Style::default().fg(Color::White))
-                    .padding(Padding::uniform(1))
-                    .style(Style::default().fg(Color::DarkGray))
-                    .borders(Borders::LEFT | Borders::RIGHT | Borders::TOP)
-                    .border_set(TOP_DIALOG_BORDER),
-            )
-            .highlight_style(
-                Style::default()
-                    .fg(Color::Black)
-                    .bg(Color::White)
-                    .add_modifier(Modifier::BOLD),
-            )
-            .style(Style::default().fg(Color::White))
-            .highlight_symbol("> ");
+        self.list = List::new(self.language_names()).highlight_symbol("> ");
         self.list_state
             .select(self.selection_from_language(self.spoken_language));
 
@@ -196,29 +184,53 @@ impl Spoken<'_> {
     }
 
     // render the list
-    fn render_list(&mut self, area: Rect, buf: &mut Buffer) {
+    fn render_list(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
         // clear popup area
         Widget::render(Clear, area, buf);
 
+        let title = Line::from(vec![
+            Span::styled("─", theme.border()),
+            Span::styled("/ Select a Spoken Language /", theme.text()),
+        ]);
+        let block = Block::default()
+            .title(title)
+            .title_style(theme.text())
+            .padding(Padding::uniform(1))
+            .style(theme.border())
+            .borders(Borders::LEFT | Borders::RIGHT | Borders::TOP)
+            .border_set(TOP_DIALOG_BORDER);
+        self.list_area = block.inner(area);
+        Widget::render(block, area, buf);
+
         // render the list of programming language names
-        StatefulWidget::render(&self.list, area, buf, &mut self.list_state);
+        let list = self
+            .list
+            .clone()
+            .highlight_style(theme.selection().add_modifier(Modifier::BOLD))
+            .style(theme.text());
+        StatefulWidget::render(&list, self.list_area, buf, &mut self.list_state);
     }
 
     // render the status bar at the bottom
-    fn render_status(&mut self, area: Rect, buf: &mut Buffer) {
+    fn render_status(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
         let line = Line::from(vec![
-            Span::styled("─", Style::default().fg(Color::DarkGray)),
+            Span::styled("─", theme.border()),
             Span::styled(
-                "/ j,k scroll / ↵ select /",
-                Style::default().fg(Color::White),
+                format!(
+                    "/ {},{} scroll / {} select /",
+                    self.keymap.key(Action::Down),
+                    self.keymap.key(Action::Up),
+                    self.keymap.key(Action::Select)
+                ),
+                theme.text(),
             ),
         ]);
         let block = Block::default()
             .title(line)
-            .title_style(Style::default().fg(Color::White))
+            .title_style(theme.text())
             .title_position(Position::Bottom)
             .title_alignment(Alignment::Left)
-            .style(Style::default().fg(Color::DarkGray))
+            .style(theme.border())
             .borders(Borders::LEFT | Borders::BOTTOM | Borders::RIGHT)
             .border_set(STATUS_BORDER)
             .padding(Padding::horizontal(1));
@@ -251,6 +263,25 @@ impl Spoken<'_> {
         Ok(())
     }
 
+    /// choose the selected spoken language
+    async fn select(&mut self, to_ui: &Sender<screens::Event>) -> Result<(), Error> {
+        // take the event leaving None in its place
+        let event = self.event.take();
+        if let Some(selected) = self.list_state.selected() {
+            let spoken_language = self.language_from_selection(selected);
+            let set_spoken_language = (
+                None,
+                tui::Event::SetSpokenLanguage(
+                    spoken_language,
+                    None, // None, because we don't know if it should be the default
+                    event,
+                ),
+            );
+            to_ui.send(set_spoken_language.into()).await?;
+        }
+        Ok(())
+    }
+
     /// handle input events
     pub async fn handle_input_event(
         &mut self,
@@ -258,36 +289,42 @@ impl Spoken<'_> {
         to_ui: Sender<screens::Event>,
         _status: Arc<Mutex<Status>>,
     ) -> Result<(), Error> {
-        if let event::Event::Key(key) = event {
-            match key.code {
-                KeyCode::PageUp => self.list_state.select_first(),
-                KeyCode::PageDown => self.list_state.select_last(),
-                KeyCode::Char('b') | KeyCode::Esc => {
+        match event {
+            event::Event::Key(key) => match self.keymap.action(&key) {
+                Some(Action::Top) => self.list_state.select_first(),
+                Some(Action::Bottom) => self.list_state.select_last(),
+                Some(Action::Back) => {
                     debug!("Back to previous screen");
                     to_ui
                         .send((Some(screens::Screens::Workshops), tui::Event::LoadWorkshops).into())
                         .await?;
                 }
-                KeyCode::Char('j') | KeyCode::Down => self.list_state.select_next(),
-                KeyCode::Char('k') | KeyCode::Up => self.list_state.select_previous(),
-                KeyCode::Enter => {
-                    // take the event leaving None in its place
-                    let event = self.event.take();
-                    if let Some(selected) = self.list_state.selected() {
-                        let spoken_language = self.language_from_selection(selected);
-                        let set_spoken_language = (
-                            None,
-                            tui::Event::SetSpokenLanguage(
-                                spoken_language,
-                                None, // None, because we don't know if it should be the default
-                                event,
-                            ),
-                        );
-                        to_ui.send(set_spoken_language.into()).await?;
+                Some(Action::Down) => self.list_state.select_next(),
+                Some(Action::Up) => self.list_state.select_previous(),
+                Some(Action::Select) => self.select(&to_ui).await?,
+                _ => {}
+            },
+            event::Event::Mouse(mouse) if mouse::contains(self.centered, &mouse) => {
+                match mouse.kind {
+                    event::MouseEventKind::ScrollDown => self.list_state.select_next(),
+                    event::MouseEventKind::ScrollUp => self.list_state.select_previous(),
+                    event::MouseEventKind::Down(event::MouseButton::Left) => {
+                        let len = self.list.len();
+                        if let Some(index) =
+                            mouse::list_index(self.list_area, &self.list_state, len, &mouse)
+                        {
+                            // clicking the selected row chooses it
+                            if self.list_state.selected() == Some(index) {
+                                self.select(&to_ui).await?;
+                            } else {
+                                self.list_state.select(Some(index));
+                            }
+                        }
                     }
+                    _ => {}
                 }
-                _ => {}
             }
+            _ => {}
         }
         Ok(())
     }
@@ -309,7 +346,7 @@ impl Screen for Spoken<'_> {
         }
     }
 
-    fn render_screen(&mut self, area: Rect, buf: &mut Buffer) -> Result<(), Error> {
+    fn render_screen(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) -> Result<(), Error> {
         self.recalculate_rect(area);
 
         // clear area around the popup
@@ -320,8 +357,8 @@ impl Screen for Spoken<'_> {
                 .flex(Flex::End)
                 .areas(self.centered);
 
-        self.render_list(list_area, buf);
-        self.render_status(status_area, buf);
+        self.render_list(list_area, buf, theme);
+        self.render_status(status_area, buf, theme);
         Ok(())
     }
 }
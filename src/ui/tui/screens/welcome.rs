@@ -1,12 +1,12 @@
 use crate::{
-    ui::tui::{self, screens, Screen},
+    ui::tui::{self, screens, Action, Keymap, Screen, Theme},
     Error, Status,
 };
-use crossterm::event::{self, KeyCode};
+use crossterm::event;
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Constraint, Flex, Layout, Offset, Rect},
-    style::{Color, Style},
+    style::Style,
     widgets::{Block, Borders, Clear, Padding, Paragraph, Widget, Wrap},
 };
 use std::sync::{Arc, Mutex};
@@ -20,6 +20,8 @@ pub struct Welcome<'a> {
     centered: Rect,
     /// the cached paragraph
     text: Paragraph<'a>,
+    /// the key bindings
+    keymap: Keymap,
 }
 
 impl Default for Welcome<'_> {
@@ -28,20 +30,22 @@ impl Default for Welcome<'_> {
             area: Rect::default(),
             centered: Rect::default(),
             text: Paragraph::new("")
-                .block(
-                    Block::default()
-                        .title(" Workshop v1.0 ")
-                        .padding(Padding::horizontal(1))
-                        .style(Style::default().fg(Color::White))
-                        .borders(Borders::ALL),
-                )
                 .alignment(Alignment::Center)
                 .wrap(Wrap { trim: true }),
+            keymap: Keymap::default(),
         }
     }
 }
 
 impl Welcome<'_> {
+    /// create a new welcome screen with the given key bindings
+    pub fn new(keymap: Keymap) -> Self {
+        Self {
+            keymap,
+            ..Default::default()
+        }
+    }
+
     fn recalculate_rect(&mut self, area: Rect) {
         if self.area != area {
             let [_, hc, _] = Layout::horizontal([
@@ -60,23 +64,29 @@ impl Welcome<'_> {
     }
 
     // render the dialog
-    fn render_dialog(&mut self, area: Rect, buf: &mut Buffer) {
+    fn render_dialog(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
         // clear popup area
         Widget::render(Clear, area, buf);
 
+        let block = Block::default()
+            .title(" Workshop v1.0 ")
+            .padding(Padding::horizontal(1))
+            .style(theme.text())
+            .borders(Borders::ALL);
+
         // render the list of programming language names
-        Widget::render(&self.text, area, buf);
+        Widget::render(self.text.clone().block(block), area, buf);
     }
 
     // render the status bar at the bottom
-    fn render_status(&mut self, area: Rect, buf: &mut Buffer) {
+    fn render_status(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
         let block = Block::default()
             .borders(Borders::NONE)
             .padding(Padding::horizontal(1));
 
         let keys = Paragraph::new(" enter: Ok ")
             .block(block)
-            .style(Style::default().fg(Color::Black).bg(Color::White))
+            .style(theme.selection())
             .wrap(Wrap { trim: true })
             .alignment(Alignment::Left);
 
@@ -94,7 +104,7 @@ impl Screen for Welcome<'_> {
         _status: Arc<Mutex<Status>>,
     ) -> Result<(), Error> {
         if let screens::Event::Input(event::Event::Key(key)) = event {
-            if key.code == KeyCode::Enter {
+            if self.keymap.action(&key) == Some(Action::Select) {
                 to_ui
                     .send((Some(screens::Screens::Workshops), tui::Event::LoadWorkshops).into())
                     .await?;
@@ -103,7 +113,7 @@ impl Screen for Welcome<'_> {
         Ok(())
     }
 
-    fn render_screen(&mut self, area: Rect, buf: &mut Buffer) -> Result<(), Error> {
+    fn render_screen(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) -> Result<(), Error> {
         self.recalculate_rect(area);
 
         // clear area around the popup
@@ -119,7 +129,7 @@ impl Screen for Welcome<'_> {
         shadow_area = shadow_area.offset(Offset { x: 1, y: 1 });
         let block = Block::default()
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::DarkGray).bg(Color::DarkGray));
+            .border_style(Style::default().fg(theme.shadow).bg(theme.shadow));
         Widget::render(block, shadow_area, buf);
 
         let [dialog_area, status_area] =
@@ -127,8 +137,8 @@ impl Screen for Welcome<'_> {
                 .flex(Flex::End)
                 .areas(working_area);
 
-        self.render_dialog(dialog_area, buf);
-        self.render_status(status_area, buf);
+        self.render_dialog(dialog_area, buf, theme);
+        self.render_status(status_area, buf, theme);
         Ok(())
     }
 }
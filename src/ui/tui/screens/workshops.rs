@@ -1,26 +1,26 @@
 use crate::{
     evt, fs,
     languages::{self, programming, spoken},
-    models::{workshop, Workshop, WorkshopData},
+    models::{lesson, workshop, Workshop, WorkshopData},
     ui::tui::{
         self,
         screens::{self, Screens},
-        widgets::{LessonBox, LessonBoxState, ScrollBox},
-        Screen,
+        widgets::{mouse, LessonBox, LessonBoxState, ScrollBox},
+        Action, Keymap, Screen, Theme,
     },
     Error, Status,
 };
-use crossterm::event::{self, KeyCode};
+use crossterm::event;
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Constraint, Flex, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     symbols::border::Set,
     text::{Line, Span},
     widgets::{block::Position, Block, Borders, List, ListState, Padding, StatefulWidget, Widget},
 };
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::HashMap,
     fmt,
     sync::{Arc, Mutex},
 };
@@ -138,21 +138,29 @@ pub struct Workshops<'a> {
     workshops: HashMap<String, WorkshopData>,
     /// the currently selected workshop data
     selected: Option<Cached>,
-    /// the map of workshop titles to workshop keys in sorted order
-    titles_map: BTreeMap<String, String>,
+    /// the workshop titles and keys in series order
+    titles: Vec<(String, String)>,
+    /// why each locked workshop cannot be started yet, by workshop key
+    locks: HashMap<String, String>,
     /// the views
     views: HashMap<&'static str, FocusedView<'a>>,
     /// currently focused view
     focused: &'static str,
+    /// the cached rects of the views from last render
+    areas: HashMap<&'static str, Rect>,
+    /// the cached inner rect of the workshop list from last render
+    list_area: Rect,
     /// the currently selected spoken language
     spoken_language: Option<spoken::Code>,
     /// the currently selected programming language
     programming_language: Option<programming::Code>,
+    /// the key bindings
+    keymap: Keymap,
 }
 
 impl Workshops<'_> {
     /// create a new Workshops instance
-    pub fn new() -> Self {
+    pub fn new(keymap: Keymap) -> Self {
         Workshops {
             views: [
                 (
@@ -172,6 +180,7 @@ impl Workshops<'_> {
             .into_iter()
             .collect(),
             focused: FocusedView::default().as_str(),
+            keymap,
             ..Default::default()
         }
     }
@@ -199,15 +208,7 @@ impl Workshops<'_> {
             }
 
             // set the titles
-            *titles = List::new(t)
-                .highlight_style(
-                    Style::default()
-                        .fg(Color::Black)
-                        .bg(Color::White)
-                        .add_modifier(Modifier::BOLD),
-                )
-                .style(Style::default().fg(Color::White))
-                .highlight_symbol("> ");
+            *titles = List::new(t).highlight_symbol("> ");
         }
 
         // cache all of the data for the selected workshop
@@ -216,35 +217,43 @@ impl Workshops<'_> {
         Ok(())
     }
 
-    // get the workshop titles with status indicators
+    // get the workshop titles with status and lock indicators in series order
     async fn get_titles(&mut self) -> Result<Vec<String>, Error> {
         debug!("Caching workshop titles");
-        self.titles_map.clear();
+        self.titles.clear();
+        self.locks.clear();
 
-        // Get workshops with their calculated status
-        let mut workshops_with_status: Vec<(String, String, workshop::Status)> = Vec::new();
+        let mut workshops: HashMap<String, Workshop> = HashMap::new();
         for (key, wd) in self.workshops.iter() {
             let workshop = wd.get_metadata(self.spoken_language).await?;
-            let status = workshop.status.clone();
-            workshops_with_status.push((key.clone(), workshop.title.clone(), status));
+            let unmet = fs::workshops::unmet_requirements(
+                &workshop,
+                self.spoken_language,
+                self.programming_language,
+            )
+            .await;
+            if let Some(cause) = lesson::complete_first(&unmet) {
+                self.locks.insert(key.clone(), cause);
+            }
+            workshops.insert(key.clone(), workshop);
         }
 
-        // Sort by workshop title
-        workshops_with_status.sort_by(|a, b| a.1.cmp(&b.1));
-
-        for (key, title, status) in workshops_with_status.iter() {
-            let status_indicator = match status {
+        // workshops in a series come after the workshops they require
+        for key in workshop::series_order(workshops.iter()) {
+            let workshop = &workshops[key];
+            // a locked workshop cannot be opened, whatever its status
+            let status_indicator = match workshop.status {
+                _ if self.locks.contains_key(key) => "🔒 ",
                 workshop::Status::Completed => "✅ ",
                 workshop::Status::InProgress => "🤔 ",
                 workshop::Status::NotStarted => "   ",
             };
 
-            let title_with_status = format!("{status_indicator} {title}");
-            self.titles_map
-                .insert(title_with_status.clone(), key.clone());
+            let title_with_status = format!("{status_indicator} {}", workshop.title);
+            self.titles.push((title_with_status, key.clone()));
         }
 
-        Ok(self.titles_map.keys().cloned().collect())
+        Ok(self.titles.iter().map(|(title, _)| title.clone()).collect())
     }
 
     // cached selected workshop data
@@ -266,7 +275,7 @@ impl Workshops<'_> {
                 let license = workshop_data.get_license().await?;
 
                 // update the scroll boxes
-                let metadata = format!(
+                let mut metadata = format!(
                     "Status: {}\nAuthors: {}\nCopyright: {}\nLicense: {}\nHomepage: {}\nDifficulty: {}\nLanguages:\n{}",
                     workshop.status,
                     workshop
@@ -296,17 +305,39 @@ impl Workshops<'_> {
                         .join("\n"),
                 );
 
+                if !workshop.requires_workshops.is_empty() {
+                    let requires = workshop
+                        .requires_workshops
+                        .iter()
+                        .map(|r| match r.min_lessons {
+                            Some(min_lessons) => format!(" - {} ({min_lessons} lessons)", r.name),
+                            None => format!(" - {}", r.name),
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    metadata.push_str(&format!("\nRequires:\n{requires}"));
+                }
+                if let Some(cause) = self.locks.get(&workshop_key) {
+                    metadata.push_str(&format!("\nLocked: {cause}"));
+                }
+
+                let direction = languages::spoken_direction(self.spoken_language);
                 for (_, v) in self.views.iter_mut() {
                     match v {
-                        FocusedView::Metadata(scroll_box) => scroll_box.set_text(&metadata),
+                        FocusedView::Metadata(scroll_box) => {
+                            scroll_box.set_text(&metadata);
+                            scroll_box.direction(direction);
+                        }
                         FocusedView::Description(_, state) => {
                             let mut lb = LessonBoxState::from_markdown(&description);
                             lb.set_highlighted_line(false);
+                            lb.set_direction(direction);
                             *state = lb;
                         }
                         FocusedView::SetupInstructions(_, state) => {
                             let mut lb = LessonBoxState::from_markdown(&setup_instructions);
                             lb.set_highlighted_line(false);
+                            lb.set_direction(direction);
                             *state = lb;
                         }
                         _ => {}
@@ -450,9 +481,9 @@ impl Workshops<'_> {
         }
     }
 
-    // get the sorted list of workshop keys
+    // get the list of workshop keys in series order
     fn get_workshop_keys(&self) -> Vec<String> {
-        self.titles_map.values().cloned().collect()
+        self.titles.iter().map(|(_, key)| key.clone()).collect()
     }
 
     // get the cached URL for the selected workshop
@@ -473,41 +504,75 @@ impl Workshops<'_> {
         }
     }
 
+    // get the view under the mouse
+    fn view_at(&self, mouse: &event::MouseEvent) -> Option<&'static str> {
+        self.areas
+            .iter()
+            .find(|(_, area)| mouse::contains(**area, mouse))
+            .map(|(view, _)| *view)
+    }
+
+    // select the workshop that was clicked on, or choose it if it was already selected
+    async fn click(
+        &mut self,
+        mouse: &event::MouseEvent,
+        to_ui: &Sender<screens::Event>,
+        status: &Arc<Mutex<Status>>,
+    ) -> Result<(), Error> {
+        let len = self.workshops.len();
+        if let Some(FocusedView::List(_, state)) = self.views.get_mut("list") {
+            if let Some(index) = mouse::list_index(self.list_area, state, len, mouse) {
+                if state.selected() == Some(index) {
+                    return self.select(to_ui, status).await;
+                }
+                state.select(Some(index));
+                self.cache_selected().await?;
+            }
+        }
+        Ok(())
+    }
+
     /// render the workshop list and info
-    fn render_workshops(&mut self, area: Rect, buf: &mut Buffer) {
+    fn render_workshops(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
         let [workshop_titles_area, workshop_info_area] =
             Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(70)])
                 .areas(area);
 
-        self.render_workshop_titles(workshop_titles_area, buf);
-        self.render_workshop_info(workshop_info_area, buf);
+        self.render_workshop_titles(workshop_titles_area, buf, theme);
+        self.render_workshop_info(workshop_info_area, buf, theme);
     }
 
     /// render the list of workshop titles
-    fn render_workshop_titles(&mut self, area: Rect, buf: &mut Buffer) {
+    fn render_workshop_titles(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
         // figure out the titles list border fg color based on what is focused
         let fg = if self.focused == "list" {
-            Color::White
+            theme.text
         } else {
-            Color::DarkGray
+            theme.border
         };
 
         let title = Line::from(vec![
-            Span::styled("─", Style::default().fg(Color::DarkGray)),
+            Span::styled("─", theme.border()),
             Span::styled("/ Select a Workshop /", Style::default().fg(fg)),
         ]);
 
+        let block = Block::default()
+            .title(title)
+            .padding(Padding::uniform(1))
+            .style(theme.text())
+            .border_style(theme.border())
+            .borders(Borders::LEFT | Borders::TOP | Borders::RIGHT)
+            .border_set(TOP_LEFT_BORDER);
+        self.areas.insert("list", area);
+        self.list_area = block.inner(area);
+
         if let Some(view) = self.views.get_mut("list") {
             if let FocusedView::List(list, _) = view {
-                *list = list.clone().block(
-                    Block::default()
-                        .title(title)
-                        .padding(Padding::uniform(1))
-                        .style(Style::default().fg(Color::White))
-                        .border_style(Style::default().fg(Color::DarkGray))
-                        .borders(Borders::LEFT | Borders::TOP | Borders::RIGHT)
-                        .border_set(TOP_LEFT_BORDER),
-                );
+                *list = list
+                    .clone()
+                    .highlight_style(theme.selection().add_modifier(Modifier::BOLD))
+                    .style(theme.text())
+                    .block(block);
             }
 
             Widget::render(view, area, buf);
@@ -515,7 +580,7 @@ impl Workshops<'_> {
     }
 
     /// render the workshop info
-    fn render_workshop_info(&mut self, area: Rect, buf: &mut Buffer) {
+    fn render_workshop_info(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
         let areas: [Rect; 3] = Layout::vertical([
             Constraint::Percentage(25),
             Constraint::Percentage(50),
@@ -524,9 +589,9 @@ impl Workshops<'_> {
         .flex(Flex::End)
         .areas(area);
 
-        self.render_workshop_box(areas[0], buf, "metadata", TOP_BOX_BORDER);
-        self.render_workshop_box(areas[1], buf, "description", BOTTOM_BOX_BORDER);
-        self.render_workshop_box(areas[2], buf, "setup", BOTTOM_BOX_BORDER);
+        self.render_workshop_box(areas[0], buf, "metadata", TOP_BOX_BORDER, theme);
+        self.render_workshop_box(areas[1], buf, "description", BOTTOM_BOX_BORDER, theme);
+        self.render_workshop_box(areas[2], buf, "setup", BOTTOM_BOX_BORDER, theme);
     }
 
     // render the workshop box
@@ -536,18 +601,21 @@ impl Workshops<'_> {
         buf: &mut Buffer,
         view: &'static str,
         border_set: Set,
+        theme: &Theme,
     ) {
+        self.areas.insert(view, area);
+
         // figure out the box border fg color based on what is focused
         let fg = if self.focused == view {
-            Color::White
+            theme.text
         } else {
-            Color::DarkGray
+            theme.border
         };
 
         if let Some(view) = self.views.get_mut(view) {
             // get the box title
             let title = Line::from(vec![
-                Span::styled("─", Style::default().fg(Color::DarkGray)),
+                Span::styled("─", theme.border()),
                 Span::styled(format!("/ {view} /"), Style::default().fg(fg)),
             ]);
 
@@ -558,22 +626,25 @@ impl Workshops<'_> {
                         Block::default()
                             .title(title)
                             .padding(Padding::uniform(1))
-                            .style(Style::default().fg(Color::White))
-                            .border_style(Style::default().fg(Color::DarkGray))
+                            .style(theme.text())
+                            .border_style(theme.border())
                             .borders(Borders::LEFT | Borders::TOP | Borders::RIGHT)
                             .border_set(border_set),
                     );
                 }
                 FocusedView::Description(widget, _) | FocusedView::SetupInstructions(widget, _) => {
-                    *widget = widget.clone().block(
-                        Block::default()
-                            .title(title)
-                            .padding(Padding::uniform(1))
-                            .style(Style::default().fg(Color::White))
-                            .border_style(Style::default().fg(Color::DarkGray))
-                            .borders(Borders::LEFT | Borders::TOP | Borders::RIGHT)
-                            .border_set(border_set),
-                    );
+                    *widget = widget
+                        .clone()
+                        .block(
+                            Block::default()
+                                .title(title)
+                                .padding(Padding::uniform(1))
+                                .style(theme.text())
+                                .border_style(theme.border())
+                                .borders(Borders::LEFT | Borders::TOP | Borders::RIGHT)
+                                .border_set(border_set),
+                        )
+                        .theme(theme);
                 }
                 _ => return,
             };
@@ -582,30 +653,41 @@ impl Workshops<'_> {
     }
 
     // render the status bar at the bottom
-    fn render_status(&mut self, area: Rect, buf: &mut Buffer) {
+    fn render_status(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
         // render the status bar at the bottom
         let [keys_area, lang_area] =
             Layout::horizontal([Constraint::Min(1), Constraint::Length(27)]).areas(area);
 
-        self.render_keys(keys_area, buf);
-        self.render_lang(lang_area, buf);
+        self.render_keys(keys_area, buf, theme);
+        self.render_lang(lang_area, buf, theme);
     }
 
     // render the keyboard shortcuts
-    fn render_keys(&mut self, area: Rect, buf: &mut Buffer) {
+    fn render_keys(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
         let title = Line::from(vec![
-            Span::styled("─", Style::default().fg(Color::DarkGray)),
+            Span::styled("─", theme.border()),
             Span::styled(
-                "/ j,k scroll / ⇥ focus / ↵ select / w homepage / l license / f filter / q quit /",
-                Style::default().fg(Color::White),
+                format!(
+                    "/ {},{} scroll / {} focus / {} select / {} homepage / {} license / {} filter / {} settings / {} quit /",
+                    self.keymap.key(Action::Down),
+                    self.keymap.key(Action::Up),
+                    self.keymap.key(Action::NextFocus),
+                    self.keymap.key(Action::Select),
+                    self.keymap.key(Action::Homepage),
+                    self.keymap.key(Action::License),
+                    self.keymap.key(Action::Filter),
+                    self.keymap.key(Action::Settings),
+                    self.keymap.key(Action::Quit)
+                ),
+                theme.text(),
             ),
         ]);
         let block = Block::default()
             .title(title)
-            .title_style(Style::default().fg(Color::White))
+            .title_style(theme.text())
             .title_position(Position::Bottom)
             .title_alignment(Alignment::Left)
-            .style(Style::default().fg(Color::DarkGray))
+            .style(theme.border())
             .borders(Borders::LEFT | Borders::BOTTOM)
             .border_set(STATUS_BORDER)
             .padding(Padding::horizontal(1));
@@ -614,23 +696,20 @@ impl Workshops<'_> {
     }
 
     // render the selected languages
-    fn render_lang(&mut self, area: Rect, buf: &mut Buffer) {
+    fn render_lang(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) {
         let spoken = languages::spoken_name(self.spoken_language);
         let programming = languages::programming_name(self.programming_language);
         let title = Line::from(vec![
-            Span::styled(
-                format!("/ {spoken} / {programming} /"),
-                Style::default().fg(Color::White),
-            ),
-            Span::styled("─", Style::default().fg(Color::DarkGray)),
+            Span::styled(format!("/ {spoken} / {programming} /"), theme.text()),
+            Span::styled("─", theme.border()),
         ]);
 
         let block = Block::default()
             .title(title)
-            .title_style(Style::default().fg(Color::White))
+            .title_style(theme.text())
             .title_position(Position::Bottom)
             .title_alignment(Alignment::Right)
-            .style(Style::default().fg(Color::DarkGray))
+            .style(theme.border())
             .borders(Borders::RIGHT | Borders::BOTTOM)
             .border_set(STATUS_BORDER)
             .padding(Padding::horizontal(1));
@@ -671,6 +750,42 @@ impl Workshops<'_> {
         Ok(())
     }
 
+    /// choose the selected workshop
+    async fn select(
+        &mut self,
+        to_ui: &Sender<screens::Event>,
+        status: &Arc<Mutex<Status>>,
+    ) -> Result<(), Error> {
+        // we're choosing a workshop so clear out the local status spoken and
+        // programming languages so we set them from the valid selections associated
+        // with the selected workshop
+        {
+            let mut status = status
+                .lock()
+                .map_err(|e| Error::StatusLock(e.to_string()))?;
+            status.set_spoken_language(None, false);
+            status.set_programming_language(None, false);
+        }
+        if let Some(workshop_key) = self.get_selected_workshop_key() {
+            if let Some(workshop_data) = self.workshops.get(&workshop_key) {
+                let all_languages = workshop_data.get_all_languages().clone();
+                to_ui
+                    .send(
+                        (
+                            None,
+                            tui::Event::SetWorkshop(
+                                self.get_selected_workshop_key(),
+                                all_languages,
+                            ),
+                        )
+                            .into(),
+                    )
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
     /// handle input events
     pub async fn handle_input_event(
         &mut self,
@@ -678,13 +793,13 @@ impl Workshops<'_> {
         to_ui: Sender<screens::Event>,
         status: Arc<Mutex<Status>>,
     ) -> Result<(), Error> {
-        if let event::Event::Key(key) = event {
-            match key.code {
-                KeyCode::PageUp => self.first().await?,
-                KeyCode::PageDown => self.last().await?,
-                KeyCode::Char('j') | KeyCode::Char('J') | KeyCode::Down => self.next().await?,
-                KeyCode::Char('k') | KeyCode::Char('K') | KeyCode::Up => self.prev().await?,
-                KeyCode::Char('l') | KeyCode::Char('L') => {
+        match event {
+            event::Event::Key(key) => match self.keymap.action(&key) {
+                Some(Action::Top) => self.first().await?,
+                Some(Action::Bottom) => self.last().await?,
+                Some(Action::Down) => self.next().await?,
+                Some(Action::Up) => self.prev().await?,
+                Some(Action::License) => {
                     if let Some(license) = self.get_license() {
                         to_ui
                             .send(
@@ -699,7 +814,7 @@ impl Workshops<'_> {
                         debug!("No selected workshop");
                     }
                 }
-                KeyCode::Char('f') | KeyCode::Char('F') => {
+                Some(Action::Filter) => {
                     // we're filtering workshops based on spoken and programming languages
                     // clear out the local status spoken and programming languages so we can
                     // set them from all valid selections
@@ -732,7 +847,7 @@ impl Workshops<'_> {
                     );
                     to_ui.send(change_spoken_language.into()).await?;
                 }
-                KeyCode::Char('w') | KeyCode::Char('W') => {
+                Some(Action::Homepage) => {
                     if let Some(url) = self.get_url() {
                         info!("Open homepage: {}", url);
                         if let Err(e) = webbrowser::open(&url) {
@@ -740,58 +855,54 @@ impl Workshops<'_> {
                         }
                     }
                 }
-                KeyCode::Tab => {
-                    if key.modifiers.contains(event::KeyModifiers::SHIFT) {
-                        // switch focus to the previous view
-                        self.focused = match self.focused {
-                            "list" => "setup",
-                            "metadata" => "list",
-                            "description" => "metadata",
-                            "setup" => "description",
-                            &_ => "list",
-                        };
-                    } else {
-                        // switch focus to the next view
-                        self.focused = match self.focused {
-                            "list" => "metadata",
-                            "metadata" => "description",
-                            "description" => "setup",
-                            "setup" => "list",
-                            &_ => "list",
-                        };
-                    }
+                Some(Action::Settings) => {
+                    to_ui.send((None, tui::Event::LoadSettings).into()).await?;
                 }
-                KeyCode::Enter => {
-                    // we're choosing a workshop so clear out the local status spoken and
-                    // programming languages so we set them from the valid selections associated
-                    // with the selected workshop
-                    {
-                        let mut status = status
-                            .lock()
-                            .map_err(|e| Error::StatusLock(e.to_string()))?;
-                        status.set_spoken_language(None, false);
-                        status.set_programming_language(None, false);
+                Some(Action::PreviousFocus) => {
+                    // switch focus to the previous view
+                    self.focused = match self.focused {
+                        "list" => "setup",
+                        "metadata" => "list",
+                        "description" => "metadata",
+                        "setup" => "description",
+                        &_ => "list",
+                    };
+                }
+                Some(Action::NextFocus) => {
+                    // switch focus to the next view
+                    self.focused = match self.focused {
+                        "list" => "metadata",
+                        "metadata" => "description",
+                        "description" => "setup",
+                        "setup" => "list",
+                        &_ => "list",
+                    };
+                }
+                Some(Action::Select) => self.select(&to_ui, &status).await?,
+                _ => {}
+            },
+            event::Event::Mouse(mouse) => {
+                // mouse events go to the view under the mouse and focus it
+                let Some(view) = self.view_at(&mouse) else {
+                    return Ok(());
+                };
+                match mouse.kind {
+                    event::MouseEventKind::ScrollDown => {
+                        self.focused = view;
+                        self.next().await?;
                     }
-                    if let Some(workshop_key) = self.get_selected_workshop_key() {
-                        if let Some(workshop_data) = self.workshops.get(&workshop_key) {
-                            let all_languages = workshop_data.get_all_languages().clone();
-                            to_ui
-                                .send(
-                                    (
-                                        None,
-                                        tui::Event::SetWorkshop(
-                                            self.get_selected_workshop_key(),
-                                            all_languages,
-                                        ),
-                                    )
-                                        .into(),
-                                )
-                                .await?;
-                        }
+                    event::MouseEventKind::ScrollUp => {
+                        self.focused = view;
+                        self.prev().await?;
                     }
+                    event::MouseEventKind::Down(event::MouseButton::Left) => {
+                        self.focused = view;
+                        self.click(&mouse, &to_ui, &status).await?;
+                    }
+                    _ => {}
                 }
-                _ => {}
             }
+            _ => {}
         }
         Ok(())
     }
@@ -813,15 +924,15 @@ impl Screen for Workshops<'_> {
         }
     }
 
-    fn render_screen(&mut self, area: Rect, buf: &mut Buffer) -> Result<(), Error> {
+    fn render_screen(&mut self, area: Rect, buf: &mut Buffer, theme: &Theme) -> Result<(), Error> {
         // this splits the screen into a top area and a one-line bottom area
         let [workshops_area, status_area] =
             Layout::vertical([Constraint::Percentage(100), Constraint::Min(1)])
                 .flex(Flex::End)
                 .areas(area);
 
-        self.render_workshops(workshops_area, buf);
-        self.render_status(status_area, buf);
+        self.render_workshops(workshops_area, buf, theme);
+        self.render_status(status_area, buf, theme);
 
         Ok(())
     }
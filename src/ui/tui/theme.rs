@@ -0,0 +1,448 @@
+use ratatui::style::{Color, Modifier, Style};
+use serde::{de::IntoDeserializer, Deserialize, Deserializer, Serialize, Serializer};
+use std::{collections::BTreeMap, str::FromStr};
+
+/// The built in color palettes
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Palette {
+    /// Light text on a dark terminal
+    #[default]
+    Dark,
+    /// Dark text on a light terminal
+    Light,
+    /// Bright colors with strong contrast
+    HighContrast,
+}
+
+impl Palette {
+    /// Every built in palette
+    pub const ALL: [Palette; 3] = [Palette::Dark, Palette::Light, Palette::HighContrast];
+
+    /// The name the palette is written with in `config.yaml`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Palette::Dark => "dark",
+            Palette::Light => "light",
+            Palette::HighContrast => "high-contrast",
+        }
+    }
+}
+
+/// The parts of the UI that can be given their own color
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Element {
+    Text,
+    Border,
+    SelectionFg,
+    SelectionBg,
+    Highlight,
+    Background,
+    Heading,
+    ListItem,
+    Hint,
+    Image,
+    Code,
+    CodeBg,
+    CodeBorder,
+    Keyword,
+    String,
+    Comment,
+    Macro,
+    Busy,
+    Progress,
+    Shadow,
+}
+
+/// The colors used to draw the screens and widgets
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Theme {
+    /// regular text and the titles of focused boxes
+    pub text: Color,
+    /// borders and the titles of unfocused boxes
+    pub border: Color,
+    /// the text of the selected list row
+    pub selection_fg: Color,
+    /// the background of the selected list row
+    pub selection_bg: Color,
+    /// the background of the highlighted lesson line
+    pub highlight: Color,
+    /// the background behind status text
+    pub background: Color,
+    /// markdown headings
+    pub heading: Color,
+    /// markdown list items
+    pub list_item: Color,
+    /// hint titles
+    pub hint: Color,
+    /// image placeholders
+    pub image: Color,
+    /// code text
+    pub code: Color,
+    /// the background of code blocks
+    pub code_bg: Color,
+    /// the border down the side of code blocks
+    pub code_border: Color,
+    /// code keywords
+    pub keyword: Color,
+    /// code strings
+    pub string: Color,
+    /// code comments
+    pub comment: Color,
+    /// code macros
+    pub macro_: Color,
+    /// the message shown while a command is running
+    pub busy: Color,
+    /// progress bars
+    pub progress: Color,
+    /// dialog drop shadows
+    pub shadow: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::palette(Palette::Dark)
+    }
+}
+
+impl Theme {
+    /// Get the colors of a built in palette
+    pub fn palette(palette: Palette) -> Self {
+        match palette {
+            Palette::Dark => Self {
+                text: Color::White,
+                border: Color::DarkGray,
+                selection_fg: Color::Black,
+                selection_bg: Color::White,
+                highlight: Color::DarkGray,
+                background: Color::Black,
+                heading: Color::LightBlue,
+                list_item: Color::LightYellow,
+                hint: Color::Cyan,
+                image: Color::LightMagenta,
+                code: Color::White,
+                code_bg: Color::Black,
+                code_border: Color::Gray,
+                keyword: Color::LightBlue,
+                string: Color::Green,
+                comment: Color::Gray,
+                macro_: Color::Yellow,
+                busy: Color::Yellow,
+                progress: Color::Green,
+                shadow: Color::DarkGray,
+            },
+            Palette::Light => Self {
+                text: Color::Black,
+                border: Color::DarkGray,
+                selection_fg: Color::White,
+                selection_bg: Color::Black,
+                highlight: Color::Gray,
+                background: Color::Reset,
+                heading: Color::Blue,
+                list_item: Color::Magenta,
+                hint: Color::Blue,
+                image: Color::Magenta,
+                code: Color::Black,
+                code_bg: Color::Reset,
+                code_border: Color::DarkGray,
+                keyword: Color::Blue,
+                string: Color::Green,
+                comment: Color::DarkGray,
+                macro_: Color::Red,
+                busy: Color::Red,
+                progress: Color::Green,
+                shadow: Color::Gray,
+            },
+            Palette::HighContrast => Self {
+                text: Color::White,
+                border: Color::White,
+                selection_fg: Color::Black,
+                selection_bg: Color::LightYellow,
+                highlight: Color::Blue,
+                background: Color::Black,
+                heading: Color::LightCyan,
+                list_item: Color::White,
+                hint: Color::LightYellow,
+                image: Color::LightMagenta,
+                code: Color::White,
+                code_bg: Color::Black,
+                code_border: Color::White,
+                keyword: Color::LightCyan,
+                string: Color::LightGreen,
+                comment: Color::Gray,
+                macro_: Color::LightYellow,
+                busy: Color::LightYellow,
+                progress: Color::LightGreen,
+                shadow: Color::Gray,
+            },
+        }
+    }
+
+    /// Get a theme without any colors, for when `NO_COLOR` is set
+    pub fn no_color() -> Self {
+        Self {
+            text: Color::Reset,
+            border: Color::Reset,
+            selection_fg: Color::Reset,
+            selection_bg: Color::Reset,
+            highlight: Color::Reset,
+            background: Color::Reset,
+            heading: Color::Reset,
+            list_item: Color::Reset,
+            hint: Color::Reset,
+            image: Color::Reset,
+            code: Color::Reset,
+            code_bg: Color::Reset,
+            code_border: Color::Reset,
+            keyword: Color::Reset,
+            string: Color::Reset,
+            comment: Color::Reset,
+            macro_: Color::Reset,
+            busy: Color::Reset,
+            progress: Color::Reset,
+            shadow: Color::Reset,
+        }
+    }
+
+    /// Get the theme to use, ignoring the configured one if the `NO_COLOR` environment variable
+    /// is set to anything but the empty string
+    pub fn from_env(config: &ThemeConfig) -> Self {
+        match std::env::var_os("NO_COLOR") {
+            Some(value) if !value.is_empty() => Self::no_color(),
+            _ => config.theme(),
+        }
+    }
+
+    /// Get the style of regular text
+    pub fn text(&self) -> Style {
+        Style::default().fg(self.text)
+    }
+
+    /// Get the style of borders
+    pub fn border(&self) -> Style {
+        Style::default().fg(self.border)
+    }
+
+    /// Get the style of box titles, which stand out when the box is focused
+    pub fn title(&self, focused: bool) -> Style {
+        if focused {
+            self.text()
+        } else {
+            self.border()
+        }
+    }
+
+    /// Get the style of the selected list row. Reverse video is used when the selection has no
+    /// colors so that it stays visible.
+    pub fn selection(&self) -> Style {
+        let style = Style::default().fg(self.selection_fg).bg(self.selection_bg);
+        if self.selection_fg == Color::Reset && self.selection_bg == Color::Reset {
+            style.add_modifier(Modifier::REVERSED)
+        } else {
+            style
+        }
+    }
+
+    /// Get the style patched onto the highlighted lesson line. Reverse video is used when the
+    /// highlight has no color so that it stays visible.
+    pub fn highlight(&self) -> Style {
+        if self.highlight == Color::Reset {
+            Style::default().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default().bg(self.highlight)
+        }
+    }
+
+    /// Get the style of code with the given foreground color
+    pub fn code(&self, fg: Color) -> Style {
+        Style::default().fg(fg).bg(self.code_bg)
+    }
+
+    /// Set the color of one element
+    pub fn set(&mut self, element: Element, color: Color) {
+        let slot = match element {
+            Element::Text => &mut self.text,
+            Element::Border => &mut self.border,
+            Element::SelectionFg => &mut self.selection_fg,
+            Element::SelectionBg => &mut self.selection_bg,
+            Element::Highlight => &mut self.highlight,
+            Element::Background => &mut self.background,
+            Element::Heading => &mut self.heading,
+            Element::ListItem => &mut self.list_item,
+            Element::Hint => &mut self.hint,
+            Element::Image => &mut self.image,
+            Element::Code => &mut self.code,
+            Element::CodeBg => &mut self.code_bg,
+            Element::CodeBorder => &mut self.code_border,
+            Element::Keyword => &mut self.keyword,
+            Element::String => &mut self.string,
+            Element::Comment => &mut self.comment,
+            Element::Macro => &mut self.macro_,
+            Element::Busy => &mut self.busy,
+            Element::Progress => &mut self.progress,
+            Element::Shadow => &mut self.shadow,
+        };
+        *slot = color;
+    }
+}
+
+/// The theme as it is written in the config file: either the name of a palette or a map with an
+/// optional `palette` and colors that override it, e.g.
+///
+/// ```yaml
+/// theme:
+///   palette: light
+///   code_bg: "#f0f0f0"
+///   heading: dark_gray
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ThemeConfig {
+    palette: Palette,
+    colors: BTreeMap<Element, Color>,
+}
+
+impl ThemeConfig {
+    /// Get the theme with the colors overridden
+    pub fn theme(&self) -> Theme {
+        let mut theme = Theme::palette(self.palette);
+        for (element, color) in &self.colors {
+            theme.set(*element, *color);
+        }
+        theme
+    }
+
+    /// Get the palette the colors start from
+    pub fn palette(&self) -> Palette {
+        self.palette
+    }
+
+    /// Check if any colors are overridden
+    pub fn has_custom_colors(&self) -> bool {
+        !self.colors.is_empty()
+    }
+
+    /// Change the palette, keeping the overridden colors
+    pub fn set_palette(&mut self, palette: Palette) {
+        self.palette = palette;
+    }
+}
+
+impl From<Palette> for ThemeConfig {
+    fn from(palette: Palette) -> Self {
+        Self {
+            palette,
+            colors: BTreeMap::default(),
+        }
+    }
+}
+
+impl Serialize for ThemeConfig {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // get the name a unit variant is written with
+        fn name<T: Serialize, E: serde::ser::Error>(value: &T) -> Result<String, E> {
+            match serde_yaml::to_value(value).map_err(E::custom)? {
+                serde_yaml::Value::String(name) => Ok(name),
+                _ => Err(E::custom("expected a name")),
+            }
+        }
+
+        if self.colors.is_empty() {
+            return self.palette.serialize(serializer);
+        }
+        let mut map = BTreeMap::new();
+        map.insert("palette".to_string(), name(&self.palette)?);
+        for (element, color) in &self.colors {
+            map.insert(name(element)?, color.to_string());
+        }
+        map.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ThemeConfig {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Palette(String),
+            Custom(BTreeMap<String, String>),
+        }
+
+        fn parse<'de, T: Deserialize<'de>, E: serde::de::Error>(s: &str) -> Result<T, E> {
+            T::deserialize(s.to_string().into_deserializer())
+                .map_err(|e: serde::de::value::Error| E::custom(e))
+        }
+
+        match Raw::deserialize(deserializer)? {
+            Raw::Palette(name) => Ok(Self::from(parse::<Palette, _>(&name)?)),
+            Raw::Custom(mut map) => {
+                let palette = match map.remove("palette") {
+                    Some(name) => parse(&name)?,
+                    None => Palette::default(),
+                };
+                let mut colors = BTreeMap::new();
+                for (name, value) in map {
+                    let element = parse(&name)?;
+                    let color = Color::from_str(&value).map_err(|_| {
+                        serde::de::Error::custom(format!("invalid color for {name}: {value}"))
+                    })?;
+                    colors.insert(element, color);
+                }
+                Ok(Self { palette, colors })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_named_palette() {
+        let config: ThemeConfig = serde_yaml::from_str("high-contrast").unwrap();
+        assert_eq!(config.theme(), Theme::palette(Palette::HighContrast));
+        assert_eq!(serde_yaml::to_string(&config).unwrap(), "high-contrast\n");
+    }
+
+    #[test]
+    fn test_custom_colors_override_palette() {
+        let yaml = "palette: light\ncode_bg: '#f0f0f0'\nheading: dark_gray\n";
+        let config: ThemeConfig = serde_yaml::from_str(yaml).unwrap();
+        let theme = config.theme();
+        assert_eq!(theme.code_bg, Color::Rgb(0xf0, 0xf0, 0xf0));
+        assert_eq!(theme.heading, Color::DarkGray);
+        assert_eq!(theme.text, Theme::palette(Palette::Light).text);
+
+        // round trips through the config file
+        let saved = serde_yaml::to_string(&config).unwrap();
+        let reloaded: ThemeConfig = serde_yaml::from_str(&saved).unwrap();
+        assert_eq!(reloaded, config);
+    }
+
+    #[test]
+    fn test_custom_colors_default_to_dark() {
+        let config: ThemeConfig = serde_yaml::from_str("text: yellow").unwrap();
+        let theme = config.theme();
+        assert_eq!(theme.text, Color::Yellow);
+        assert_eq!(theme.border, Theme::palette(Palette::Dark).border);
+    }
+
+    #[test]
+    fn test_invalid_theme() {
+        assert!(serde_yaml::from_str::<ThemeConfig>("sepia").is_err());
+        assert!(serde_yaml::from_str::<ThemeConfig>("txt: white").is_err());
+        assert!(serde_yaml::from_str::<ThemeConfig>("text: not-a-color").is_err());
+    }
+
+    #[test]
+    fn test_no_color_keeps_selection_visible() {
+        let theme = Theme::no_color();
+        assert!(theme.selection().add_modifier.contains(Modifier::REVERSED));
+        assert!(theme.highlight().add_modifier.contains(Modifier::REVERSED));
+        assert!(!Theme::default()
+            .selection()
+            .add_modifier
+            .contains(Modifier::REVERSED));
+    }
+}
@@ -1,3 +1,8 @@
+pub mod bidi;
+pub mod mermaid;
+pub mod mouse;
+pub mod width;
+
 pub mod scrollbox;
 pub use scrollbox::ScrollBox;
 
@@ -12,6 +17,6 @@ pub use statusbar::{StatusBar, StatusMode};
 
 pub mod lessonbox;
 pub use lessonbox::{
-    parse_markdown, CodeBlock, Content, ContentBlock, Heading, Hint, LessonBox, LessonBoxState,
-    ListItem, ParagraphBlock,
+    parse_markdown, CodeBlock, Content, ContentBlock, Heading, Hint, Image, LessonBox,
+    LessonBoxState, ListItem, ParagraphBlock,
 };
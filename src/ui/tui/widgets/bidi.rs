@@ -0,0 +1,133 @@
+use crate::languages::spoken::Direction;
+use ratatui::{
+    layout::Alignment,
+    text::{Line, Span},
+};
+use std::ops::Range;
+use unicode_bidi::{BidiInfo, Level};
+
+/// Get the paragraph alignment for the given text direction
+pub fn alignment(direction: Direction) -> Alignment {
+    match direction {
+        Direction::LeftToRight => Alignment::Left,
+        Direction::RightToLeft => Alignment::Right,
+    }
+}
+
+/// Reorder a single line of logical text into visual order using the given base direction.
+/// Lines that are already left-to-right are returned unchanged.
+pub fn reorder(text: &str, direction: Direction) -> String {
+    if direction == Direction::LeftToRight || text.is_empty() {
+        return text.to_string();
+    }
+
+    let bidi_info = BidiInfo::new(text, Some(Level::rtl()));
+    bidi_info
+        .paragraphs
+        .iter()
+        .map(|para| {
+            bidi_info
+                .reorder_line(para, para.range.clone())
+                .into_owned()
+        })
+        .collect::<Vec<_>>()
+        .join("")
+}
+
+/// Convert a rendered prose line into visual order and align it for the given direction. Each
+/// character keeps the style of the span it came from, even when reordering moves it across span
+/// boundaries.
+pub fn visual_line(line: Line<'static>, direction: Direction) -> Line<'static> {
+    if direction == Direction::LeftToRight {
+        return line;
+    }
+
+    // the logical text and the byte offset where each span's style ends
+    let mut text = String::new();
+    let mut ends = Vec::with_capacity(line.spans.len());
+    for span in &line.spans {
+        text.push_str(&span.content);
+        ends.push((text.len(), span.style));
+    }
+    let style_at = |offset: usize| {
+        ends.iter()
+            .find(|(end, _)| offset < *end)
+            .map(|(_, style)| *style)
+            .unwrap_or_default()
+    };
+
+    // collect the characters in visual order, merging neighbours with the same style
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    let mut push = |offset: usize, c: char| {
+        let style = style_at(offset);
+        match spans.last_mut() {
+            Some(last) if last.style == style => last.content.to_mut().push(c),
+            _ => spans.push(Span::styled(c.to_string(), style)),
+        }
+    };
+    if !text.is_empty() {
+        let bidi_info = BidiInfo::new(&text, Some(Level::rtl()));
+        for para in &bidi_info.paragraphs {
+            let range = para.range.clone();
+            let (levels, runs) = bidi_info.visual_runs(para, range.clone());
+            // the same runs that reorder() reverses
+            let reversed = |run: &Range<usize>| levels[run.start].is_rtl();
+            if !bidi_info.levels[range.clone()].iter().any(Level::is_rtl)
+                || !runs.iter().any(reversed)
+            {
+                text[range.clone()]
+                    .char_indices()
+                    .for_each(|(i, c)| push(range.start + i, c));
+                continue;
+            }
+            for run in &runs {
+                let chars = text[run.clone()].char_indices();
+                if reversed(run) {
+                    chars.rev().for_each(|(i, c)| push(run.start + i, c));
+                } else {
+                    chars.for_each(|(i, c)| push(run.start + i, c));
+                }
+            }
+        }
+    }
+    Line::from(spans)
+        .style(line.style)
+        .alignment(alignment(direction))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::{Modifier, Style};
+
+    #[test]
+    fn test_visual_line_keeps_span_styles() {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let line = Line::from(vec![
+            Span::raw("שלום "),
+            Span::styled("עולם", bold),
+            Span::raw(" run"),
+        ]);
+        let logical: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+        let visual = visual_line(line, Direction::RightToLeft);
+
+        // the text is reordered like a plain line
+        let text: String = visual.spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(text, reorder(&logical, Direction::RightToLeft));
+        // and the bold word is still the only bold text
+        let styled: Vec<(&str, Style)> = visual
+            .spans
+            .iter()
+            .map(|s| (s.content.as_ref(), s.style))
+            .collect();
+        assert_eq!(
+            styled,
+            vec![
+                ("run ", Style::default()),
+                ("םלוע", bold),
+                (" םולש", Style::default()),
+            ]
+        );
+        assert_eq!(visual.alignment, Some(Alignment::Right));
+    }
+}
@@ -1,9 +1,15 @@
-use crate::ui::tui::widgets::scrolltext::Scroll;
+use crate::{
+    languages::spoken::Direction,
+    ui::tui::{
+        widgets::{bidi, mermaid, scrolltext::Scroll, width},
+        Theme,
+    },
+};
 use pulldown_cmark::{Event, Parser, Tag, TagEnd};
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{
         Block, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, StatefulWidget, Widget,
@@ -16,10 +22,11 @@ pub trait ContentBlock {
     ///
     /// # Arguments
     /// * `width` - The width of the render area for text wrapping
+    /// * `theme` - The colors to style the text with
     ///
     /// # Returns
     /// A vector of ratatui Line objects with proper styling
-    fn render(&self, width: u16) -> Vec<Line<'static>>;
+    fn render(&self, width: u16, theme: &Theme) -> Vec<Line<'static>>;
 }
 
 /// A heading content block (H1, H2, H3, etc.)
@@ -30,12 +37,12 @@ pub struct Heading {
 }
 
 impl ContentBlock for Heading {
-    fn render(&self, width: u16) -> Vec<Line<'static>> {
+    fn render(&self, width: u16, theme: &Theme) -> Vec<Line<'static>> {
         let style = Style::default()
-            .fg(Color::LightBlue)
+            .fg(theme.heading)
             .add_modifier(Modifier::BOLD);
 
-        let wrapped_lines = textwrap::wrap(&self.text, width as usize);
+        let wrapped_lines = width::wrap(&self.text, width as usize);
         wrapped_lines
             .into_iter()
             .map(|line| Line::from(Span::styled(line.to_string(), style)))
@@ -50,8 +57,8 @@ pub struct ParagraphBlock {
 }
 
 impl ContentBlock for ParagraphBlock {
-    fn render(&self, width: u16) -> Vec<Line<'static>> {
-        let wrapped_lines = textwrap::wrap(&self.text, width as usize);
+    fn render(&self, width: u16, _theme: &Theme) -> Vec<Line<'static>> {
+        let wrapped_lines = width::wrap(&self.text, width as usize);
         wrapped_lines
             .into_iter()
             .map(|line| Line::from(line.to_string()))
@@ -67,14 +74,14 @@ pub struct ListItem {
 }
 
 impl ContentBlock for ListItem {
-    fn render(&self, width: u16) -> Vec<Line<'static>> {
-        let style = Style::default().fg(Color::LightYellow);
+    fn render(&self, width: u16, theme: &Theme) -> Vec<Line<'static>> {
+        let style = Style::default().fg(theme.list_item);
         let indent = "  ".repeat(self.indent_level as usize);
         let bullet_prefix = format!("{indent}• ");
         let continuation_indent = format!("{indent}  "); // Same base indent + 2 spaces for bullet alignment
 
-        let available_width = width.saturating_sub(bullet_prefix.len() as u16);
-        let wrapped_lines = textwrap::wrap(&self.text, available_width.max(10) as usize);
+        let available_width = width.saturating_sub(width::display_width(&bullet_prefix) as u16);
+        let wrapped_lines = width::wrap(&self.text, available_width.max(10) as usize);
 
         wrapped_lines
             .into_iter()
@@ -98,6 +105,29 @@ pub struct CodeBlock {
     pub code: String,
 }
 
+/// An image reference, shown as a placeholder that can be opened externally
+#[derive(Clone, Debug)]
+pub struct Image {
+    pub alt: String,
+    pub target: String,
+}
+
+impl ContentBlock for Image {
+    fn render(&self, width: u16, theme: &Theme) -> Vec<Line<'static>> {
+        let style = Style::default().fg(theme.image);
+        let label = if self.alt.is_empty() {
+            format!("▣ Image: {}", self.target)
+        } else {
+            format!("▣ Image: {} ({})", self.alt, self.target)
+        };
+
+        width::wrap(&label, width as usize)
+            .into_iter()
+            .map(|line| Line::from(Span::styled(line.to_string(), style)))
+            .collect()
+    }
+}
+
 /// Enum representing different types of content blocks
 #[derive(Clone, Debug)]
 pub enum Content {
@@ -105,17 +135,48 @@ pub enum Content {
     Paragraph(ParagraphBlock),
     ListItem(ListItem),
     CodeBlock(CodeBlock),
+    Image(Image),
     Hint(Hint),
 }
 
 impl ContentBlock for Content {
-    fn render(&self, width: u16) -> Vec<Line<'static>> {
+    fn render(&self, width: u16, theme: &Theme) -> Vec<Line<'static>> {
         match self {
-            Content::Heading(h) => h.render(width),
-            Content::Paragraph(p) => p.render(width),
-            Content::ListItem(l) => l.render(width),
-            Content::CodeBlock(c) => c.render(width),
-            Content::Hint(h) => h.render(width),
+            Content::Heading(h) => h.render(width, theme),
+            Content::Paragraph(p) => p.render(width, theme),
+            Content::ListItem(l) => l.render(width, theme),
+            Content::CodeBlock(c) => c.render(width, theme),
+            Content::Image(i) => i.render(width, theme),
+            Content::Hint(h) => h.render(width, theme),
+        }
+    }
+}
+
+impl Content {
+    /// Render the content block for the given text direction. Prose is reordered into visual
+    /// order and aligned to the reading edge; code blocks always stay left-to-right.
+    pub fn render_directional(
+        &self,
+        width: u16,
+        direction: Direction,
+        theme: &Theme,
+    ) -> Vec<Line<'static>> {
+        match self {
+            Content::CodeBlock(c) => c.render(width, theme),
+            Content::Hint(h) => h.render_directional(width, direction, theme),
+            _ => self
+                .render(width, theme)
+                .into_iter()
+                .map(|line| bidi::visual_line(line, direction))
+                .collect(),
+        }
+    }
+
+    /// Get the file or URL this content block opens, if any
+    pub fn link(&self) -> Option<&str> {
+        match self {
+            Content::Image(i) => Some(&i.target),
+            _ => None,
         }
     }
 }
@@ -129,19 +190,21 @@ pub struct Hint {
 }
 
 impl ContentBlock for CodeBlock {
-    fn render(&self, _width: u16) -> Vec<Line<'static>> {
+    fn render(&self, width: u16, theme: &Theme) -> Vec<Line<'static>> {
         let mut lines = Vec::new();
-        let border_style = Style::default().fg(Color::Gray);
+        let border_style = Style::default().fg(theme.code_border);
 
         // Create simple top border
         let top_border = "┌─";
         lines.push(Line::from(Span::styled(top_border, border_style)));
 
         // Add code content with side borders
-        let code_lines = if let Some(language) = &self.language {
-            self.render_with_syntax_highlighting(language)
-        } else {
-            self.render_plain()
+        let code_lines = match self.language.as_deref() {
+            Some("mermaid") => self
+                .render_diagram(width.saturating_sub(2), theme)
+                .unwrap_or_else(|| self.render_plain(theme)),
+            Some(language) => self.render_with_syntax_highlighting(language, theme),
+            None => self.render_plain(theme),
         };
 
         for code_line in code_lines {
@@ -160,8 +223,8 @@ impl ContentBlock for CodeBlock {
 
 impl CodeBlock {
     /// Render code block with syntax highlighting
-    fn render_with_syntax_highlighting(&self, language: &str) -> Vec<Line<'static>> {
-        let default_style = Style::default().fg(Color::White).bg(Color::Black);
+    fn render_with_syntax_highlighting(&self, language: &str, theme: &Theme) -> Vec<Line<'static>> {
+        let default_style = theme.code(theme.code);
 
         // Simple syntax highlighting based on common patterns
         let lines: Vec<&str> = self.code.lines().collect();
@@ -170,7 +233,7 @@ impl CodeBlock {
             .map(|line| {
                 let mut spans = Vec::new();
                 let trimmed = line.trim_start();
-                let indent = " ".repeat(line.len() - trimmed.len());
+                let indent = " ".repeat(width::display_width(&line[..line.len() - trimmed.len()]));
 
                 if !indent.is_empty() {
                     spans.push(Span::styled(indent, default_style));
@@ -178,8 +241,8 @@ impl CodeBlock {
 
                 // Apply simple syntax highlighting based on language
                 let styled_content = match language {
-                    "rust" => self.highlight_rust_line(trimmed),
-                    "python" => self.highlight_python_line(trimmed),
+                    "rust" => self.highlight_rust_line(trimmed, theme),
+                    "python" => self.highlight_python_line(trimmed, theme),
                     _ => vec![Span::styled(trimmed.to_string(), default_style)],
                 };
 
@@ -190,16 +253,16 @@ impl CodeBlock {
     }
 
     /// Simple Rust syntax highlighting
-    fn highlight_rust_line(&self, line: &str) -> Vec<Span<'static>> {
+    fn highlight_rust_line(&self, line: &str, theme: &Theme) -> Vec<Span<'static>> {
         let keywords = [
             "fn", "let", "mut", "if", "else", "for", "while", "match", "impl", "struct", "enum",
             "use", "pub",
         ];
-        let keyword_style = Style::default().fg(Color::LightBlue).bg(Color::Black);
-        let string_style = Style::default().fg(Color::Green).bg(Color::Black);
-        let comment_style = Style::default().fg(Color::Gray).bg(Color::Black);
-        let _function_style = Style::default().fg(Color::Yellow).bg(Color::Black);
-        let default_style = Style::default().fg(Color::White).bg(Color::Black);
+        let keyword_style = theme.code(theme.keyword);
+        let string_style = theme.code(theme.string);
+        let comment_style = theme.code(theme.comment);
+        let _function_style = theme.code(theme.macro_);
+        let default_style = theme.code(theme.code);
 
         if line.trim_start().starts_with("//") {
             return vec![Span::styled(line.to_string(), comment_style)];
@@ -271,16 +334,16 @@ impl CodeBlock {
     }
 
     /// Simple Python syntax highlighting
-    fn highlight_python_line(&self, line: &str) -> Vec<Span<'static>> {
+    fn highlight_python_line(&self, line: &str, theme: &Theme) -> Vec<Span<'static>> {
         let keywords = [
             "def", "class", "if", "elif", "else", "for", "while", "try", "except", "import",
             "from", "return", "print",
         ];
-        let keyword_style = Style::default().fg(Color::LightBlue).bg(Color::Black);
-        let string_style = Style::default().fg(Color::Green).bg(Color::Black);
-        let comment_style = Style::default().fg(Color::Gray).bg(Color::Black);
-        let _function_style = Style::default().fg(Color::Yellow).bg(Color::Black);
-        let default_style = Style::default().fg(Color::White).bg(Color::Black);
+        let keyword_style = theme.code(theme.keyword);
+        let string_style = theme.code(theme.string);
+        let comment_style = theme.code(theme.comment);
+        let _function_style = theme.code(theme.macro_);
+        let default_style = theme.code(theme.code);
 
         if line.trim_start().starts_with('#') {
             return vec![Span::styled(line.to_string(), comment_style)];
@@ -289,16 +352,13 @@ impl CodeBlock {
         // Simple keyword-based highlighting for Python
         let words: Vec<&str> = line.split_whitespace().collect();
         let mut spans = Vec::new();
-        let mut pos = 0;
+        let mut rest = line;
 
         for word in words {
             // Add any whitespace before the word
-            while pos < line.len() && line.chars().nth(pos).unwrap().is_whitespace() {
-                spans.push(Span::styled(
-                    line.chars().nth(pos).unwrap().to_string(),
-                    default_style,
-                ));
-                pos += 1;
+            let word_start = rest.find(word).unwrap_or(0);
+            for ch in rest[..word_start].chars() {
+                spans.push(Span::styled(ch.to_string(), default_style));
             }
 
             let style = if keywords.contains(&word.trim_end_matches(['(', ':', ')', '"'])) {
@@ -310,15 +370,27 @@ impl CodeBlock {
             };
 
             spans.push(Span::styled(word.to_string(), style));
-            pos += word.len();
+            rest = &rest[word_start + word.len()..];
         }
 
         spans
     }
 
+    /// Render a mermaid graph as a box-drawing diagram, if it can be laid out in the width
+    fn render_diagram(&self, width: u16, theme: &Theme) -> Option<Vec<Line<'static>>> {
+        let style = theme.code(theme.code);
+
+        let rows = mermaid::render(&self.code, width as usize)?;
+        Some(
+            rows.into_iter()
+                .map(|row| Line::from(Span::styled(row, style)))
+                .collect(),
+        )
+    }
+
     /// Render code block with plain styling
-    fn render_plain(&self) -> Vec<Line<'static>> {
-        let style = Style::default().bg(Color::Black).fg(Color::White);
+    fn render_plain(&self, theme: &Theme) -> Vec<Line<'static>> {
+        let style = theme.code(theme.code);
 
         let lines: Vec<&str> = self.code.lines().collect();
         lines
@@ -329,48 +401,80 @@ impl CodeBlock {
 }
 
 impl ContentBlock for Hint {
-    fn render(&self, width: u16) -> Vec<Line<'static>> {
+    fn render(&self, width: u16, theme: &Theme) -> Vec<Line<'static>> {
+        self.render_directional(width, Direction::LeftToRight, theme)
+    }
+}
+
+impl Hint {
+    /// Render the hint for the given text direction
+    fn render_directional(
+        &self,
+        width: u16,
+        direction: Direction,
+        theme: &Theme,
+    ) -> Vec<Line<'static>> {
+        self.render_linked(width, direction, theme)
+            .into_iter()
+            .map(|(line, _)| line)
+            .collect()
+    }
+
+    /// Render the hint for the given text direction, pairing each line with the file or URL
+    /// it opens, if any
+    fn render_linked(
+        &self,
+        width: u16,
+        direction: Direction,
+        theme: &Theme,
+    ) -> Vec<(Line<'static>, Option<String>)> {
         if self.expanded {
             // When expanded, show title and all content
-            let title_style = Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD);
+            let title_style = Style::default().fg(theme.hint).add_modifier(Modifier::BOLD);
 
-            let mut lines = vec![Line::from(Span::styled(
-                format!("▼ Hint: {}", self.title),
-                title_style,
-            ))];
+            let mut lines = vec![(
+                bidi::visual_line(
+                    Line::from(Span::styled(format!("▼ Hint: {}", self.title), title_style)),
+                    direction,
+                ),
+                None,
+            )];
 
             // Add blank line after title if there's content
             if !self.content.is_empty() {
-                lines.push(Line::from(""));
+                lines.push((Line::from(""), None));
             }
 
             // Render all content blocks recursively with blank lines between them
             for (i, content) in self.content.iter().enumerate() {
                 // Add blank line before each content block (except first)
                 if i > 0 {
-                    lines.push(Line::from(""));
+                    lines.push((Line::from(""), None));
                 }
-                lines.extend(content.render(width));
+                let link = content.link().map(String::from);
+                lines.extend(
+                    content
+                        .render_directional(width, direction, theme)
+                        .into_iter()
+                        .map(|line| (line, link.clone())),
+                );
             }
 
             lines
         } else {
             // When collapsed, show only title with right arrow
-            let title_style = Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD);
-
-            vec![Line::from(Span::styled(
-                format!("▶ Hint: {}", self.title),
-                title_style,
-            ))]
+            let title_style = Style::default().fg(theme.hint).add_modifier(Modifier::BOLD);
+
+            vec![(
+                bidi::visual_line(
+                    Line::from(Span::styled(format!("▶ Hint: {}", self.title), title_style)),
+                    direction,
+                ),
+                None,
+            )]
         }
     }
-}
 
-impl Hint {
     /// Toggle the expanded state of the hint
     pub fn toggle(&mut self) {
         self.expanded = !self.expanded;
@@ -401,6 +505,9 @@ pub fn parse_markdown(markdown: &str) -> Vec<Content> {
     let mut collecting_hint = false;
     let mut hint_title = String::new();
     let mut hint_content = Vec::new();
+    let mut image: Option<(String, usize)> = None;
+    let mut images = Vec::new();
+    let mut after_image = false;
 
     for event in parser {
         match event {
@@ -409,6 +516,7 @@ pub fn parse_markdown(markdown: &str) -> Vec<Content> {
                 heading_level = level as u8;
                 current_text.clear();
             }
+            #[allow(clippy::collapsible_match)]
             Event::End(TagEnd::Heading(_)) => {
                 if in_heading {
                     let text = current_text.trim().to_string();
@@ -446,6 +554,14 @@ pub fn parse_markdown(markdown: &str) -> Vec<Content> {
                         content_blocks.push(Content::Heading(heading));
                     }
 
+                    // Images in the heading follow it, or start the hint's content
+                    for image in images.drain(..) {
+                        if collecting_hint {
+                            hint_content.push(Content::Image(image));
+                        } else {
+                            content_blocks.push(Content::Image(image));
+                        }
+                    }
                     in_heading = false;
                     current_text.clear();
                 }
@@ -466,6 +582,14 @@ pub fn parse_markdown(markdown: &str) -> Vec<Content> {
                         content_blocks.push(Content::Paragraph(paragraph));
                     }
                 }
+                // Images in the paragraph follow it as their own blocks
+                for image in images.drain(..) {
+                    if collecting_hint {
+                        hint_content.push(Content::Image(image));
+                    } else {
+                        content_blocks.push(Content::Image(image));
+                    }
+                }
                 in_paragraph = false;
                 current_text.clear();
             }
@@ -486,6 +610,13 @@ pub fn parse_markdown(markdown: &str) -> Vec<Content> {
                         content_blocks.push(Content::ListItem(list_item));
                     }
                 }
+                for image in images.drain(..) {
+                    if collecting_hint {
+                        hint_content.push(Content::Image(image));
+                    } else {
+                        content_blocks.push(Content::Image(image));
+                    }
+                }
                 in_list_item = false;
                 current_text.clear();
             }
@@ -520,12 +651,27 @@ pub fn parse_markdown(markdown: &str) -> Vec<Content> {
                 code_language = None;
                 code_content.clear();
             }
+            Event::Start(Tag::Image { dest_url, .. }) => {
+                // The alt text arrives as text events until the image ends
+                image = Some((dest_url.to_string(), current_text.len()));
+            }
+            Event::End(TagEnd::Image) => {
+                if let Some((target, start)) = image.take() {
+                    let alt = current_text.split_off(start).trim().to_string();
+                    images.push(Image { alt, target });
+                    after_image = true;
+                }
+            }
             Event::Text(text) => {
                 if in_code_block {
                     code_content.push_str(&text);
+                } else if after_image && current_text.ends_with(char::is_whitespace) {
+                    // the spaces on both sides of a removed image collapse into one
+                    current_text.push_str(text.trim_start());
                 } else {
                     current_text.push_str(&text);
                 }
+                after_image = false;
             }
             Event::Code(code) => {
                 current_text.push_str(&format!("`{code}`"));
@@ -533,8 +679,11 @@ pub fn parse_markdown(markdown: &str) -> Vec<Content> {
             Event::SoftBreak | Event::HardBreak => {
                 if in_code_block {
                     code_content.push('\n');
-                } else if !current_text.is_empty() {
-                    current_text.push(' ');
+                } else {
+                    let spaced = after_image && current_text.ends_with(char::is_whitespace);
+                    if !current_text.is_empty() && !spaced {
+                        current_text.push(' ');
+                    }
                 }
             }
             _ => {}
@@ -566,6 +715,12 @@ pub struct LessonBoxState {
     highlighted_line: usize,
     /// Is there a highlighted line?
     is_highlighted_line: bool,
+    /// The reading direction of the prose
+    direction: Direction,
+    /// The colors the cached lines are styled with
+    theme: Theme,
+    /// The width the cached lines are wrapped to, from the last render
+    width: u16,
 }
 
 /// Cached line with metadata for hint tracking
@@ -577,6 +732,8 @@ struct CachedLine {
     hint_index: Option<usize>,
     /// Whether this line is the title line of a hint
     is_hint_title: bool,
+    /// The file or URL this line opens (if any)
+    link: Option<String>,
 }
 
 impl LessonBoxState {
@@ -591,11 +748,27 @@ impl LessonBoxState {
             window_lines: 0,
             highlighted_line: 0,
             is_highlighted_line: true,
+            direction: Direction::LeftToRight,
+            theme: Theme::default(),
+            width: 80, // Default width
         };
-        state.rebuild_cache(80); // Default width
+        state.rebuild_cache(state.width);
         state
     }
 
+    /// Set the reading direction of the prose and rebuild the cached lines
+    pub fn set_direction(&mut self, direction: Direction) {
+        if self.direction != direction {
+            self.direction = direction;
+            self.rebuild_cache(self.width);
+        }
+    }
+
+    /// Get the width the lines are wrapped to, from the last render
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
     pub fn set_highlighted_line(&mut self, highlighting: bool) {
         self.is_highlighted_line = highlighting;
     }
@@ -622,28 +795,32 @@ impl LessonBoxState {
                     line: Line::from(""),
                     hint_index: None,
                     is_hint_title: false,
+                    link: None,
                 });
             }
 
             match content_block {
                 Content::Hint(hint) => {
-                    let lines = hint.render(width);
-                    for (i, line) in lines.into_iter().enumerate() {
+                    let lines = hint.render_linked(width, self.direction, &self.theme);
+                    for (i, (line, link)) in lines.into_iter().enumerate() {
                         self.cached_lines.push(CachedLine {
                             line,
                             hint_index: Some(hint_index),
                             is_hint_title: i == 0, // First line is the title
+                            link,
                         });
                     }
                     hint_index += 1;
                 }
                 _ => {
-                    let lines = content_block.render(width);
+                    let lines =
+                        content_block.render_directional(width, self.direction, &self.theme);
                     for line in lines {
                         self.cached_lines.push(CachedLine {
                             line,
                             hint_index: None,
                             is_hint_title: false,
+                            link: content_block.link().map(String::from),
                         });
                     }
                 }
@@ -682,13 +859,7 @@ impl LessonBoxState {
             return;
         }
 
-        let scroll_offset = match self.scroll {
-            Scroll::Top => 0,
-            Scroll::MaybeTop(offset) | Scroll::Offset(offset) | Scroll::MaybeBottom(offset) => {
-                offset
-            }
-            Scroll::Bottom => self.total_lines.saturating_sub(self.window_lines),
-        };
+        let scroll_offset = self.scroll_offset();
 
         let view_start = scroll_offset;
         let view_end = scroll_offset + self.window_lines;
@@ -706,6 +877,32 @@ impl LessonBoxState {
         }
     }
 
+    /// Get the index of the first line in the current view
+    fn scroll_offset(&self) -> usize {
+        match self.scroll {
+            Scroll::Top => 0,
+            Scroll::MaybeTop(offset) | Scroll::Offset(offset) | Scroll::MaybeBottom(offset) => {
+                offset
+            }
+            Scroll::Bottom => self.total_lines.saturating_sub(self.window_lines),
+        }
+    }
+
+    /// Move the highlight to the line shown at the given row of the view, returning false if
+    /// there is no line there
+    pub fn highlight_at(&mut self, row: usize) -> bool {
+        if row >= self.window_lines {
+            return false;
+        }
+        let line = self.scroll_offset() + row;
+        if line < self.total_lines {
+            self.highlighted_line = line;
+            true
+        } else {
+            false
+        }
+    }
+
     /// Check if the highlighted line is a collapsed hint title
     pub fn is_highlighted_hint(&self) -> Option<usize> {
         if !self.is_highlighted_line {
@@ -721,6 +918,16 @@ impl LessonBoxState {
         None
     }
 
+    /// Get the file or URL the highlighted line opens, if any
+    pub fn highlighted_link(&self) -> Option<&str> {
+        if !self.is_highlighted_line {
+            return None;
+        }
+        self.cached_lines
+            .get(self.highlighted_line)
+            .and_then(|cached_line| cached_line.link.as_deref())
+    }
+
     /// Toggle hint at highlighted line if it's a hint title
     pub fn toggle_highlighted_hint(&mut self, width: u16) -> bool {
         if let Some(hint_idx) = self.is_highlighted_hint() {
@@ -804,6 +1011,8 @@ pub struct LessonBox<'a> {
     block: Option<Block<'a>>,
     /// The style of the text
     style: Style,
+    /// The colors to style the content with
+    theme: Theme,
 }
 
 impl<'a> LessonBox<'a> {
@@ -823,6 +1032,12 @@ impl<'a> LessonBox<'a> {
         self.style = style;
         self
     }
+
+    /// Set the colors
+    pub fn theme(mut self, theme: &Theme) -> Self {
+        self.theme = *theme;
+        self
+    }
 }
 
 impl StatefulWidget for LessonBox<'_> {
@@ -836,7 +1051,17 @@ impl StatefulWidget for LessonBox<'_> {
             area.width
         };
 
+        // Restyle the cached lines if the colors changed
+        if state.theme != self.theme {
+            state.theme = self.theme;
+            state.cached_lines.clear();
+        }
+
         // Rebuild cache if width changed or cache is empty
+        if state.width != width {
+            state.width = width;
+            state.cached_lines.clear();
+        }
         if state.cached_lines.is_empty() {
             state.rebuild_cache(width);
         }
@@ -901,14 +1126,14 @@ impl StatefulWidget for LessonBox<'_> {
                     let mut highlighted_line = cached_line.line.clone();
 
                     if is_hint_title {
-                        // Highlighted hint title: black text on white background
+                        // Highlighted hint title: selection colors
                         for span in &mut highlighted_line.spans {
-                            span.style = Style::default().fg(Color::Black).bg(Color::White);
+                            span.style = self.theme.selection();
                         }
                     } else {
-                        // Regular highlighted line: dark gray background
+                        // Regular highlighted line: highlight background
                         for span in &mut highlighted_line.spans {
-                            span.style = span.style.bg(Color::DarkGray);
+                            span.style = span.style.patch(self.theme.highlight());
                         }
                     }
 
@@ -916,22 +1141,26 @@ impl StatefulWidget for LessonBox<'_> {
                     let current_width: usize = highlighted_line
                         .spans
                         .iter()
-                        .map(|span| span.content.chars().count())
+                        .map(|span| width::display_width(&span.content))
                         .sum();
 
                     let remaining_width = content_width.saturating_sub(current_width as u16);
 
                     if remaining_width > 0 {
                         let fill_style = if is_hint_title {
-                            Style::default().fg(Color::Black).bg(Color::White)
+                            self.theme.selection()
                         } else {
-                            Style::default().bg(Color::DarkGray)
+                            self.theme.highlight()
                         };
 
-                        highlighted_line.spans.push(Span::styled(
-                            " ".repeat(remaining_width as usize),
-                            fill_style,
-                        ));
+                        let fill = Span::styled(" ".repeat(remaining_width as usize), fill_style);
+
+                        // right aligned lines are filled from the left edge
+                        if highlighted_line.alignment == Some(Alignment::Right) {
+                            highlighted_line.spans.insert(0, fill);
+                        } else {
+                            highlighted_line.spans.push(fill);
+                        }
                     }
 
                     highlighted_line
@@ -982,7 +1211,7 @@ mod tests {
             level: 1,
             text: "Test Heading".to_string(),
         };
-        let lines = heading.render(80);
+        let lines = heading.render(80, &Theme::default());
         assert_eq!(lines.len(), 1);
         assert_eq!(lines[0].spans[0].content, "Test Heading");
     }
@@ -992,7 +1221,7 @@ mod tests {
         let paragraph = ParagraphBlock {
             text: "This is a test paragraph with some content.".to_string(),
         };
-        let lines = paragraph.render(80);
+        let lines = paragraph.render(80, &Theme::default());
         assert_eq!(lines.len(), 1);
         assert_eq!(
             lines[0].spans[0].content,
@@ -1006,7 +1235,7 @@ mod tests {
             text: "Test list item".to_string(),
             indent_level: 0,
         };
-        let lines = list_item.render(80);
+        let lines = list_item.render(80, &Theme::default());
         assert_eq!(lines.len(), 1);
         assert!(lines[0].spans[0].content.contains("• Test list item"));
     }
@@ -1017,7 +1246,7 @@ mod tests {
             text: "This is a very long list item that should wrap to multiple lines when the width is constrained".to_string(),
             indent_level: 0,
         };
-        let lines = list_item.render(30);
+        let lines = list_item.render(30, &Theme::default());
         assert!(lines.len() > 1);
 
         // First line should have bullet
@@ -1035,7 +1264,7 @@ mod tests {
             language: Some("rust".to_string()),
             code: "fn main() {\n    println!(\"Hello, world!\");\n}".to_string(),
         };
-        let lines = code_block.render(80);
+        let lines = code_block.render(80, &Theme::default());
         assert_eq!(lines.len(), 5); // top border + 3 code lines + bottom border
 
         // Check top border
@@ -1073,7 +1302,7 @@ mod tests {
             level: 1,
             text: "Test".to_string(),
         });
-        let lines = content.render(80);
+        let lines = content.render(80, &Theme::default());
         assert_eq!(lines.len(), 1);
         assert_eq!(lines[0].spans[0].content, "Test");
     }
@@ -1191,7 +1420,7 @@ fn main() {
             language: Some("rust".to_string()),
             code: "fn main() {\n    println!(\"Hello, world!\");\n}".to_string(),
         };
-        let lines = code_block.render(80);
+        let lines = code_block.render(80, &Theme::default());
         assert_eq!(lines.len(), 5); // top border + 3 code lines + bottom border
 
         // Should have multiple spans with different colors for syntax highlighting
@@ -1211,7 +1440,7 @@ fn main() {
             language: None,
             code: "some code without language".to_string(),
         };
-        let lines = code_block.render(80);
+        let lines = code_block.render(80, &Theme::default());
         assert_eq!(lines.len(), 3); // top border + 1 code line + bottom border
 
         // Check top border
@@ -1240,7 +1469,7 @@ fn main() {
             language: Some("python".to_string()),
             code: "def hello():\n    print(\"Hello, world!\")".to_string(),
         };
-        let lines = code_block.render(80);
+        let lines = code_block.render(80, &Theme::default());
         assert_eq!(lines.len(), 4); // top border + 2 code lines + bottom border
 
         // Check top border
@@ -1265,7 +1494,7 @@ fn main() {
             language: Some("javascript".to_string()),
             code: "console.log('Hello');".to_string(),
         };
-        let lines = code_block.render(40);
+        let lines = code_block.render(40, &Theme::default());
         assert_eq!(lines.len(), 3); // top border + 1 code line + bottom border
 
         // Check top border formatting
@@ -1295,7 +1524,7 @@ This is hint content.
 
         // Should have content and cached lines
         assert_eq!(state.content.len(), 3); // heading, paragraph, hint
-        assert!(state.cached_lines.len() > 0);
+        assert!(!state.cached_lines.is_empty());
 
         // Should have one hint
         let hint_count = state
@@ -1583,4 +1812,289 @@ It should be in the main document.
             assert_ne!(initial_lines, after_toggle_lines);
         }
     }
+
+    #[test]
+    fn test_rtl_prose_is_right_aligned_and_reordered() {
+        let markdown = "# שלום עולם\n\nזהו paragraph בעברית.\n";
+        let mut state = LessonBoxState::from_markdown(markdown);
+        state.set_direction(Direction::RightToLeft);
+
+        let heading = &state.cached_lines[0].line;
+        assert_eq!(heading.alignment, Some(Alignment::Right));
+        let heading_text: String = heading.spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(heading_text, "םלוע םולש");
+
+        let paragraph = &state.cached_lines[2].line;
+        assert_eq!(paragraph.alignment, Some(Alignment::Right));
+        let paragraph_text: String = paragraph.spans.iter().map(|s| s.content.as_ref()).collect();
+        // the latin word keeps its own order inside the right-to-left run
+        assert!(paragraph_text.contains("paragraph"));
+        assert!(paragraph_text.starts_with('.'));
+    }
+
+    #[test]
+    fn test_rtl_code_blocks_stay_left_to_right() {
+        let markdown = "שלום\n\n```rust\nfn main() {}\n```\n";
+        let mut state = LessonBoxState::from_markdown(markdown);
+        state.set_direction(Direction::RightToLeft);
+
+        let code_lines: Vec<&CachedLine> = state
+            .cached_lines
+            .iter()
+            .filter(|l| {
+                l.line
+                    .spans
+                    .first()
+                    .is_some_and(|s| s.content.starts_with('│'))
+            })
+            .collect();
+        assert_eq!(code_lines.len(), 1);
+        assert_eq!(code_lines[0].line.alignment, None);
+        let code_text: String = code_lines[0]
+            .line
+            .spans
+            .iter()
+            .map(|s| s.content.as_ref())
+            .collect();
+        assert_eq!(code_text, "│ fn main() {}");
+    }
+
+    fn line_text(line: &Line) -> String {
+        line.spans.iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    fn assert_prose_fits(lesson_content: &str, width: u16) {
+        let mut state = LessonBoxState::from_markdown(lesson_content);
+        let hint_count = state
+            .content
+            .iter()
+            .filter(|c| matches!(c, Content::Hint(_)))
+            .count();
+        for i in 0..hint_count {
+            state.toggle_hint(i, width);
+        }
+        state.rebuild_cache(width);
+
+        for cached in &state.cached_lines {
+            let text = line_text(&cached.line);
+            // code blocks and hint titles are not wrapped
+            if text.starts_with(['│', '┌', '└', '▼', '▶']) {
+                continue;
+            }
+            assert!(
+                width::display_width(&text) <= width as usize,
+                "line is wider than {width} columns: {text:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_localized_lessons_wrap_within_width() {
+        assert_prose_fits(
+            include_str!("../../../../examples/example-workshop/hi/py/01-hello-world/lesson.md"),
+            40,
+        );
+        assert_prose_fits(
+            include_str!("../../../../examples/example-workshop/it/py/01-hello-world/lesson.md"),
+            40,
+        );
+    }
+
+    #[test]
+    fn test_wide_characters_wrap_by_display_width() {
+        let paragraph = ParagraphBlock {
+            text: "こんにちは世界、これは日本語の段落です。 नमस्ते दुनिया, यह हिंदी है। 🎉🎉🎉🎉🎉🎉"
+                .to_string(),
+        };
+        let lines = paragraph.render(12, &Theme::default());
+        assert!(lines.len() > 1);
+        for line in &lines {
+            assert!(width::display_width(&line_text(line)) <= 12);
+        }
+
+        let item = ListItem {
+            text: "これは日本語のリスト項目です".to_string(),
+            indent_level: 0,
+        };
+        for line in item.render(16, &Theme::default()) {
+            assert!(width::display_width(&line_text(&line)) <= 16);
+        }
+    }
+
+    #[test]
+    fn test_code_block_with_wide_characters() {
+        let code_block = CodeBlock {
+            language: Some("python".to_string()),
+            code: "\u{3000}print(\"नमस्ते\") # 挨拶 done\n    x = \"日本\" + y".to_string(),
+        };
+        let lines = code_block.render(80, &Theme::default());
+        assert_eq!(lines.len(), 4); // top border + 2 code lines + bottom border
+
+        // the ideographic space is two columns wide, and the text survives highlighting intact
+        assert_eq!(line_text(&lines[1]), "│   print(\"नमस्ते\") # 挨拶 done");
+        assert_eq!(line_text(&lines[2]), "│     x = \"日本\" + y");
+    }
+
+    #[test]
+    fn test_parse_image_placeholder() {
+        let markdown = "# Topology\n\nThe network: ![Two peers](topology.png) as shown.\n";
+        let content = parse_markdown(markdown);
+        assert_eq!(content.len(), 3); // heading, paragraph, image
+
+        if let Content::Paragraph(p) = &content[1] {
+            assert_eq!(p.text, "The network: as shown.");
+        } else {
+            panic!("Expected paragraph");
+        }
+        if let Content::Image(image) = &content[2] {
+            assert_eq!(image.alt, "Two peers");
+            assert_eq!(image.target, "topology.png");
+        } else {
+            panic!("Expected image");
+        }
+
+        let mut state = LessonBoxState::from_markdown(markdown);
+        assert_eq!(state.highlighted_link(), None);
+        while state.highlighted_link().is_none() {
+            state.highlight_down();
+        }
+        assert_eq!(state.highlighted_link(), Some("topology.png"));
+        assert_eq!(
+            line_text(&state.cached_lines[state.highlighted_line].line),
+            "▣ Image: Two peers (topology.png)"
+        );
+    }
+
+    #[test]
+    fn test_parse_image_spacing() {
+        let paragraph = |markdown: &str| match &parse_markdown(markdown)[0] {
+            Content::Paragraph(p) => p.text.clone(),
+            _ => panic!("Expected paragraph"),
+        };
+        assert_eq!(
+            paragraph("See ![a](a.png) and ![b](b.png) here."),
+            "See and here."
+        );
+        assert_eq!(paragraph("See ![a](a.png)\nbelow."), "See below.");
+        assert_eq!(paragraph("![a](a.png) comes first."), "comes first.");
+        // spaces that were not around an image are kept
+        assert_eq!(paragraph("Run `a  b` now."), "Run `a  b` now.");
+    }
+
+    #[test]
+    fn test_parse_image_in_heading() {
+        let markdown = "# Topology ![logo](logo.png)
+
+Two peers.
+";
+        let content = parse_markdown(markdown);
+        assert_eq!(content.len(), 3); // heading, image, paragraph
+        assert!(matches!(&content[0], Content::Heading(h) if h.text == "Topology"));
+        assert!(matches!(&content[1], Content::Image(i) if i.target == "logo.png"));
+        assert!(matches!(&content[2], Content::Paragraph(_)));
+    }
+
+    #[test]
+    fn test_mermaid_graph_renders_as_diagram() {
+        let code_block = CodeBlock {
+            language: Some("mermaid".to_string()),
+            code: "graph TD\n    A[Dialer] --> B[Listener]\n    A --> C[Relay]\n    C --> B\n"
+                .to_string(),
+        };
+        let lines: Vec<String> = code_block
+            .render(80, &Theme::default())
+            .iter()
+            .map(line_text)
+            .collect();
+        let expected = vec![
+            "┌─",
+            "│  ┌────────┐",
+            "│  │ Dialer │",
+            "│  └────┬───┘",
+            "│       │",
+            "│     ┌─┴────┐",
+            "│     ▼      │",
+            "│ ┌───────┐  │",
+            "│ │ Relay │  │",
+            "│ └───┬───┘  │",
+            "│     │      │",
+            "│     └─┬────┘",
+            "│       ▼",
+            "│ ┌──────────┐",
+            "│ │ Listener │",
+            "│ └──────────┘",
+            "└─",
+        ];
+        assert_eq!(lines, expected);
+    }
+
+    #[test]
+    fn test_mermaid_falls_back_to_source() {
+        let code = "graph TD\n    subgraph peers\n    A --> B\n    end";
+        let code_block = CodeBlock {
+            language: Some("mermaid".to_string()),
+            code: code.to_string(),
+        };
+        let lines = code_block.render(80, &Theme::default());
+        assert_eq!(lines.len(), 6); // top border + 4 source lines + bottom border
+        assert_eq!(line_text(&lines[2]), "│     subgraph peers");
+
+        // a diagram that is too wide for the area also shows the source
+        let code_block = CodeBlock {
+            language: Some("mermaid".to_string()),
+            code: "graph LR\n    A[Dialer] --> B[Listener]".to_string(),
+        };
+        assert_eq!(code_block.render(10, &Theme::default()).len(), 4);
+        assert_eq!(code_block.render(80, &Theme::default()).len(), 5);
+    }
+
+    #[test]
+    fn test_highlight_at_row_accounts_for_scroll() {
+        let markdown =
+            "# Title\n\nIntro\n\n## Hint - First\n\nHidden\n\n## Hint - Second\n\nAlso hidden\n";
+        let mut state = LessonBoxState::from_markdown(markdown);
+        state.window_lines = 10;
+
+        let hint_row = state
+            .cached_lines
+            .iter()
+            .position(|l| l.is_hint_title)
+            .expect("hint title");
+        assert!(state.highlight_at(hint_row));
+        assert_eq!(state.is_highlighted_hint(), Some(0));
+
+        // rows below the view or past the content do not move the highlight
+        assert!(!state.highlight_at(10));
+        state.window_lines = 100;
+        assert!(!state.highlight_at(state.total_lines));
+        assert_eq!(state.get_highlighted_line(), hint_row);
+
+        // once scrolled, row 0 is the first line in view
+        state.scroll = Scroll::Offset(1);
+        assert!(state.highlight_at(hint_row - 1));
+        assert_eq!(state.is_highlighted_hint(), Some(0));
+        assert!(state.toggle_highlighted_hint(80));
+    }
+
+    #[test]
+    fn test_toggle_wraps_to_rendered_width() {
+        let markdown =
+            "# Title\n\n## Hint - First\n\nThis hint has more words than fit in twenty columns\n";
+        let mut state = LessonBoxState::from_markdown(markdown);
+        let area = Rect::new(0, 0, 20, 30);
+        let mut buf = Buffer::empty(area);
+        StatefulWidget::render(LessonBox::new(), area, &mut buf, &mut state);
+        assert_eq!(state.width(), 20);
+
+        let hint_row = state
+            .cached_lines
+            .iter()
+            .position(|l| l.is_hint_title)
+            .expect("hint title");
+        state.highlighted_line = hint_row;
+        assert!(state.toggle_highlighted_hint(state.width()));
+        for cached in &state.cached_lines[hint_row + 1..] {
+            assert!(width::display_width(&line_text(&cached.line)) <= 20);
+        }
+    }
 }
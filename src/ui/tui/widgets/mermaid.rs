@@ -0,0 +1,593 @@
+use crate::ui::tui::widgets::width;
+use std::collections::{HashMap, VecDeque};
+use unicode_width::UnicodeWidthChar;
+
+/// rows between two ranks of a top-down graph
+const GAP_ROWS: usize = 3;
+/// columns between two ranks of a left-right graph
+const GAP_COLUMNS: usize = 5;
+/// spacing between nodes in the same rank
+const SPACING: usize = 2;
+
+const UP: u8 = 0b0001;
+const DOWN: u8 = 0b0010;
+const LEFT: u8 = 0b0100;
+const RIGHT: u8 = 0b1000;
+
+/// The direction the graph flows in
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Flow {
+    Down,
+    Right,
+}
+
+/// A link between two nodes
+#[derive(Clone, Debug)]
+struct Edge {
+    from: usize,
+    to: usize,
+    arrow: bool,
+}
+
+/// A parsed mermaid graph
+#[derive(Clone, Debug)]
+struct Graph {
+    flow: Flow,
+    ids: HashMap<String, usize>,
+    labels: Vec<String>,
+    edges: Vec<Edge>,
+}
+
+/// A positioned node or a pass-through point for an edge spanning several ranks
+#[derive(Clone, Debug)]
+struct Slot {
+    node: Option<usize>,
+    rank: usize,
+    x: usize,
+    y: usize,
+    w: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Cell {
+    Empty,
+    Char(char),
+    /// the second column of a wide character
+    Wide,
+    Line(u8),
+}
+
+/// Render a mermaid `graph` or `flowchart` block as box-drawing text no wider than
+/// `max_width` columns. Returns `None` if the source uses syntax that is not supported or the
+/// diagram does not fit, so that the caller can fall back to showing the source.
+pub fn render(source: &str, max_width: usize) -> Option<Vec<String>> {
+    let graph = parse(source)?;
+    let rows = draw(&graph)?;
+    if rows.iter().any(|row| width::display_width(row) > max_width) {
+        return None;
+    }
+    Some(rows)
+}
+
+/// Parse the subset of mermaid flowchart syntax we can lay out
+fn parse(source: &str) -> Option<Graph> {
+    let mut statements = source
+        .lines()
+        .flat_map(|line| line.split(';'))
+        .map(str::trim)
+        .filter(|s| !s.is_empty() && !s.starts_with("%%"));
+
+    let mut header = statements.next()?.split_whitespace();
+    if !matches!(header.next()?, "graph" | "flowchart") {
+        return None;
+    }
+    let flow = match header.next().unwrap_or("TD") {
+        "TD" | "TB" => Flow::Down,
+        "LR" => Flow::Right,
+        _ => return None,
+    };
+    if header.next().is_some() {
+        return None;
+    }
+
+    let mut graph = Graph {
+        flow,
+        ids: HashMap::new(),
+        labels: Vec::new(),
+        edges: Vec::new(),
+    };
+
+    for statement in statements {
+        match statement.split_whitespace().next().unwrap_or_default() {
+            // styling does not change the layout
+            "classDef" | "class" | "style" | "linkStyle" | "click" => continue,
+            "subgraph" | "end" | "direction" => return None,
+            _ => graph.parse_statement(statement)?,
+        }
+    }
+
+    if graph.labels.is_empty() {
+        None
+    } else {
+        Some(graph)
+    }
+}
+
+impl Graph {
+    /// Parse a chain of nodes and links, e.g. `A[Start] --> B --> C`
+    fn parse_statement(&mut self, statement: &str) -> Option<()> {
+        let mut rest = statement;
+        let mut from = self.parse_node(&mut rest)?;
+        loop {
+            rest = rest.trim_start();
+            if rest.is_empty() {
+                return Some(());
+            }
+            let arrow = parse_link(&mut rest)?;
+            let to = self.parse_node(&mut rest)?;
+            if from == to {
+                return None;
+            }
+            self.edges.push(Edge { from, to, arrow });
+            from = to;
+        }
+    }
+
+    /// Parse a node id with an optional shape and label, e.g. `A`, `A[label]` or `A((label))`
+    fn parse_node(&mut self, rest: &mut &str) -> Option<usize> {
+        let s = rest.trim_start();
+        let id_len = s
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(s.len());
+        if id_len == 0 {
+            return None;
+        }
+        let id = &s[..id_len];
+        let mut s = &s[id_len..];
+
+        let open_len = s.find(|c| !"[({>".contains(c)).unwrap_or(s.len());
+        let label = if open_len > 0 {
+            let close: String = s[..open_len]
+                .chars()
+                .rev()
+                .map(|c| match c {
+                    '(' => ')',
+                    '{' => '}',
+                    _ => ']',
+                })
+                .collect();
+            let end = s[open_len..].find(&close)?;
+            let label = s[open_len..open_len + end]
+                .trim()
+                .trim_matches('"')
+                .to_string();
+            s = &s[open_len + end + close.len()..];
+            Some(label)
+        } else {
+            None
+        };
+        *rest = s;
+
+        let index = match self.ids.get(id) {
+            Some(index) => {
+                if let Some(label) = label {
+                    self.labels[*index] = label;
+                }
+                *index
+            }
+            None => {
+                self.labels.push(label.unwrap_or_else(|| id.to_string()));
+                self.ids.insert(id.to_string(), self.labels.len() - 1);
+                self.labels.len() - 1
+            }
+        };
+        Some(index)
+    }
+
+    /// Assign every node to a rank using the longest path from a source node. Returns `None`
+    /// if the graph has a cycle.
+    fn ranks(&self) -> Option<Vec<usize>> {
+        let mut indegree = vec![0; self.labels.len()];
+        for edge in &self.edges {
+            indegree[edge.to] += 1;
+        }
+
+        let mut rank = vec![0; self.labels.len()];
+        let mut queue: VecDeque<usize> = (0..self.labels.len())
+            .filter(|n| indegree[*n] == 0)
+            .collect();
+        let mut visited = 0;
+        while let Some(node) = queue.pop_front() {
+            visited += 1;
+            for edge in self.edges.iter().filter(|e| e.from == node) {
+                rank[edge.to] = rank[edge.to].max(rank[node] + 1);
+                indegree[edge.to] -= 1;
+                if indegree[edge.to] == 0 {
+                    queue.push_back(edge.to);
+                }
+            }
+        }
+
+        if visited == self.labels.len() {
+            Some(rank)
+        } else {
+            None
+        }
+    }
+}
+
+/// Parse a link such as `-->`, `---`, `-.->`, `==>`, `-->|text|` or `-- text -->` and return
+/// whether it ends in an arrow head. Link text is not drawn.
+fn parse_link(rest: &mut &str) -> Option<bool> {
+    let s = rest.trim_start();
+    let len = s.find(|c| !"-=.<>".contains(c)).unwrap_or(s.len());
+    let link = &s[..len];
+    let mut s = &s[len..];
+    if link.len() < 2 || !link.contains(['-', '=']) {
+        return None;
+    }
+
+    // cross and circle ends, e.g. `--x` and `--o`, before they are taken for link text
+    let mut arrow = link.ends_with('>');
+    if s.starts_with(['x', 'o']) && s[1..].starts_with(char::is_whitespace) {
+        arrow = true;
+        s = &s[1..];
+    } else if matches!(link, "--" | "==" | "-.") {
+        // text between the two halves of a link, e.g. `A -- text --> B`
+        let close = ["-->", "---", "==>", "===", ".->"]
+            .iter()
+            .filter_map(|pattern| s.find(pattern))
+            .min()?;
+        let mut closing = &s[close..];
+        let arrow = parse_link(&mut closing)?;
+        *rest = closing;
+        return Some(arrow);
+    }
+
+    // text after the link, e.g. `-->|text|`
+    let trimmed = s.trim_start();
+    if let Some(text) = trimmed.strip_prefix('|') {
+        s = &text[text.find('|')? + 1..];
+    }
+
+    *rest = s;
+    Some(arrow)
+}
+
+/// Lay the graph out in ranks and draw it onto a character canvas
+fn draw(graph: &Graph) -> Option<Vec<String>> {
+    let node_ranks = graph.ranks()?;
+
+    // every node gets a slot; edges that span more than one rank pass through extra slots
+    let mut slots: Vec<Slot> = node_ranks
+        .iter()
+        .enumerate()
+        .map(|(node, rank)| Slot {
+            node: Some(node),
+            rank: *rank,
+            x: 0,
+            y: 0,
+            w: width::display_width(&graph.labels[node]) + 4,
+        })
+        .collect();
+    let mut segments = Vec::new();
+    for edge in &graph.edges {
+        let mut from = edge.from;
+        for rank in node_ranks[edge.from] + 1..node_ranks[edge.to] {
+            slots.push(Slot {
+                node: None,
+                rank,
+                x: 0,
+                y: 0,
+                w: 1,
+            });
+            segments.push((from, slots.len() - 1, false));
+            from = slots.len() - 1;
+        }
+        segments.push((from, edge.to, edge.arrow));
+    }
+
+    let rank_count = node_ranks.iter().max()? + 1;
+    let mut ranks: Vec<Vec<usize>> = vec![Vec::new(); rank_count];
+    for (index, slot) in slots.iter().enumerate() {
+        ranks[slot.rank].push(index);
+    }
+    order_ranks(&mut ranks, &segments);
+
+    let (width, height) = match graph.flow {
+        Flow::Down => place_down(&mut slots, &ranks),
+        Flow::Right => place_right(&mut slots, &ranks),
+    };
+
+    let mut canvas = Canvas::new(width, height);
+    for slot in &slots {
+        match slot.node {
+            Some(node) => canvas.draw_box(slot, &graph.labels[node]),
+            None => canvas.draw_pass_through(slot, graph.flow),
+        }
+    }
+    for (from, to, arrow) in segments {
+        let arrow = arrow && slots[to].node.is_some();
+        match graph.flow {
+            Flow::Down => canvas.connect_down(&slots[from], &slots[to], arrow),
+            Flow::Right => canvas.connect_right(&slots[from], &slots[to], arrow),
+        }
+    }
+
+    Some(canvas.rows())
+}
+
+/// Order the slots in each rank by the average position of their predecessors to reduce
+/// crossing edges
+fn order_ranks(ranks: &mut [Vec<usize>], segments: &[(usize, usize, bool)]) {
+    for r in 1..ranks.len() {
+        let previous: HashMap<usize, usize> = ranks[r - 1]
+            .iter()
+            .enumerate()
+            .map(|(position, slot)| (*slot, position))
+            .collect();
+        let mut keyed: Vec<(f64, usize)> = ranks[r]
+            .iter()
+            .enumerate()
+            .map(|(position, slot)| {
+                let predecessors: Vec<usize> = segments
+                    .iter()
+                    .filter(|(_, to, _)| to == slot)
+                    .filter_map(|(from, _, _)| previous.get(from).copied())
+                    .collect();
+                let key = if predecessors.is_empty() {
+                    position as f64
+                } else {
+                    predecessors.iter().sum::<usize>() as f64 / predecessors.len() as f64
+                };
+                (key, *slot)
+            })
+            .collect();
+        keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+        ranks[r] = keyed.into_iter().map(|(_, slot)| slot).collect();
+    }
+}
+
+/// Place ranks as centered rows from top to bottom, returning the canvas size
+fn place_down(slots: &mut [Slot], ranks: &[Vec<usize>]) -> (usize, usize) {
+    let row_width = |rank: &Vec<usize>| {
+        rank.iter().map(|s| slots[*s].w).sum::<usize>() + SPACING * rank.len().saturating_sub(1)
+    };
+    let width = ranks.iter().map(row_width).max().unwrap_or(0);
+    let offsets: Vec<usize> = ranks.iter().map(|r| (width - row_width(r)) / 2).collect();
+
+    for (r, rank) in ranks.iter().enumerate() {
+        let mut x = offsets[r];
+        for slot in rank {
+            slots[*slot].x = x;
+            slots[*slot].y = r * (3 + GAP_ROWS);
+            x += slots[*slot].w + SPACING;
+        }
+    }
+    (width, ranks.len() * (3 + GAP_ROWS) - GAP_ROWS)
+}
+
+/// Place ranks as vertically centered columns from left to right, returning the canvas size
+fn place_right(slots: &mut [Slot], ranks: &[Vec<usize>]) -> (usize, usize) {
+    let height = ranks.iter().map(|r| r.len() * 4 - 1).max().unwrap_or(0);
+
+    let mut x = 0;
+    for rank in ranks {
+        let column_width = rank
+            .iter()
+            .filter(|s| slots[**s].node.is_some())
+            .map(|s| slots[*s].w)
+            .max()
+            .unwrap_or(1);
+        let offset = (height - (rank.len() * 4 - 1)) / 2;
+        for (position, slot) in rank.iter().enumerate() {
+            slots[*slot].x = x;
+            slots[*slot].y = offset + position * 4;
+            if slots[*slot].node.is_none() {
+                slots[*slot].w = column_width;
+            }
+        }
+        x += column_width + GAP_COLUMNS;
+    }
+    (x - GAP_COLUMNS, height)
+}
+
+/// A grid of cells that box-drawing lines are merged into
+struct Canvas {
+    cells: Vec<Vec<Cell>>,
+    /// combining marks that follow the character in a cell
+    marks: HashMap<(usize, usize), String>,
+}
+
+impl Canvas {
+    fn new(width: usize, height: usize) -> Self {
+        Self {
+            cells: vec![vec![Cell::Empty; width]; height],
+            marks: HashMap::new(),
+        }
+    }
+
+    fn set(&mut self, x: usize, y: usize, cell: Cell) {
+        if let Some(c) = self.cells.get_mut(y).and_then(|row| row.get_mut(x)) {
+            *c = cell;
+        }
+    }
+
+    /// Add line directions to a cell, leaving box borders and arrow heads alone
+    fn line(&mut self, x: usize, y: usize, directions: u8) {
+        if let Some(c) = self.cells.get_mut(y).and_then(|row| row.get_mut(x)) {
+            *c = match *c {
+                Cell::Empty => Cell::Line(directions),
+                Cell::Line(existing) => Cell::Line(existing | directions),
+                other => other,
+            };
+        }
+    }
+
+    fn draw_box(&mut self, slot: &Slot, label: &str) {
+        let (x, y, w) = (slot.x, slot.y, slot.w);
+        self.set(x, y, Cell::Char('┌'));
+        self.set(x + w - 1, y, Cell::Char('┐'));
+        self.set(x, y + 1, Cell::Char('│'));
+        self.set(x + w - 1, y + 1, Cell::Char('│'));
+        self.set(x, y + 2, Cell::Char('└'));
+        self.set(x + w - 1, y + 2, Cell::Char('┘'));
+        for column in x + 1..x + w - 1 {
+            self.set(column, y, Cell::Char('─'));
+            self.set(column, y + 2, Cell::Char('─'));
+        }
+
+        let mut column = x + 2;
+        let mut last = column;
+        for ch in label.chars() {
+            match ch.width().unwrap_or(0) {
+                0 => {
+                    self.marks.entry((last, y + 1)).or_default().push(ch);
+                    continue;
+                }
+                1 => self.set(column, y + 1, Cell::Char(ch)),
+                _ => {
+                    self.set(column, y + 1, Cell::Char(ch));
+                    self.set(column + 1, y + 1, Cell::Wide);
+                }
+            }
+            last = column;
+            column += ch.width().unwrap_or(0);
+        }
+    }
+
+    fn draw_pass_through(&mut self, slot: &Slot, flow: Flow) {
+        match flow {
+            Flow::Down => {
+                for row in slot.y..slot.y + 3 {
+                    self.line(slot.x, row, UP | DOWN);
+                }
+            }
+            Flow::Right => {
+                for column in slot.x..slot.x + slot.w {
+                    self.line(column, slot.y + 1, LEFT | RIGHT);
+                }
+            }
+        }
+    }
+
+    /// Connect the bottom of one slot to the top of a slot in the next rank down
+    fn connect_down(&mut self, from: &Slot, to: &Slot, arrow: bool) {
+        let (fx, tx) = (from.x + from.w / 2, to.x + to.w / 2);
+        let gap = from.y + 3;
+
+        if from.node.is_some() {
+            self.set(fx, from.y + 2, Cell::Char('┬'));
+        }
+        self.line(fx, gap, UP | DOWN);
+        if fx == tx {
+            self.line(fx, gap + 1, UP | DOWN);
+        } else {
+            let (toward_to, toward_from) = if tx > fx {
+                (RIGHT, LEFT)
+            } else {
+                (LEFT, RIGHT)
+            };
+            self.line(fx, gap + 1, UP | toward_to);
+            for column in fx.min(tx) + 1..fx.max(tx) {
+                self.line(column, gap + 1, LEFT | RIGHT);
+            }
+            self.line(tx, gap + 1, toward_from | DOWN);
+        }
+        if arrow {
+            self.set(tx, gap + 2, Cell::Char('▼'));
+        } else {
+            self.line(tx, gap + 2, UP | DOWN);
+        }
+    }
+
+    /// Connect the right side of one slot to the left side of a slot in the next rank over
+    fn connect_right(&mut self, from: &Slot, to: &Slot, arrow: bool) {
+        let (fy, ty) = (from.y + 1, to.y + 1);
+        let route = to.x - 3;
+
+        if from.node.is_some() {
+            self.set(from.x + from.w - 1, fy, Cell::Char('├'));
+        }
+        for column in from.x + from.w..route {
+            self.line(column, fy, LEFT | RIGHT);
+        }
+        if fy == ty {
+            self.line(route, fy, LEFT | RIGHT);
+        } else {
+            let (toward_to, toward_from) = if ty > fy { (DOWN, UP) } else { (UP, DOWN) };
+            self.line(route, fy, LEFT | toward_to);
+            for row in fy.min(ty) + 1..fy.max(ty) {
+                self.line(route, row, UP | DOWN);
+            }
+            self.line(route, ty, toward_from | RIGHT);
+        }
+        self.line(route + 1, ty, LEFT | RIGHT);
+        if arrow {
+            self.set(route + 2, ty, Cell::Char('▶'));
+        } else {
+            self.line(route + 2, ty, LEFT | RIGHT);
+        }
+    }
+
+    /// Convert the canvas to text with trailing whitespace removed
+    fn rows(&self) -> Vec<String> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(y, row)| {
+                let mut text = String::new();
+                for (x, cell) in row.iter().enumerate() {
+                    match cell {
+                        Cell::Empty => text.push(' '),
+                        Cell::Char(c) => text.push(*c),
+                        Cell::Wide => {}
+                        Cell::Line(directions) => text.push(line_char(*directions)),
+                    }
+                    if let Some(marks) = self.marks.get(&(x, y)) {
+                        text.push_str(marks);
+                    }
+                }
+                text.trim_end().to_string()
+            })
+            .collect()
+    }
+}
+
+/// Get the box-drawing character that joins the given directions
+fn line_char(directions: u8) -> char {
+    match directions {
+        d if d == UP | DOWN || d == UP || d == DOWN => '│',
+        d if d == LEFT | RIGHT || d == LEFT || d == RIGHT => '─',
+        d if d == DOWN | RIGHT => '┌',
+        d if d == DOWN | LEFT => '┐',
+        d if d == UP | RIGHT => '└',
+        d if d == UP | LEFT => '┘',
+        d if d == UP | DOWN | RIGHT => '├',
+        d if d == UP | DOWN | LEFT => '┤',
+        d if d == LEFT | RIGHT | DOWN => '┬',
+        d if d == LEFT | RIGHT | UP => '┴',
+        _ => '┼',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_link_ends() {
+        for (source, arrow) in [
+            ("A --x B", true),
+            ("A --o B", true),
+            ("A --- B", false),
+            ("A -- text --> B", true),
+            ("A -- over --- B", false),
+        ] {
+            let graph = parse(&format!("graph TD\n    {source}\n")).unwrap();
+            assert_eq!(graph.labels, ["A", "B"], "{source}");
+            assert_eq!(graph.edges.len(), 1, "{source}");
+            assert_eq!(graph.edges[0].arrow, arrow, "{source}");
+        }
+    }
+}
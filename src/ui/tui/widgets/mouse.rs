@@ -0,0 +1,63 @@
+use crossterm::event::MouseEvent;
+use ratatui::{
+    layout::{Position, Rect},
+    widgets::ListState,
+};
+
+/// Check if the mouse event happened inside of the area
+pub fn contains(area: Rect, mouse: &MouseEvent) -> bool {
+    area.contains(Position::new(mouse.column, mouse.row))
+}
+
+/// Get the row within the area that the mouse event happened on, or None if it happened outside
+pub fn row(area: Rect, mouse: &MouseEvent) -> Option<usize> {
+    if contains(area, mouse) {
+        Some((mouse.row - area.y) as usize)
+    } else {
+        None
+    }
+}
+
+/// Get the index of the list item under the mouse. The area is the inner area of the list, inside
+/// of any borders and padding, and each item is assumed to be a single line.
+pub fn list_index(area: Rect, state: &ListState, len: usize, mouse: &MouseEvent) -> Option<usize> {
+    row(area, mouse)
+        .map(|row| state.offset() + row)
+        .filter(|index| *index < len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyModifiers, MouseButton, MouseEventKind};
+
+    fn click(column: u16, row: u16) -> MouseEvent {
+        MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    #[test]
+    fn test_row_inside_and_outside() {
+        let area = Rect::new(2, 3, 10, 4);
+        assert_eq!(row(area, &click(2, 3)), Some(0));
+        assert_eq!(row(area, &click(11, 6)), Some(3));
+        assert_eq!(row(area, &click(1, 3)), None);
+        assert_eq!(row(area, &click(5, 7)), None);
+    }
+
+    #[test]
+    fn test_list_index_uses_offset() {
+        let area = Rect::new(0, 2, 20, 5);
+        let mut state = ListState::default().with_offset(4);
+        assert_eq!(list_index(area, &state, 10, &click(3, 2)), Some(4));
+        assert_eq!(list_index(area, &state, 10, &click(3, 6)), Some(8));
+        // past the end of the list
+        assert_eq!(list_index(area, &state, 6, &click(3, 6)), None);
+        *state.offset_mut() = 0;
+        assert_eq!(list_index(area, &state, 10, &click(3, 3)), Some(1));
+    }
+}
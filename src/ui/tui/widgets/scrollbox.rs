@@ -1,4 +1,4 @@
-use crate::ui::tui::widgets::ScrollText;
+use crate::{languages::spoken::Direction, ui::tui::widgets::ScrollText};
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
@@ -27,6 +27,10 @@ impl<'a> ScrollBox<'a> {
         self.st.block(block);
     }
 
+    pub fn direction(&mut self, direction: Direction) {
+        self.st.direction(direction);
+    }
+
     /// Scroll to the top
     pub fn scroll_top(&mut self) {
         self.st.scroll_top();
@@ -1,3 +1,4 @@
+use crate::ui::tui::widgets::width;
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Rect},
@@ -8,7 +9,6 @@ use ratatui::{
     },
 };
 use std::{collections::VecDeque, fmt};
-use textwrap;
 
 #[derive(Clone, Debug, Default)]
 pub enum Scroll {
@@ -132,29 +132,30 @@ impl StatefulWidget for &mut ScrollLog<'_> {
             area
         };
 
-        // left column is 3 characters wide, right column takes the rest
+        // left column is 3 columns wide, right column takes the rest
         let left_column_width = 3;
         let right_column_width = inner_area.width.saturating_sub(left_column_width) as usize;
+        let blank_column = width::pad_right("", left_column_width as usize);
 
         // collect all log entries and wrap the messages
         let mut all_lines = Vec::new();
 
         for (emoji, message) in log_messages.iter() {
-            let wrap_options = textwrap::Options::new(right_column_width).break_words(true);
-            let wrapped_lines = textwrap::wrap(message, &wrap_options);
+            let wrapped_lines = width::wrap(message, right_column_width);
 
-            // first line includes the emoji
+            // first line includes the emoji, padded by display width so wide emoji line up
             if let Some(first_line) = wrapped_lines.first() {
                 if let Some(emoji_str) = emoji {
-                    all_lines.push(format!("{emoji_str:<2}{first_line}"));
+                    let emoji_column = width::pad_right(emoji_str, left_column_width as usize);
+                    all_lines.push(format!("{emoji_column}{first_line}"));
                 } else {
-                    all_lines.push(format!("{:<3}{}", "", first_line));
+                    all_lines.push(format!("{blank_column}{first_line}"));
                 }
             }
 
             // subsequent lines have blank emoji column
             for line in wrapped_lines.iter().skip(1) {
-                all_lines.push(format!("{:<3}{}", "   ", line));
+                all_lines.push(format!("{blank_column}{line}"));
             }
         }
 
@@ -1,6 +1,10 @@
+use crate::{
+    languages::spoken::Direction,
+    ui::tui::widgets::{bidi, width},
+};
 use ratatui::{
     buffer::Buffer,
-    layout::{Alignment, Rect},
+    layout::Rect,
     style::Style,
     text::Line,
     widgets::{
@@ -8,7 +12,6 @@ use ratatui::{
     },
 };
 use std::fmt;
-use textwrap;
 
 #[derive(Clone, Debug, Default)]
 pub enum Scroll {
@@ -45,6 +48,8 @@ pub struct ScrollText<'a> {
     block: Option<Block<'a>>,
     /// The style of the text
     style: Style,
+    /// The reading direction of the text
+    direction: Direction,
 }
 
 impl<'a> ScrollText<'a> {
@@ -58,6 +63,11 @@ impl<'a> ScrollText<'a> {
         self.style = style;
     }
 
+    /// set the reading direction
+    pub fn direction(&mut self, direction: Direction) {
+        self.direction = direction;
+    }
+
     /// get the current scroll position
     pub fn get_scroll(&self) -> &Scroll {
         &self.scroll
@@ -122,9 +132,7 @@ impl StatefulWidget for &mut ScrollText<'_> {
         };
 
         // wrap the text
-        let wrap_options = textwrap::Options::new(width as usize).break_words(true);
-
-        let wrapped_lines = textwrap::wrap(state, &wrap_options)
+        let wrapped_lines = width::wrap(state, width as usize)
             .iter()
             .map(|line| line.to_string())
             .collect::<Vec<_>>();
@@ -166,13 +174,13 @@ impl StatefulWidget for &mut ScrollText<'_> {
             .iter()
             .skip(start_line)
             .take(end_line - start_line)
-            .map(|line| Line::from(line.clone()))
+            .map(|line| Line::from(bidi::reorder(line, self.direction)))
             .collect();
 
         let mut scrollbar_area = area;
 
         let mut paragraph = Paragraph::new(items)
-            .alignment(Alignment::Left)
+            .alignment(bidi::alignment(self.direction))
             .style(self.style);
 
         if let Some(block) = &self.block {
@@ -1,7 +1,8 @@
+use crate::ui::tui::{widgets::width, Theme};
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Rect},
-    style::{Color, Style},
+    style::Style,
     text::Line,
     widgets::{Block, Gauge, Paragraph, Widget},
 };
@@ -28,6 +29,8 @@ pub struct StatusBar<'a> {
     start_time: Option<Instant>,
     /// the block to render with
     block: Block<'a>,
+    /// the colors to render with
+    theme: Theme,
 }
 
 impl<'a> StatusBar<'a> {
@@ -65,6 +68,11 @@ impl<'a> StatusBar<'a> {
         self.block = block;
     }
 
+    /// Set the colors to render with
+    pub fn set_theme(&mut self, theme: &Theme) {
+        self.theme = *theme;
+    }
+
     /// Update message (for Messages mode)
     pub fn update_message(&mut self, message: String) {
         if self.mode == StatusMode::Messages {
@@ -118,9 +126,10 @@ impl Widget for &mut StatusBar<'_> {
                 // Render throbber + message
                 let throbber = self.get_throbber_char();
                 let content = format!("{} {}", throbber, self.message);
+                let content = width::truncate(&content, self.block.inner(area).width as usize);
                 let paragraph = Paragraph::new(Line::from(content))
                     .block(self.block.clone())
-                    .style(Style::default().fg(Color::Yellow))
+                    .style(Style::default().fg(self.theme.busy))
                     .alignment(Alignment::Left);
                 Widget::render(paragraph, area, buf);
             }
@@ -128,10 +137,11 @@ impl Widget for &mut StatusBar<'_> {
                 // Render progress bar with throbber and message
                 let throbber = self.get_throbber_char();
                 let label = format!("{} {}", throbber, self.message);
+                let label = width::truncate(&label, self.block.inner(area).width as usize);
 
                 let gauge = Gauge::default()
                     .block(self.block.clone())
-                    .gauge_style(Style::default().fg(Color::Green))
+                    .gauge_style(Style::default().fg(self.theme.progress))
                     .percent(self.progress as u16)
                     .label(label);
 
@@ -0,0 +1,44 @@
+use std::borrow::Cow;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Get the number of terminal columns the text occupies. Wide characters (CJK, most emoji)
+/// count as two columns and combining marks count as zero.
+pub fn display_width(text: &str) -> usize {
+    text.width()
+}
+
+/// Wrap text to the given display width, breaking words that are wider than a whole line
+pub fn wrap(text: &str, width: usize) -> Vec<Cow<'_, str>> {
+    let options = textwrap::Options::new(width.max(1)).break_words(true);
+    textwrap::wrap(text, &options)
+}
+
+/// Pad text with trailing spaces until it occupies the given display width
+pub fn pad_right(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(display_width(text));
+    format!("{text}{}", " ".repeat(padding))
+}
+
+/// Truncate text so that it fits in the given display width, marking the cut with an ellipsis
+pub fn truncate(text: &str, width: usize) -> Cow<'_, str> {
+    if display_width(text) <= width {
+        return Cow::Borrowed(text);
+    }
+    if width == 0 {
+        return Cow::Borrowed("");
+    }
+
+    // leave room for the ellipsis
+    let mut truncated = String::new();
+    let mut used = 0;
+    for ch in text.chars() {
+        let ch_width = ch.width().unwrap_or(0);
+        if used + ch_width > width - 1 {
+            break;
+        }
+        truncated.push(ch);
+        used += ch_width;
+    }
+    truncated.push('…');
+    Cow::Owned(truncated)
+}